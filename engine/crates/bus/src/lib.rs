@@ -1,21 +1,49 @@
 //! D‑Bus surface & orchestration glue (stubs for M0).
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
 use anyhow::Result;
+use lancea_config::EngineConfig;
 use lancea_model::{Envelope, Outcome, Provider, ResolvedCommand, ResultItem, ResultsBatch};
 use lancea_provider_apps::AppsProvider;
+use lancea_provider_bookmarks::BookmarksProvider;
+use lancea_provider_calc::CalcProvider;
+use lancea_provider_char::CharProvider;
+use lancea_provider_clip::ClipProvider;
+use lancea_provider_color::ColorProvider;
+use lancea_provider_convert::ConvertProvider;
+use lancea_provider_date::DateProvider;
 use lancea_provider_emoji::EmojiProvider;
+use lancea_provider_files::FilesProvider;
+use lancea_provider_pass::PassProvider;
+use lancea_provider_proc::ProcProvider;
+use lancea_provider_recent::RecentProvider;
+use lancea_provider_repo::RepoProvider;
+use lancea_provider_run::RunProvider;
+use lancea_provider_session::SessionProvider;
+use lancea_provider_snip::SnipProvider;
+use lancea_provider_ssh::SshProvider;
+use lancea_provider_systemd::SystemdProvider;
+use lancea_provider_tmux::TmuxProvider;
+use lancea_provider_web::WebProvider;
+use lancea_provider_windows::WindowsProvider;
 use lancea_registry::CommandRegistry;
 use serde_json::json;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 use zbus::object_server::SignalEmitter;
 use zbus::{connection, interface};
 
 pub struct EngineBus {
     registry: CommandRegistry,
     providers: HashMap<String, Box<dyn Provider>>,
+    /// Also fed directly by `RecordClipboard`.
+    clip: Arc<ClipProvider>,
+    /// Also asked for a fallback item in global searches.
+    web: Arc<WebProvider>,
+    /// Also asked which clipboard text is a password it copied.
+    pass: Arc<PassProvider>,
     epoch: AtomicU64,
 }
 
@@ -23,15 +51,67 @@ impl EngineBus {
     pub fn new() -> Self {
         let mut providers: HashMap<String, Box<dyn Provider>> = HashMap::new();
 
+        let config = EngineConfig::load().unwrap_or_else(|e| {
+            warn!("ignoring engine config: {e:#}");
+            EngineConfig::default()
+        });
+
         let emoji = EmojiProvider::new().expect("Failed to initialize EmojiProvider");
-        let apps = AppsProvider::new().expect("Apps scan");
+        let apps = Arc::new(
+            AppsProvider::new()
+                .expect("Apps scan")
+                .with_config(config_section(&config, "apps")),
+        );
+        let files = FilesProvider::new(config_section(&config, "files"), apps.clone());
+        let recent = RecentProvider::new(config_section(&config, "recent"), apps.clone());
+        let clip = Arc::new(ClipProvider::new(config_section(&config, "clip")));
+        let snip = SnipProvider::new(config_section(&config, "snip"));
+        let ssh = SshProvider::new(config_section(&config, "ssh"), apps.clone());
+        let bookmarks = BookmarksProvider::new(config_section(&config, "bookmarks"), apps.clone());
+        let web = Arc::new(WebProvider::new(config_section(&config, "web")));
+        let run = RunProvider::new(config_section(&config, "run"), apps.clone());
+        let proc = ProcProvider::new(config_section(&config, "proc"));
+        let systemd = SystemdProvider::new(config_section(&config, "systemd"), apps.clone());
+        let session = SessionProvider::new(config_section(&config, "session"));
+        let windows = WindowsProvider::new(config_section(&config, "windows"), apps.clone());
+        let pass = Arc::new(PassProvider::new(config_section(&config, "pass")));
+        let char = CharProvider::new(config_section(&config, "char"));
+        let repo = RepoProvider::new(config_section(&config, "repo"), apps.clone());
+        let tmux = TmuxProvider::new(config_section(&config, "tmux"), apps.clone());
+        let date = DateProvider::new(config_section(&config, "date"));
+        let color = ColorProvider::new();
+        let calc = CalcProvider::new();
+        let convert = ConvertProvider::new().with_config(config_section(&config, "convert"));
 
         providers.insert(emoji.id().to_string(), Box::new(emoji));
         providers.insert(apps.id().to_string(), Box::new(apps));
+        providers.insert(calc.id().to_string(), Box::new(calc));
+        providers.insert(convert.id().to_string(), Box::new(convert));
+        providers.insert(files.id().to_string(), Box::new(files));
+        providers.insert(recent.id().to_string(), Box::new(recent));
+        providers.insert(clip.id().to_string(), Box::new(clip.clone()));
+        providers.insert(snip.id().to_string(), Box::new(snip));
+        providers.insert(ssh.id().to_string(), Box::new(ssh));
+        providers.insert(bookmarks.id().to_string(), Box::new(bookmarks));
+        providers.insert(web.id().to_string(), Box::new(web.clone()));
+        providers.insert(run.id().to_string(), Box::new(run));
+        providers.insert(proc.id().to_string(), Box::new(proc));
+        providers.insert(systemd.id().to_string(), Box::new(systemd));
+        providers.insert(session.id().to_string(), Box::new(session));
+        providers.insert(windows.id().to_string(), Box::new(windows));
+        providers.insert(pass.id().to_string(), Box::new(pass.clone()));
+        providers.insert(char.id().to_string(), Box::new(char));
+        providers.insert(repo.id().to_string(), Box::new(repo));
+        providers.insert(tmux.id().to_string(), Box::new(tmux));
+        providers.insert(date.id().to_string(), Box::new(date));
+        providers.insert(color.id().to_string(), Box::new(color));
 
         Self {
-            registry: CommandRegistry::new(),
+            registry: CommandRegistry::new().with_command("web", web.id(), web.commands()),
             providers,
+            clip,
+            web,
+            pass,
             epoch: AtomicU64::new(0),
         }
     }
@@ -53,6 +133,17 @@ impl EngineBus {
     }
 }
 
+/// A config table, or its defaults (with a warning) when it doesn't parse.
+fn config_section<T>(config: &EngineConfig, name: &str) -> T
+where
+    T: serde::de::DeserializeOwned + Default,
+{
+    config.section(name).unwrap_or_else(|e| {
+        warn!("{e:#}; using defaults");
+        T::default()
+    })
+}
+
 #[interface(name = "org.lancea.Engine1")]
 impl EngineBus {
     fn resolve_command(&self, text_json: &str) -> String {
@@ -124,10 +215,14 @@ impl EngineBus {
                 "[EngineBus#search] - Unknown provider '{}', falling back to apps",
                 provider_id
             );
-            self.providers
+            let mut items = self
+                .providers
                 .get("apps")
                 .map(|p| p.search(&text))
-                .unwrap_or_default()
+                .unwrap_or_default();
+            let best = items.iter().map(|i| i.score).reduce(f32::max);
+            items.extend(self.web.fallback(&text, best));
+            items
         };
 
         dbg!(
@@ -213,7 +308,7 @@ impl EngineBus {
     /// Execute(args_json) -> envelope(outcome)
     ///
     /// args_json envelope data:
-    /// { "providerId":"emoji", "actionId":"copy_glyph", "key":"emoji:joy" }
+    /// { "providerId":"emoji", "actionId":"copy_glyph", "key":"emoji:joy", "args": [<optional strings>] }
     fn execute(&self, args_json: &str) -> String {
         dbg!(
             "[EngineBus#execute] - Called execute with args: {}",
@@ -230,39 +325,42 @@ impl EngineBus {
             .and_then(|v| v.as_str())
             .unwrap_or("");
         let key = args.data.get("key").and_then(|v| v.as_str()).unwrap_or("");
+        let action_args: Vec<String> = args
+            .data
+            .get("args")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
 
         // Determine provider from key prefix (e.g., "emoji:joy" -> "emoji")
         let provider_id = key.split(':').next().unwrap_or("");
 
-        let ok = if let Some(provider) = self.providers.get(provider_id) {
+        let outcome = if let Some(provider) = self.providers.get(provider_id) {
             dbg!(
                 "[EngineBus#execute] - Executing action '{}' on key '{}' with provider '{}'",
                 action,
                 key,
                 provider_id
             );
-            provider.execute(action, key)
+            provider.execute_outcome(action, key, &action_args)
         } else {
             dbg!(
                 "[EngineBus#execute] - Unknown provider '{}' for key '{}'",
                 provider_id,
                 key
             );
-            false
-        };
-
-        let outcome = if ok {
-            Outcome {
-                status: "ok".into(),
-                message: Some(format!("Action '{}' executed successfully", action)),
-            }
-        } else {
             Outcome {
                 status: "error".into(),
                 message: Some(format!(
                     "Failed to execute action '{}' or unknown provider/key",
                     action
                 )),
+                exit_code: None,
             }
         };
 
@@ -270,6 +368,40 @@ impl EngineBus {
         return serde_json::to_string(&Envelope::wrap(outcome)).unwrap();
     }
 
+    /// RecordClipboard(args_json) -> recorded
+    ///
+    /// args_json envelope data:
+    /// { "text": "copied text", "mimeTypes": ["text/plain", ...] }
+    fn record_clipboard(&self, args_json: &str) -> bool {
+        let args: Envelope<serde_json::Value> =
+            serde_json::from_str(args_json).unwrap_or_else(|_| Envelope {
+                v: "1.0".into(),
+                data: json!({}),
+            });
+        let text = args.data.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        let mime_types: Vec<String> = args
+            .data
+            .get("mimeTypes")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Passwords copied by /pass are never kept.
+        if self.pass.is_copied_password(text) {
+            return false;
+        }
+        // The text itself stays out of the logs; it may be sensitive.
+        self.clip.record(text, &mime_types).unwrap_or_else(|e| {
+            warn!("clipboard history: {e:#}");
+            false
+        })
+    }
+
     #[zbus(signal)]
     async fn results_updated(
         #[zbus(signal_emitter)] emitter: &SignalEmitter<'_>,
@@ -0,0 +1,93 @@
+//! Engine configuration, read from `$XDG_CONFIG_HOME/lancea/engine.toml`.
+//!
+//! The file is a set of tables, one per provider or subsystem. Each owner
+//! defines its own `Deserialize + Default` type and pulls its table out with
+//! [`EngineConfig::section`], so a missing file or table means defaults.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Clone, Default)]
+pub struct EngineConfig {
+    table: toml::Table,
+}
+
+impl EngineConfig {
+    /// Loads the user's config file, or defaults when there is none.
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::from_path(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let txt = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        Self::parse(&txt).with_context(|| format!("parse {}", path.display()))
+    }
+
+    pub fn parse(txt: &str) -> Result<Self> {
+        Ok(Self {
+            table: txt.parse()?,
+        })
+    }
+
+    /// `$XDG_CONFIG_HOME/lancea/engine.toml`.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("lancea").join("engine.toml"))
+    }
+
+    /// Deserializes the `[name]` table, falling back to `T::default()` when
+    /// it is absent.
+    pub fn section<T>(&self, name: &str) -> Result<T>
+    where
+        T: DeserializeOwned + Default,
+    {
+        match self.table.get(name) {
+            Some(value) => value
+                .clone()
+                .try_into()
+                .with_context(|| format!("invalid [{name}] config")),
+            None => Ok(T::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(default)]
+    struct Sample {
+        name: String,
+        limit: u32,
+    }
+
+    #[test]
+    fn sections_deserialize_with_defaults() {
+        let config = EngineConfig::parse("[sample]\nlimit = 3\n").unwrap();
+
+        let sample: Sample = config.section("sample").unwrap();
+        assert_eq!(
+            sample,
+            Sample {
+                name: String::new(),
+                limit: 3
+            }
+        );
+
+        let missing: Sample = config.section("other").unwrap();
+        assert_eq!(missing, Sample::default());
+    }
+
+    #[test]
+    fn bad_sections_are_errors() {
+        let config = EngineConfig::parse("[sample]\nlimit = \"lots\"\n").unwrap();
+        assert!(config.section::<Sample>("sample").is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! The system clipboard, for providers that write it from the engine
+//! rather than through the UI: `wl-clipboard` on Wayland, `xclip` on X11.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Whether the session is Wayland rather than X11.
+pub fn wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// Current clipboard text, if any.
+pub fn get() -> Option<String> {
+    let argv: &[&str] = if wayland() {
+        &["wl-paste", "--no-newline"]
+    } else {
+        &["xclip", "-selection", "clipboard", "-o"]
+    };
+    let out = Command::new(argv[0])
+        .args(&argv[1..])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+pub fn set(text: &str) -> io::Result<()> {
+    let argv: &[&str] = if wayland() {
+        &["wl-copy"]
+    } else {
+        &["xclip", "-selection", "clipboard"]
+    };
+    pipe(argv, text)
+}
+
+pub fn clear() -> io::Result<()> {
+    if !wayland() {
+        return set("");
+    }
+    let status = Command::new("wl-copy")
+        .arg("--clear")
+        .status()
+        .map_err(|e| spawn_error("wl-copy", e))?;
+    if !status.success() {
+        return Err(io::Error::other(format!("wl-copy exited with {status}")));
+    }
+    Ok(())
+}
+
+/// Runs `argv` with `input` on stdin and waits for it.
+pub fn pipe(argv: &[&str], input: &str) -> io::Result<()> {
+    let Some((program, args)) = argv.split_first() else {
+        return Err(io::Error::other("empty command line"));
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| spawn_error(program, e))?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| io::Error::other("no stdin"))?
+        .write_all(input.as_bytes())?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("{program} exited with {status}")));
+    }
+    Ok(())
+}
+
+fn spawn_error(program: &str, e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("failed to spawn {program}: {e}"))
+}
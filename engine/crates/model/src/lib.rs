@@ -1,7 +1,16 @@
 //! Core data model for Lancea M0.
 
+pub mod clipboard;
+mod state;
+mod util;
+
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
+pub use state::{Stored, load_state, save_state, unix_now};
+pub use util::{command_query, expand_home, off_runtime, strip_command, tildify};
+
 pub const API_VERSION: &str = "1.0";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -40,6 +49,57 @@ pub struct ResultItem {
     pub score: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extras: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matches: Option<Matches>,
+}
+
+/// Which parts of a result's title (and subtitle) matched the query.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct Matches {
+    pub title: Vec<MatchRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<Vec<MatchRange>>,
+}
+
+/// Half-open `[start, end)` span in UTF-16 code units, which is what QML
+/// strings index by.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct MatchRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl MatchRange {
+    /// Turns char indices into `text` into sorted, merged UTF-16 ranges.
+    /// Out-of-range and duplicate indices are ignored.
+    pub fn from_char_indices<I>(text: &str, indices: I) -> Vec<MatchRange>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let mut hit: Vec<bool> = vec![false; text.chars().count()];
+        for i in indices {
+            if let Some(h) = hit.get_mut(i) {
+                *h = true;
+            }
+        }
+
+        let mut out: Vec<MatchRange> = Vec::new();
+        let mut offset = 0u32;
+        for (ch, hit) in text.chars().zip(hit) {
+            let width = ch.len_utf16() as u32;
+            if hit {
+                match out.last_mut() {
+                    Some(last) if last.end == offset => last.end += width,
+                    _ => out.push(MatchRange {
+                        start: offset,
+                        end: offset + width,
+                    }),
+                }
+            }
+            offset += width;
+        }
+        out
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -64,11 +124,133 @@ pub struct Outcome {
     pub status: String, // "ok" | "error"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// For actions that ran a command to completion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
 }
 
 pub trait Provider: Send + Sync {
     fn id(&self) -> &str;
     fn search(&self, query: &str) -> Vec<ResultItem>;
     fn preview(&self, key: &str) -> Option<Preview>;
+
+    /// Runs `action` on the result `key`. Copy actions are the exception:
+    /// the UI itself copies the result's `extras.copyText`, or the action's
+    /// entry of `extras.copyTexts` where a result copies several things,
+    /// and only then calls this. Providers check the key still stands,
+    /// and may note the use, but never touch the clipboard for them.
     fn execute(&self, action: &str, key: &str) -> bool;
+
+    /// `execute` with caller-supplied arguments, such as files to open.
+    /// Providers that take none refuse any.
+    fn execute_with_args(&self, action: &str, key: &str, args: &[String]) -> bool {
+        args.is_empty() && self.execute(action, key)
+    }
+
+    /// `execute_with_args` as the [`Outcome`] sent back to the UI.
+    /// Providers with more to report, such as an exit code, override it.
+    fn execute_outcome(&self, action: &str, key: &str, args: &[String]) -> Outcome {
+        if self.execute_with_args(action, key, args) {
+            Outcome {
+                status: "ok".into(),
+                message: Some(format!("Action '{}' executed successfully", action)),
+                exit_code: None,
+            }
+        } else {
+            Outcome {
+                status: "error".into(),
+                message: Some(format!(
+                    "Failed to execute action '{}' or unknown provider/key",
+                    action
+                )),
+                exit_code: None,
+            }
+        }
+    }
+}
+
+/// Lets one provider instance be registered on the bus and also used by
+/// other providers, e.g. apps opening files for the files provider.
+impl<T: Provider + ?Sized> Provider for Arc<T> {
+    fn id(&self) -> &str {
+        (**self).id()
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        (**self).search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        (**self).preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        (**self).execute(action, key)
+    }
+
+    fn execute_with_args(&self, action: &str, key: &str, args: &[String]) -> bool {
+        (**self).execute_with_args(action, key, args)
+    }
+
+    fn execute_outcome(&self, action: &str, key: &str, args: &[String]) -> Outcome {
+        (**self).execute_outcome(action, key, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_ranges_merge_adjacent_chars() {
+        let ranges = MatchRange::from_char_indices("Firefox", [0, 1, 2, 4, 2, 99]);
+        assert_eq!(
+            ranges,
+            vec![
+                MatchRange { start: 0, end: 3 },
+                MatchRange { start: 4, end: 5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn match_ranges_count_utf16_units() {
+        // 😂 is a surrogate pair, so "joy" starts at unit 3.
+        let ranges = MatchRange::from_char_indices("😂 joy", [2, 3, 4]);
+        assert_eq!(ranges, vec![MatchRange { start: 3, end: 6 }]);
+    }
+
+    struct Flaky;
+
+    impl Provider for Flaky {
+        fn id(&self) -> &str {
+            "flaky"
+        }
+
+        fn search(&self, _query: &str) -> Vec<ResultItem> {
+            Vec::new()
+        }
+
+        fn preview(&self, _key: &str) -> Option<Preview> {
+            None
+        }
+
+        fn execute(&self, action: &str, _key: &str) -> bool {
+            action == "works"
+        }
+    }
+
+    #[test]
+    fn outcomes_default_to_execute_result() {
+        let ok = Flaky.execute_outcome("works", "flaky:1", &[]);
+        assert_eq!(ok.status, "ok");
+        assert_eq!(ok.exit_code, None);
+
+        let json = serde_json::to_value(Flaky.execute_outcome("fails", "flaky:1", &[])).unwrap();
+        assert_eq!(json["status"], "error");
+        assert!(json.get("exit_code").is_none());
+        // Args are refused unless the provider takes them.
+        let refused = Arc::new(Flaky).execute_outcome("works", "flaky:1", &["x".into()]);
+        assert_eq!(refused.status, "error");
+    }
 }
@@ -0,0 +1,90 @@
+//! JSON files providers keep their state in between runs: histories, and
+//! caches that answer before a rescan finishes.
+
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// A history as it is stored: `{"entries": [...]}`, newest first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Stored<T> {
+    pub entries: Vec<T>,
+}
+
+impl<T> Default for Stored<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// The state kept at `path`, or the default when there's none yet.
+pub fn load_state<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes `state` to a temporary file and renames it over `path`, so a
+/// crash never leaves it truncated. The file is the owner's alone: state
+/// can hold copied passwords and the commands someone ran.
+pub fn save_state<T: Serialize>(path: &Path, state: &T) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_vec(state)?;
+    let tmp = path.with_extension("json.tmp");
+    // A leftover from a crash may have been created with another mode.
+    let _ = fs::remove_file(&tmp);
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&tmp)?
+        .write_all(&json)?;
+    fs::rename(&tmp, path)
+}
+
+/// Now, as the Unix seconds state files record times in.
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[test]
+    fn state_round_trips_privately() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state/history.json");
+        let empty: Stored<String> = load_state(&path).unwrap();
+        assert!(empty.entries.is_empty());
+
+        let stored = Stored {
+            entries: vec!["b".to_string(), "a".to_string()],
+        };
+        save_state(&path, &stored).unwrap();
+        let loaded: Stored<String> = load_state(&path).unwrap();
+        assert_eq!(loaded.entries, ["b", "a"]);
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(load_state::<Stored<String>>(&path).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Small helpers providers share for reading queries, showing paths and
+//! blocking calls.
+
+use std::path::{Path, PathBuf};
+use std::thread;
+
+/// The rest of `query` after whichever of `prefixes` it starts with,
+/// ignoring case, trimmed; `None` when it starts with none of them.
+/// List longer prefixes first, so `/files` isn't read as `/f`.
+pub fn strip_command<'a>(query: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    let q = query.trim_start();
+    prefixes.iter().find_map(|prefix| {
+        q.get(..prefix.len())
+            .filter(|head| head.eq_ignore_ascii_case(prefix))
+            .map(|_| q[prefix.len()..].trim())
+    })
+}
+
+/// What a provider searches for: `query` without its slash command, or
+/// all of it when it was typed without one.
+pub fn command_query<'a>(query: &'a str, prefixes: &[&str]) -> &'a str {
+    strip_command(query, prefixes).unwrap_or_else(|| query.trim())
+}
+
+/// `~/…` from a config file as a path under the home directory.
+pub fn expand_home(path: PathBuf) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path,
+    }
+}
+
+/// `~/…` for paths under the home directory, `~` for the directory itself.
+pub fn tildify(path: &Path) -> String {
+    match dirs::home_dir().and_then(|h| path.strip_prefix(h).ok().map(Path::to_path_buf)) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".into(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Runs blocking calls, such as zbus's blocking API, on a thread of their
+/// own: the engine calls providers from its async runtime, where they
+/// can't block.
+pub fn off_runtime<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    thread::scope(|s| match s.spawn(f).join() {
+        Ok(value) => value,
+        Err(panic) => std::panic::resume_unwind(panic),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_strip_case_insensitively() {
+        assert_eq!(strip_command("  /SSH web ", &["/ssh"]), Some("web"));
+        assert_eq!(strip_command("/files x", &["/files", "/f"]), Some("x"));
+        assert_eq!(strip_command("/f x", &["/files", "/f"]), Some("x"));
+        assert_eq!(strip_command("web", &["/ssh"]), None);
+        assert_eq!(command_query(" web ", &["/ssh"]), "web");
+        // A multi-byte character where the prefix would end.
+        assert_eq!(command_query("/sé", &["/ssh"]), "/sé");
+    }
+
+    #[test]
+    fn paths_under_home_get_a_tilde() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        assert_eq!(tildify(&home), "~");
+        assert_eq!(tildify(&home.join("src/lancea")), "~/src/lancea");
+        assert_eq!(tildify(Path::new("/nonexistent/x")), "/nonexistent/x");
+    }
+
+    #[test]
+    fn a_leading_tilde_expands_to_home() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        assert_eq!(expand_home(PathBuf::from("~/src")), home.join("src"));
+        assert_eq!(expand_home(PathBuf::from("~")), home);
+        assert_eq!(
+            expand_home(PathBuf::from("/srv/~")),
+            PathBuf::from("/srv/~")
+        );
+    }
+}
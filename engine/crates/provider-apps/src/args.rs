@@ -0,0 +1,78 @@
+//! Files and URLs handed to an app at launch.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use url::Url;
+
+/// URL schemes whose URLs have no `//`, as in `mailto:me@example.org`.
+const OPAQUE_SCHEMES: &[&str] = &["mailto", "tel", "sms", "magnet", "geo", "news", "xmpp"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchArg {
+    Path(PathBuf),
+    Url(Url),
+}
+
+impl LaunchArg {
+    /// Accepts absolute paths, `~/` paths and URLs. Relative paths are
+    /// rejected: the engine's working directory means nothing to the user.
+    pub fn parse(s: &str) -> Option<Self> {
+        if let Some(rest) = s.strip_prefix("~/") {
+            return dirs::home_dir().map(|home| LaunchArg::Path(home.join(rest)));
+        }
+        if s.starts_with('/') {
+            return Some(LaunchArg::Path(PathBuf::from(s)));
+        }
+
+        // Anything with a colon parses as some URL (`localhost:8080`,
+        // `c:file`), so only take `scheme://…` and the well-known schemes
+        // written without slashes.
+        let (scheme, rest) = s.split_once(':')?;
+        if !rest.starts_with("//")
+            && !OPAQUE_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str())
+        {
+            return None;
+        }
+        // Single-letter schemes are far more likely to be typos than URLs.
+        Url::parse(s)
+            .ok()
+            .filter(|u| u.scheme().len() > 1)
+            .map(LaunchArg::Url)
+    }
+
+    /// Local path, for plain paths and `file://` URLs.
+    pub fn as_path(&self) -> Option<PathBuf> {
+        match self {
+            LaunchArg::Path(p) => Some(p.clone()),
+            LaunchArg::Url(u) if u.scheme() == "file" => u.to_file_path().ok(),
+            LaunchArg::Url(_) => None,
+        }
+    }
+
+    /// URL form, turning plain paths into `file://` URLs.
+    pub fn as_url(&self) -> String {
+        match self {
+            LaunchArg::Path(p) => Url::from_file_path(p)
+                .map(String::from)
+                .unwrap_or_else(|_| p.display().to_string()),
+            LaunchArg::Url(u) => u.to_string(),
+        }
+    }
+
+    pub fn scheme(&self) -> Option<&str> {
+        match self {
+            LaunchArg::Path(_) => None,
+            LaunchArg::Url(u) => Some(u.scheme()),
+        }
+    }
+}
+
+impl fmt::Display for LaunchArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchArg::Path(p) => write!(f, "{}", p.display()),
+            LaunchArg::Url(u) => write!(f, "{u}"),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! `mimeapps.list` handling, per the XDG MIME Applications Associations
+//! spec: default applications plus added and removed associations, read at
+//! every precedence level.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::{env, fs};
+
+use ini::Ini;
+
+/// One `mimeapps.list` file. Values are desktop ids without `.desktop`.
+#[derive(Debug, Clone, Default)]
+struct Level {
+    defaults: HashMap<String, Vec<String>>,
+    added: HashMap<String, Vec<String>>,
+    removed: HashMap<String, Vec<String>>,
+}
+
+/// Every `mimeapps.list` that applies, most important first.
+#[derive(Debug, Clone, Default)]
+pub struct MimeApps {
+    levels: Vec<Level>,
+}
+
+impl MimeApps {
+    /// Reads the lists from the XDG config and data dirs, including the
+    /// `$desktop-mimeapps.list` variants for `XDG_CURRENT_DESKTOP`.
+    pub fn new() -> Self {
+        Self::from_files(&mimeapps_paths())
+    }
+
+    /// Reads `paths` as a precedence-ordered list: earlier files win.
+    /// Missing or unreadable files are skipped.
+    pub fn from_files(paths: &[PathBuf]) -> Self {
+        let levels = paths
+            .iter()
+            .filter_map(|p| {
+                let txt = fs::read_to_string(p).ok()?;
+                let ini = Ini::load_from_str(&txt).ok()?;
+                Some(Level {
+                    defaults: read_group(&ini, "Default Applications"),
+                    added: read_group(&ini, "Added Associations"),
+                    removed: read_group(&ini, "Removed Associations"),
+                })
+            })
+            .collect();
+
+        Self { levels }
+    }
+
+    /// Default-application candidates for exactly `mime`, in preference
+    /// order. Callers pick the first one that is installed.
+    pub fn defaults(&self, mime: &str) -> Vec<&str> {
+        self.levels
+            .iter()
+            .flat_map(|l| l.defaults.get(mime).into_iter().flatten())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Added associations for exactly `mime`, in preference order. A removal
+    /// only hides associations from its own and less important files.
+    pub fn added(&self, mime: &str) -> Vec<&str> {
+        let mut out: Vec<&str> = Vec::new();
+        let mut removed: Vec<&str> = Vec::new();
+
+        for level in &self.levels {
+            removed.extend(
+                level
+                    .removed
+                    .get(mime)
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str),
+            );
+            for id in level.added.get(mime).into_iter().flatten() {
+                if !removed.contains(&id.as_str()) && !out.contains(&id.as_str()) {
+                    out.push(id);
+                }
+            }
+        }
+        out
+    }
+
+    /// Whether any list removes the `id` ↔ `mime` association.
+    pub fn is_removed(&self, mime: &str, id: &str) -> bool {
+        self.levels.iter().any(|l| {
+            l.removed
+                .get(mime)
+                .is_some_and(|ids| ids.iter().any(|r| r == id))
+        })
+    }
+}
+
+fn read_group(ini: &Ini, name: &str) -> HashMap<String, Vec<String>> {
+    let Some(sec) = ini.section(Some(name)) else {
+        return HashMap::new();
+    };
+
+    sec.iter()
+        .map(|(mime, ids)| {
+            let ids = ids
+                .split(';')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(|id| id.strip_suffix(".desktop").unwrap_or(id).to_string())
+                .collect();
+            (mime.trim().to_string(), ids)
+        })
+        .collect()
+}
+
+/// The spec's lookup order: config home, config dirs, then (deprecated)
+/// data home and data dirs, each with desktop-specific files first.
+fn mimeapps_paths() -> Vec<PathBuf> {
+    let desktops: Vec<String> = env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let config_dirs = env::var_os("XDG_CONFIG_DIRS")
+        .map(|v| env::split_paths(&v).collect::<Vec<PathBuf>>())
+        .unwrap_or_else(|| vec![PathBuf::from("/etc/xdg")]);
+    let data_dirs = env::var_os("XDG_DATA_DIRS")
+        .map(|v| env::split_paths(&v).collect::<Vec<PathBuf>>())
+        .unwrap_or_else(|| {
+            vec![
+                PathBuf::from("/usr/local/share"),
+                PathBuf::from("/usr/share"),
+            ]
+        });
+
+    let mut bases: Vec<PathBuf> = Vec::new();
+    bases.extend(dirs::config_dir());
+    bases.extend(config_dirs);
+    bases.extend(dirs::data_dir().map(|d| d.join("applications")));
+    bases.extend(data_dirs.into_iter().map(|d| d.join("applications")));
+
+    let mut paths = Vec::new();
+    for base in bases {
+        for desktop in &desktops {
+            paths.push(base.join(format!("{desktop}-mimeapps.list")));
+        }
+        paths.push(base.join("mimeapps.list"));
+    }
+    paths
+}
@@ -0,0 +1,165 @@
+//! `Exec=` parsing and field-code expansion, per the Desktop Entry spec.
+
+use anyhow::{Context, Result, bail};
+
+use crate::AppRecord;
+use crate::args::LaunchArg;
+
+/// Splits an `Exec=` value into words, honouring double quotes and the
+/// backslash escapes allowed inside them.
+pub(crate) fn split_exec(exec: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut cur = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            '\\' if quoted => cur.push(chars.next().context("dangling escape in Exec")?),
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut cur));
+                    in_word = false;
+                }
+            }
+            c => {
+                cur.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quoted {
+        bail!("unterminated quote in Exec");
+    }
+    if in_word {
+        words.push(cur);
+    }
+    Ok(words)
+}
+
+/// Expands `app`'s `Exec=` for `args`, returning one command line per
+/// process to start: `%f` and `%u` take a single argument, so several
+/// arguments mean several instances.
+pub(crate) fn expand(app: &AppRecord, args: &[LaunchArg]) -> Result<Vec<Vec<String>>> {
+    let exec = app
+        .exec
+        .as_deref()
+        .with_context(|| format!("{} has no Exec line", app.desktop_id))?;
+    let words = split_exec(exec)?;
+
+    let groups: Vec<&[LaunchArg]> = match file_code(&words) {
+        None if !args.is_empty() => {
+            bail!("{} does not take files or URLs", app.desktop_id)
+        }
+        Some('f' | 'u') if args.len() > 1 => args.chunks(1).collect(),
+        _ => vec![args],
+    };
+
+    groups
+        .into_iter()
+        .map(|group| expand_words(app, &words, group))
+        .collect()
+}
+
+/// Whether `Exec=` has somewhere to put files or URLs.
+pub(crate) fn takes_args(app: &AppRecord) -> bool {
+    app.exec
+        .as_deref()
+        .and_then(|e| split_exec(e).ok())
+        .is_some_and(|words| file_code(&words).is_some())
+}
+
+fn file_code(words: &[String]) -> Option<char> {
+    words.iter().find_map(|w| {
+        let mut chars = w.chars();
+        while let Some(c) = chars.next() {
+            if c == '%' {
+                match chars.next() {
+                    Some(code @ ('f' | 'F' | 'u' | 'U')) => return Some(code),
+                    _ => continue,
+                }
+            }
+        }
+        None
+    })
+}
+
+fn expand_words(app: &AppRecord, words: &[String], args: &[LaunchArg]) -> Result<Vec<String>> {
+    let mut argv = Vec::with_capacity(words.len() + args.len());
+
+    for word in words {
+        match word.as_str() {
+            "%F" => {
+                for a in args {
+                    argv.push(file_arg(app, a)?);
+                }
+                continue;
+            }
+            "%U" => {
+                argv.extend(args.iter().map(LaunchArg::as_url));
+                continue;
+            }
+            "%i" => {
+                if let Some(icon) = &app.icon {
+                    argv.push("--icon".into());
+                    argv.push(icon.clone());
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut out = String::with_capacity(word.len());
+        let mut had_code = false;
+        let mut chars = word.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('%') => out.push('%'),
+                Some('f') => {
+                    if let Some(a) = args.first() {
+                        out.push_str(&file_arg(app, a)?);
+                    }
+                }
+                Some('u') => {
+                    if let Some(a) = args.first() {
+                        out.push_str(&a.as_url());
+                    }
+                }
+                Some('c') => out.push_str(&app.name),
+                Some('k') => out.push_str(&app.desktop_path.to_string_lossy()),
+                // Deprecated codes, and list codes that aren't a word of
+                // their own, expand to nothing.
+                _ => {}
+            }
+            had_code = true;
+        }
+
+        // A word that was only a field code with nothing to fill it vanishes.
+        if !(had_code && out.is_empty()) {
+            argv.push(out);
+        }
+    }
+
+    if argv.is_empty() {
+        bail!("{} has an empty Exec line", app.desktop_id);
+    }
+    Ok(argv)
+}
+
+fn file_arg(app: &AppRecord, arg: &LaunchArg) -> Result<String> {
+    match arg.as_path() {
+        Some(p) => Ok(p.to_string_lossy().into_owned()),
+        None => bail!("{} only opens local files, not {arg}", app.desktop_id),
+    }
+}
@@ -0,0 +1,115 @@
+//! How expanded command lines are started: directly, inside a transient
+//! systemd user scope, or through a user-supplied wrapper command.
+
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+/// Selected by `strategy` in the `[apps.launch]` config table.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case")]
+pub enum LaunchStrategy {
+    /// Spawn the app as a child of the engine.
+    #[default]
+    Direct,
+    /// `systemd-run --user --scope`, giving each app its own cgroup named
+    /// after the XDG `app-<launcher>-<id>-<random>.scope` convention.
+    SystemdScope,
+    /// Any wrapper, e.g. `["uwsm", "app", "--", "{argv}"]`. `{argv}` must be
+    /// a word of its own and is replaced by the app's command line (which is
+    /// appended when absent); `{desktop_id}` and `{name}` are replaced
+    /// anywhere.
+    Wrapper { command: Vec<String> },
+}
+
+impl LaunchStrategy {
+    /// Wraps `argv`, started for the app (or other command) `id` called
+    /// `name`, according to the strategy.
+    pub(crate) fn wrap(&self, id: &str, name: &str, argv: Vec<String>) -> Result<Vec<String>> {
+        match self {
+            LaunchStrategy::Direct => Ok(argv),
+            LaunchStrategy::SystemdScope => {
+                let mut out = vec![
+                    "systemd-run".to_string(),
+                    "--user".into(),
+                    "--scope".into(),
+                    "--quiet".into(),
+                    "--collect".into(),
+                    "--slice=app.slice".into(),
+                    format!("--unit={}", scope_name(id)),
+                    format!("--description={name}"),
+                    "--".into(),
+                ];
+                out.extend(argv);
+                Ok(out)
+            }
+            LaunchStrategy::Wrapper { command } => {
+                if command.is_empty() {
+                    bail!("launch wrapper command is empty");
+                }
+
+                let mut out = Vec::with_capacity(command.len() + argv.len());
+                let mut placed = false;
+                for word in command {
+                    if word == "{argv}" {
+                        out.extend(argv.iter().cloned());
+                        placed = true;
+                    } else {
+                        out.push(word.replace("{desktop_id}", id).replace("{name}", name));
+                    }
+                }
+                if !placed {
+                    out.extend(argv);
+                }
+                Ok(out)
+            }
+        }
+    }
+}
+
+/// Starts `argv` detached from the engine's stdio and process group,
+/// reaping it in the background so finished apps don't linger as zombies.
+pub(crate) fn spawn(argv: &[String]) -> Result<()> {
+    let (program, rest) = argv.split_first().context("empty command line")?;
+    let mut child = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .with_context(|| format!("failed to spawn {program}"))?;
+
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// `app-lancea-<id>-<random>.scope`, with characters systemd won't take in
+/// a unit name replaced.
+fn scope_name(desktop_id: &str) -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let id: String = desktop_id
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | ':' => c,
+            _ => '_',
+        })
+        .collect();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    format!(
+        "app-lancea-{id}-{:x}.scope",
+        nanos ^ (n << 20) ^ std::process::id()
+    )
+}
@@ -1,19 +1,44 @@
 use anyhow::{Context, Result};
-use deunicode::deunicode;
+use deunicode::deunicode_char;
 use dirs;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use ini::Ini;
-use lancea_model::{Preview, Provider, ResultItem};
-use serde::Serialize;
-use std::borrow::Cow;
+use lancea_model::{MatchRange, Matches, Preview, Provider, ResultItem, command_query};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 use unicode_normalization::UnicodeNormalization;
 use walkdir::WalkDir;
 
+mod args;
+mod assoc;
+mod exec;
+mod launch;
+mod mime;
+mod ranking;
+
+pub use args::LaunchArg;
+pub use assoc::MimeApps;
+pub use launch::LaunchStrategy;
+pub use mime::MimeDb;
+pub use ranking::{MatchTerms, MatchWeights};
+
 const PROVIDER_ID: &str = "apps";
 
+/// The `[apps]` table of the engine config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AppsConfig {
+    pub launch: LaunchStrategy,
+    pub weights: MatchWeights,
+    /// Command that runs a program in a terminal, with the program's argv
+    /// appended, e.g. `["foot"]` or `["kitty", "--"]`. Defaults to
+    /// `$TERMINAL -e`, else `xterm -e`.
+    pub terminal: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct AppRecord {
     pub desktop_id: String,
@@ -22,11 +47,17 @@ pub struct AppRecord {
     pub comment: Option<String>,
     pub exec: Option<String>,
     pub icon: Option<String>,
+    /// The window class or Wayland app id its windows carry.
+    pub startup_wm_class: Option<String>,
     pub categories: Vec<String>,
     pub keywords: Vec<String>,
+    pub mime_types: Vec<String>,
+    pub terminal: bool,
     pub nodisplay: bool,
     pub desktop_path: PathBuf,
     pub search_blob: String,
+    #[serde(skip)]
+    pub(crate) terms: MatchTerms,
 }
 
 impl AppRecord {
@@ -40,13 +71,24 @@ impl AppRecord {
 
 pub struct AppsProvider {
     apps: Vec<AppRecord>,
+    weights: MatchWeights,
+    mime: MimeDb,
+    assoc: MimeApps,
+    launcher: LaunchStrategy,
+    terminal: Vec<String>,
 }
 
 impl AppsProvider {
     pub fn new() -> Result<Self, anyhow::Error> {
+        Self::from_dirs(&application_dirs())
+    }
+
+    /// Builds the index from an explicit list of `applications` directories
+    /// instead of the XDG ones.
+    pub fn from_dirs(dirs: &[PathBuf]) -> Result<Self, anyhow::Error> {
         let mut apps = vec![];
 
-        for p in application_dirs() {
+        for p in dirs {
             if !p.exists() {
                 continue;
             }
@@ -73,16 +115,60 @@ impl AppsProvider {
         apps.sort_by(|a, b| a.desktop_id.cmp(&b.desktop_id));
         apps.dedup_by(|a, b| a.desktop_id == b.desktop_id);
 
-        Ok(Self { apps })
+        Ok(Self {
+            apps,
+            weights: MatchWeights::default(),
+            mime: MimeDb::new(),
+            assoc: MimeApps::new(),
+            launcher: LaunchStrategy::default(),
+            terminal: Vec::new(),
+        })
+    }
+
+    /// Replaces the default per-tier ranking weights.
+    pub fn with_weights(mut self, weights: MatchWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Applies the `[apps]` table of the engine config.
+    pub fn with_config(self, config: AppsConfig) -> Self {
+        self.with_weights(config.weights)
+            .with_launch_strategy(config.launch)
+            .with_terminal(config.terminal)
+    }
+
+    /// Replaces the default direct spawn.
+    pub fn with_launch_strategy(mut self, launcher: LaunchStrategy) -> Self {
+        self.launcher = launcher;
+        self
+    }
+
+    /// Sets the terminal command; empty means the default.
+    pub fn with_terminal(mut self, terminal: Vec<String>) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Replaces the system MIME database used to vet launch arguments.
+    pub fn with_mime_db(mut self, mime: MimeDb) -> Self {
+        self.mime = mime;
+        self
+    }
+
+    /// Replaces the `mimeapps.list` files read from the XDG dirs.
+    pub fn with_mime_apps(mut self, assoc: MimeApps) -> Self {
+        self.assoc = assoc;
+        self
     }
 
     pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
-        let q = normalize_query(raw_query);
-        let q = q
-            .strip_prefix("/apps")
-            .or_else(|| q.strip_prefix("/ap"))
-            .map(|s| s.trim())
-            .unwrap_or(&q);
+        let (words, args) = split_launch_args(command_query(raw_query, &["/apps", "/ap"]));
+        let q = normalize_query(&words);
+
+        if !args.is_empty() {
+            return self.search_with_args(&q, &args);
+        }
 
         if q.is_empty() {
             return Vec::new();
@@ -92,53 +178,245 @@ impl AppsProvider {
         }
 
         let matcher = SkimMatcherV2::default();
+        let scored = self.rank(&q, self.apps.iter(), &matcher);
 
-        let mut scored: Vec<(f32, ResultItem)> = Vec::new();
-
-        for app in &self.apps {
-            let hay = app.search_blob.as_str();
-            let mut best: Option<f32> = None;
-
-            if starts_with_token(&app.name, &q)
-                || app
-                    .generic_name
-                    .as_ref()
-                    .map_or(false, |g| starts_with_token(g, &q))
-                || app
-                    .comment
-                    .as_ref()
-                    .map_or(false, |c| starts_with_token(c, &q))
-            {
-                best = Some(1.0);
-            }
+        let scored_results: Vec<ResultItem> = scored
+            .into_iter()
+            .take(25)
+            .map(|(score, app)| to_result_item(app, score, app_matches(app, &q, &matcher)))
+            .collect();
 
-            if best.is_none() {
-                if let Some(score) = matcher.fuzzy_match(hay, &q) {
-                    let s = (score as f32 / 100.0).clamp(0.1, 0.7);
-                    best = Some(s)
-                }
-            }
+        return scored_results;
+    }
 
-            if best.is_none() && hay.contains(&q) {
-                best = Some(0.35);
-            }
+    /// Search when the query names files or URLs: only apps that can open
+    /// all of them are offered, and with no app query at all this is the
+    /// "Open with…" list.
+    fn search_with_args(&self, q: &str, args: &[LaunchArg]) -> Vec<ResultItem> {
+        let matcher = SkimMatcherV2::default();
 
-            if let Some(score) = best {
-                let item = to_result_item(app, score);
-                scored.push((score, item));
-            }
-        }
+        let scored: Vec<(f32, &AppRecord, Matches)> = if q.is_empty() {
+            self.open_with(args)
+                .into_iter()
+                .map(|(score, app)| (score, app, Matches::default()))
+                .collect()
+        } else {
+            // Once per argument, not once per app: each guesses the type
+            // and walks every app.
+            let accepted: Vec<Option<HashSet<&str>>> =
+                args.iter().map(|a| self.accepted_ids(a)).collect();
+            let candidates = self.apps.iter().filter(|app| {
+                exec::takes_args(app) && accepted.iter().all(|ids| accepts(app, ids.as_ref()))
+            });
+            self.rank(q, candidates, &matcher)
+                .into_iter()
+                .map(|(score, app)| (score, app, app_matches(app, q, &matcher)))
+                .collect()
+        };
+
+        let default = args.first().and_then(|a| self.default_app(a));
+        let arg_list: Vec<String> = args.iter().map(LaunchArg::to_string).collect();
+        scored
+            .into_iter()
+            .take(25)
+            .map(|(score, app, matches)| {
+                let is_default = default.is_some_and(|d| d.desktop_id == app.desktop_id);
+                let mut item = to_result_item(app, score, matches);
+                if let Some(extras) = item.extras.as_mut() {
+                    extras["args"] = serde_json::json!(arg_list);
+                    extras["isDefault"] = serde_json::json!(is_default);
+                }
+                item
+            })
+            .collect()
+    }
+
+    fn rank<'a>(
+        &'a self,
+        q: &str,
+        apps: impl Iterator<Item = &'a AppRecord>,
+        matcher: &SkimMatcherV2,
+    ) -> Vec<(f32, &'a AppRecord)> {
+        let mut scored: Vec<(f32, &AppRecord)> = apps
+            .filter_map(|app| {
+                app.terms
+                    .score(&app.search_blob, q, &self.weights, matcher)
+                    .map(|s| (s, app))
+            })
+            .collect();
 
         scored.sort_by(|a, b| {
             b.0.partial_cmp(&a.0)
                 .unwrap_or(std::cmp::Ordering::Equal)
-                .then_with(|| natord(&a.1.title, &b.1.title))
+                .then_with(|| natord(a.1.title(), b.1.title()))
         });
+        scored
+    }
 
-        let scored_results: Vec<ResultItem> =
-            scored.into_iter().map(|(_, it)| it).take(25).collect();
+    /// Apps that can open every argument, best first: the default app,
+    /// then direct associations, then apps that only handle a parent type.
+    pub fn open_with(&self, args: &[LaunchArg]) -> Vec<(f32, &AppRecord)> {
+        let Some((first, rest)) = args.split_first() else {
+            return Vec::new();
+        };
+        let others: Vec<Vec<(f32, &AppRecord)>> =
+            rest.iter().map(|a| self.associations(a)).collect();
+
+        let mut scored: Vec<(f32, &AppRecord)> = self
+            .associations(first)
+            .into_iter()
+            .filter_map(|(score, app)| {
+                others
+                    .iter()
+                    .try_fold(score, |acc, list| {
+                        list.iter()
+                            .find(|(_, a)| a.desktop_id == app.desktop_id)
+                            .map(|(s, _)| acc.min(*s))
+                    })
+                    .map(|s| (s, app))
+            })
+            .collect();
+
+        // Stable, so association order survives within a score.
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored
+    }
 
-        return scored_results;
+    /// The `/apps` query that lists what can open `path`: the "Open with…"
+    /// list for another provider's result. The path goes as a `file://`
+    /// URL so spaces in it survive being split into words.
+    pub fn open_with_query(path: &Path) -> Option<String> {
+        let url = url::Url::from_file_path(path).ok()?;
+        Some(format!("/apps {url}"))
+    }
+
+    /// The app that opens `arg` by default, following `mimeapps.list` and
+    /// falling back to the most preferred association.
+    pub fn default_app(&self, arg: &LaunchArg) -> Option<&AppRecord> {
+        self.associations(arg)
+            .into_iter()
+            .next()
+            .map(|(_, app)| app)
+    }
+
+    /// The app whose windows carry `app_id` (a Wayland app id or X11
+    /// class): by `StartupWMClass`, else by desktop id, else by the last
+    /// part of a reverse-DNS desktop id.
+    pub fn find_by_window_class(&self, app_id: &str) -> Option<&AppRecord> {
+        let by_class = || {
+            self.apps.iter().find(|a| {
+                a.startup_wm_class
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(app_id))
+            })
+        };
+        let by_id = || {
+            self.apps
+                .iter()
+                .find(|a| a.desktop_id.eq_ignore_ascii_case(app_id))
+        };
+        let by_last_part = || {
+            self.apps.iter().find(|a| {
+                a.desktop_id
+                    .rsplit('.')
+                    .next()
+                    .is_some_and(|last| last.eq_ignore_ascii_case(app_id))
+            })
+        };
+        by_class().or_else(by_id).or_else(by_last_part)
+    }
+
+    /// Launches each target with its default app.
+    pub fn open_default(&self, targets: &[String]) -> Result<()> {
+        for target in targets {
+            let arg =
+                LaunchArg::parse(target).with_context(|| format!("not a path or URL: {target}"))?;
+            let app = self
+                .default_app(&arg)
+                .with_context(|| format!("no application opens {target}"))?;
+            self.execute_launch(&app.desktop_id, std::slice::from_ref(target))?;
+        }
+        Ok(())
+    }
+
+    /// Every installed app associated with `arg`'s MIME type or one of its
+    /// parents, scored by how it got there. Removed associations are
+    /// honoured; apps whose `Exec=` can't take arguments are left out.
+    pub fn associations(&self, arg: &LaunchArg) -> Vec<(f32, &AppRecord)> {
+        let Some(mime) = self.mime.guess(arg) else {
+            return Vec::new();
+        };
+        let lineage = self.mime.ancestors(&mime);
+
+        let usable = |id: &str| {
+            self.apps
+                .iter()
+                .find(|a| a.desktop_id == id)
+                .filter(|a| exec::takes_args(a))
+        };
+
+        let declared = |m: &str| {
+            let mut apps: Vec<&AppRecord> = self
+                .apps
+                .iter()
+                .filter(|a| a.mime_types.iter().any(|t| self.mime.canonical(t) == m))
+                .filter(|a| !self.assoc.is_removed(m, &a.desktop_id) && exec::takes_args(a))
+                .collect();
+            apps.sort_by(|a, b| natord(a.title(), b.title()));
+            apps
+        };
+
+        let mut out: Vec<(f32, &AppRecord)> = Vec::new();
+
+        // As the spec looks a type up: its default, else its added or
+        // declared apps (listed first below anyway), before its parents'.
+        // So a parent's default only applies when the type has no apps.
+        let default = lineage
+            .iter()
+            .find_map(|m| {
+                if let Some(app) = self.assoc.defaults(m).into_iter().find_map(&usable) {
+                    return Some(Some(app));
+                }
+                let own = self
+                    .assoc
+                    .added(m)
+                    .into_iter()
+                    .any(|id| usable(id).is_some())
+                    || !declared(m).is_empty();
+                own.then_some(None)
+            })
+            .flatten();
+        if let Some(app) = default {
+            push_unique(&mut out, ASSOC_DEFAULT, app);
+        }
+
+        for (depth, m) in lineage.iter().enumerate() {
+            let score = if depth == 0 {
+                ASSOC_DIRECT
+            } else {
+                ASSOC_INHERITED
+            };
+
+            for app in self.assoc.added(m).into_iter().filter_map(&usable) {
+                push_unique(&mut out, score, app);
+            }
+            for app in declared(m) {
+                push_unique(&mut out, score, app);
+            }
+        }
+        out
+    }
+
+    /// Desktop ids of the apps that can be handed `arg`, or `None` when
+    /// its type can't be guessed and any app may try.
+    fn accepted_ids(&self, arg: &LaunchArg) -> Option<HashSet<&str>> {
+        self.mime.guess(arg)?;
+        Some(
+            self.associations(arg)
+                .into_iter()
+                .map(|(_, app)| app.desktop_id.as_str())
+                .collect(),
+        )
     }
 
     pub fn preview(&self, key: &str) -> Option<Preview> {
@@ -160,29 +438,95 @@ impl AppsProvider {
         })
     }
 
-    pub fn execute_launch(&self, key: &str) -> Result<(), anyhow::Error> {
+    /// Command lines that launching `key` with `args` would run, after
+    /// checking each argument against the app's `MimeType=` and applying
+    /// the launch strategy.
+    pub fn launch_argv(&self, key: &str, args: &[String]) -> Result<Vec<Vec<String>>> {
         let id = key.strip_prefix("apps:").unwrap_or(key);
-
-        if !self.apps.iter().any(|a| a.desktop_id == id) {
+        let Some(app) = self.apps.iter().find(|a| a.desktop_id == id) else {
             anyhow::bail!("Unknown desktop-id: {id}");
+        };
+
+        let args: Vec<LaunchArg> = args
+            .iter()
+            .map(|a| LaunchArg::parse(a).with_context(|| format!("not a path or URL: {a}")))
+            .collect::<Result<_>>()?;
+        if let Some(bad) = args
+            .iter()
+            .find(|a| !accepts(app, self.accepted_ids(a).as_ref()))
+        {
+            anyhow::bail!("{id} cannot open {bad}");
         }
 
-        let status = std::process::Command::new("gtk-launch")
-            .arg(id)
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .status()
-            .context("failed to spawn gtk-lauch")?;
+        exec::expand(app, &args)?
+            .into_iter()
+            .map(|mut argv| {
+                if app.terminal {
+                    argv.splice(0..0, self.terminal_prefix());
+                }
+                self.launcher.wrap(&app.desktop_id, &app.name, argv)
+            })
+            .collect()
+    }
 
-        if status.success() {
-            Ok(())
-        } else {
-            anyhow::bail!("gtk-launch exited with status {status}");
+    pub fn execute_launch(&self, key: &str, args: &[String]) -> Result<(), anyhow::Error> {
+        for argv in self.launch_argv(key, args)? {
+            launch::spawn(&argv)?;
+        }
+        Ok(())
+    }
+
+    /// Starts `argv` through the launch strategy, like an app. `id` and
+    /// `name` are as for [`Self::terminal_argv`].
+    pub fn run_command(&self, id: &str, name: &str, argv: &[String]) -> Result<()> {
+        launch::spawn(&self.launcher.wrap(id, name, argv.to_vec())?)
+    }
+
+    /// Command line that runs `argv` in the configured terminal, through the
+    /// launch strategy. `id` and `name` stand in for an app's desktop id and
+    /// name, e.g. in scope unit names.
+    pub fn terminal_argv(&self, id: &str, name: &str, argv: &[String]) -> Result<Vec<String>> {
+        let mut full = self.terminal_prefix();
+        full.extend(argv.iter().cloned());
+        self.launcher.wrap(id, name, full)
+    }
+
+    pub fn run_in_terminal(&self, id: &str, name: &str, argv: &[String]) -> Result<()> {
+        launch::spawn(&self.terminal_argv(id, name, argv)?)
+    }
+
+    /// Terminal used for `Terminal=true` apps and [`Self::run_in_terminal`]:
+    /// the configured one, `$TERMINAL`, else xterm.
+    fn terminal_prefix(&self) -> Vec<String> {
+        if !self.terminal.is_empty() {
+            return self.terminal.clone();
         }
+        let term = env::var("TERMINAL")
+            .ok()
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| "xterm".into());
+        vec![term, "-e".into()]
     }
 }
 
+/// Whether `app` can be handed an argument whose accepted apps are
+/// `accepted`. Apps without `MimeType=`, and arguments whose type can't be
+/// guessed, get the benefit of the doubt.
+fn accepts(app: &AppRecord, accepted: Option<&HashSet<&str>>) -> bool {
+    app.mime_types.is_empty() || accepted.is_none_or(|ids| ids.contains(app.desktop_id.as_str()))
+}
+
+fn push_unique<'a>(out: &mut Vec<(f32, &'a AppRecord)>, score: f32, app: &'a AppRecord) {
+    if !out.iter().any(|(_, a)| a.desktop_id == app.desktop_id) {
+        out.push((score, app));
+    }
+}
+
+/// Scores for the "Open with…" list.
+const ASSOC_DEFAULT: f32 = 1.0;
+const ASSOC_DIRECT: f32 = 0.9;
+const ASSOC_INHERITED: f32 = 0.6;
+
 fn application_dirs() -> Vec<PathBuf> {
     let mut dirs = Vec::new();
 
@@ -231,6 +575,18 @@ fn parse_desktop_file(path: &Path) -> Result<Option<AppRecord>, anyhow::Error> {
     let comment = get_best_locale(&sec, "Comment");
     let exec = get_best_locale(&sec, "Exec");
     let icon = get_best_locale(&sec, "Icon");
+    let startup_wm_class = get_best_locale(&sec, "StartupWMClass");
+
+    let mime_types = get_best_locale(&sec, "MimeType")
+        .map(|s| {
+            s.split(';')
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let terminal = get_best_locale(&sec, "Terminal").is_some_and(|s| s == "true" || s == "1");
 
     let nodisplay = get_best_locale(&sec, "NoDisplay")
         .map(|s| s == "true" || s == "1")
@@ -276,6 +632,8 @@ fn parse_desktop_file(path: &Path) -> Result<Option<AppRecord>, anyhow::Error> {
         push_norm(&mut blob, c);
     }
 
+    let terms = MatchTerms::new(&name, generic.as_deref(), &keywords);
+
     Ok(Some(AppRecord {
         desktop_id,
         name: name.to_string(),
@@ -283,11 +641,15 @@ fn parse_desktop_file(path: &Path) -> Result<Option<AppRecord>, anyhow::Error> {
         comment,
         exec,
         icon,
+        startup_wm_class,
         categories,
         keywords,
+        mime_types,
+        terminal,
         nodisplay,
         desktop_path: path.to_path_buf(),
         search_blob: blob,
+        terms,
     }))
 }
 
@@ -299,6 +661,22 @@ fn push_norm(blob: &mut String, s: &str) {
     }
 }
 
+/// Pulls existing paths and URLs out of the query, leaving the words that
+/// select the app. Paths that don't exist stay in the query so a stray `/`
+/// still reads as the start of another command.
+fn split_launch_args(raw: &str) -> (String, Vec<LaunchArg>) {
+    let mut words: Vec<&str> = Vec::new();
+    let mut args: Vec<LaunchArg> = Vec::new();
+
+    for token in raw.split_whitespace() {
+        match LaunchArg::parse(token) {
+            Some(arg) if arg.as_path().is_none_or(|p| p.exists()) => args.push(arg),
+            _ => words.push(token),
+        }
+    }
+    (words.join(" "), args)
+}
+
 fn desktop_id_from_path(path: &Path) -> String {
     let stem = path
         .file_name()
@@ -344,7 +722,9 @@ fn get_best_locale(sec: &ini::Properties, key: &str) -> Option<String> {
     None
 }
 
-fn normalize_query<S: AsRef<str>>(s: S) -> String {
+/// Folds text the way app names are matched: accents and other scripts
+/// transliterated to lowercase ASCII, whitespace collapsed.
+pub fn normalize_query<S: AsRef<str>>(s: S) -> String {
     let s = s.as_ref().trim();
     if s.is_empty() {
         return String::new();
@@ -353,30 +733,89 @@ fn normalize_query<S: AsRef<str>>(s: S) -> String {
 }
 
 fn norm(s: &str) -> String {
-    let folded: Cow<'_, str> = Cow::Owned(
-        deunicode(&s.nfkd().collect::<String>())
-            .to_lowercase()
-            .chars()
-            .map(|c| if c.is_control() { ' ' } else { c })
-            .collect::<String>(),
-    );
-
-    let mut out = String::with_capacity(folded.len());
-    let mut prev_space = false;
-    for ch in folded.chars() {
-        let space = ch.is_whitespace();
-        if space && prev_space {
-            continue;
+    fold(s).text
+}
+
+/// Output of `norm`, plus the index of the source char each output char
+/// was folded from, so match positions can be mapped back onto the
+/// original string.
+struct Folded {
+    text: String,
+    src: Vec<usize>,
+}
+
+fn fold(s: &str) -> Folded {
+    let mut text = String::with_capacity(s.len());
+    let mut src = Vec::with_capacity(s.len());
+    // Starting "after a space" drops leading whitespace.
+    let mut prev_space = true;
+
+    for (i, ch) in s.chars().enumerate() {
+        for d in ch.nfkd() {
+            let mut buf = [0; 4];
+            let ascii: &str = if d.is_ascii() {
+                d.encode_utf8(&mut buf)
+            } else {
+                deunicode_char(d).unwrap_or("[?]")
+            };
+
+            for c in ascii.chars() {
+                let space = c.is_whitespace() || c.is_control();
+                if space && prev_space {
+                    continue;
+                }
+                text.push(if space { ' ' } else { c.to_ascii_lowercase() });
+                src.push(i);
+                prev_space = space;
+            }
         }
-        out.push(if space { ' ' } else { ch });
-        prev_space = space
     }
 
-    return out.trim().to_string();
+    if text.ends_with(' ') {
+        text.pop();
+        src.pop();
+    }
+
+    Folded { text, src }
 }
 
-fn starts_with_token(hay: &str, q: &str) -> bool {
-    return norm(hay).starts_with(&norm(q));
+/// Where `q` (already normalised) hits `field`: a contiguous run when it is
+/// a substring of the folded field, otherwise the fuzzy matcher's picks.
+/// Multi-token queries are highlighted token by token.
+pub fn match_ranges(field: &str, q: &str, matcher: &SkimMatcherV2) -> Vec<MatchRange> {
+    let folded = fold(field);
+    let tokens: Vec<&str> = if folded.text.contains(q) {
+        vec![q]
+    } else {
+        q.split(' ').filter(|t| !t.is_empty()).collect()
+    };
+
+    // Folded text is pure ASCII, so byte offsets are char offsets.
+    let hits: Vec<usize> = tokens
+        .into_iter()
+        .flat_map(|t| match folded.text.find(t) {
+            Some(at) => (at..at + t.len()).collect(),
+            None => matcher
+                .fuzzy_indices(&folded.text, t)
+                .map(|(_, idx)| idx)
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    MatchRange::from_char_indices(
+        field,
+        hits.into_iter().filter_map(|i| folded.src.get(i).copied()),
+    )
+}
+
+fn app_matches(app: &AppRecord, q: &str, matcher: &SkimMatcherV2) -> Matches {
+    Matches {
+        title: match_ranges(app.title(), q, matcher),
+        subtitle: app
+            .subtitle()
+            .map(|s| match_ranges(s, q, matcher))
+            .filter(|r| !r.is_empty()),
+    }
 }
 
 fn natord(a: &str, b: &str) -> std::cmp::Ordering {
@@ -386,7 +825,7 @@ fn natord(a: &str, b: &str) -> std::cmp::Ordering {
     return a[..la].cmp(&b[..lb]);
 }
 
-fn to_result_item(app: &AppRecord, score: f32) -> ResultItem {
+fn to_result_item(app: &AppRecord, score: f32, matches: Matches) -> ResultItem {
     let subtitle = app.subtitle().unwrap_or_default();
     let extras = serde_json::json!({
         "subtitle": subtitle,
@@ -401,6 +840,7 @@ fn to_result_item(app: &AppRecord, score: f32) -> ResultItem {
         provider_id: PROVIDER_ID.into(),
         score,
         extras: Some(extras),
+        matches: Some(matches),
     }
 }
 
@@ -418,8 +858,12 @@ impl Provider for AppsProvider {
     }
 
     fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_with_args(action, key, &[])
+    }
+
+    fn execute_with_args(&self, action: &str, key: &str, args: &[String]) -> bool {
         match action {
-            "launch" => self.execute_launch(key).is_ok(),
+            "launch" => self.execute_launch(key, args).is_ok(),
             _ => false,
         }
     }
@@ -0,0 +1,268 @@
+//! Just enough of the shared-mime-info database to guess a MIME type from a
+//! file name and walk its parent types.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use crate::args::LaunchArg;
+
+#[derive(Debug, Clone)]
+struct Glob {
+    weight: u32,
+    mime: String,
+    pattern: String,
+    case_sensitive: bool,
+}
+
+/// Glob, alias and subclass tables loaded from `<data dir>/mime`.
+#[derive(Debug, Clone, Default)]
+pub struct MimeDb {
+    globs: Vec<Glob>,
+    aliases: HashMap<String, String>,
+    parents: HashMap<String, Vec<String>>,
+}
+
+impl MimeDb {
+    /// Loads the database from every XDG data dir.
+    pub fn new() -> Self {
+        Self::from_dirs(&mime_dirs())
+    }
+
+    /// Loads `globs2`, `aliases` and `subclasses` from each of `dirs`, which
+    /// are `mime` directories such as `/usr/share/mime`.
+    pub fn from_dirs(dirs: &[PathBuf]) -> Self {
+        let mut db = Self::default();
+
+        for dir in dirs {
+            if let Ok(txt) = fs::read_to_string(dir.join("globs2")) {
+                db.globs.extend(txt.lines().filter_map(parse_glob));
+            }
+            for (alias, canonical) in read_pairs(&dir.join("aliases")) {
+                db.aliases.insert(alias, canonical);
+            }
+            for (child, parent) in read_pairs(&dir.join("subclasses")) {
+                db.parents.entry(child).or_default().push(parent);
+            }
+        }
+
+        // Heaviest, then longest pattern wins, as the spec asks.
+        db.globs.sort_by(|a, b| {
+            b.weight
+                .cmp(&a.weight)
+                .then_with(|| b.pattern.len().cmp(&a.pattern.len()))
+        });
+        db
+    }
+
+    /// MIME type of a launch argument: `inode/directory` for directories,
+    /// a glob match for other paths and `x-scheme-handler/<scheme>` for
+    /// non-file URLs. `None` when nothing matches.
+    pub fn guess(&self, arg: &LaunchArg) -> Option<String> {
+        match arg.as_path() {
+            Some(path) => self.guess_path(&path),
+            None => arg
+                .scheme()
+                .map(|s| format!("x-scheme-handler/{}", s.to_ascii_lowercase())),
+        }
+    }
+
+    pub fn guess_path(&self, path: &Path) -> Option<String> {
+        if path.is_dir() {
+            return Some("inode/directory".into());
+        }
+
+        let name = path.file_name()?.to_str()?;
+        let lower = name.to_lowercase();
+        self.globs
+            .iter()
+            .find(|g| {
+                if g.case_sensitive {
+                    glob_match(&g.pattern, name)
+                } else {
+                    glob_match(&g.pattern.to_lowercase(), &lower)
+                }
+            })
+            .map(|g| g.mime.clone())
+    }
+
+    /// Whether `mime` is `ancestor` or one of its subclasses.
+    pub fn is_a(&self, mime: &str, ancestor: &str) -> bool {
+        let ancestor = self.canonical(ancestor);
+        self.ancestors(mime).iter().any(|m| m == ancestor)
+    }
+
+    /// `mime` followed by its parent types, nearest first, including the
+    /// implicit `text/plain` and `application/octet-stream` parents.
+    pub fn ancestors(&self, mime: &str) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        let mut queue = std::collections::VecDeque::from([self.canonical(mime).to_string()]);
+
+        while let Some(m) = queue.pop_front() {
+            if out.contains(&m) {
+                continue;
+            }
+
+            if let Some(parents) = self.parents.get(&m) {
+                queue.extend(parents.iter().map(|p| self.canonical(p).to_string()));
+            }
+            if m.starts_with("text/") && m != "text/plain" {
+                queue.push_back("text/plain".into());
+            }
+            if !m.starts_with("inode/")
+                && !m.starts_with("x-scheme-handler/")
+                && m != "application/octet-stream"
+            {
+                queue.push_back("application/octet-stream".into());
+            }
+            out.push(m);
+        }
+
+        // The catch-all parent goes last however it was reached.
+        if let Some(pos) = out.iter().position(|m| m == "application/octet-stream") {
+            let any = out.remove(pos);
+            out.push(any);
+        }
+        out
+    }
+
+    /// Resolves aliases such as `image/x-png` to the canonical type.
+    pub fn canonical<'a>(&'a self, mime: &'a str) -> &'a str {
+        self.aliases.get(mime).map(String::as_str).unwrap_or(mime)
+    }
+}
+
+fn mime_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(user) = dirs::data_dir() {
+        dirs.push(user.join("mime"));
+    }
+
+    let system = env::var_os("XDG_DATA_DIRS")
+        .map(|v| env::split_paths(&v).collect::<Vec<PathBuf>>())
+        .unwrap_or_else(|| {
+            vec![
+                PathBuf::from("/usr/local/share"),
+                PathBuf::from("/usr/share"),
+            ]
+        });
+    dirs.extend(system.into_iter().map(|d| d.join("mime")));
+
+    dirs.retain(|p| p.exists());
+    dirs
+}
+
+/// `weight:mime/type:glob[:flags]`
+fn parse_glob(line: &str) -> Option<Glob> {
+    if line.starts_with('#') {
+        return None;
+    }
+
+    let mut parts = line.splitn(4, ':');
+    let weight = parts.next()?.parse().ok()?;
+    let mime = parts.next()?.to_string();
+    let pattern = parts.next()?.to_string();
+    let case_sensitive = parts
+        .next()
+        .is_some_and(|flags| flags.split(',').any(|f| f == "cs"));
+
+    Some(Glob {
+        weight,
+        mime,
+        pattern,
+        case_sensitive,
+    })
+}
+
+/// Lines of two space-separated MIME types, as in `aliases` and
+/// `subclasses`.
+fn read_pairs(path: &Path) -> Vec<(String, String)> {
+    let Ok(txt) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    txt.lines()
+        .filter_map(|l| {
+            let (a, b) = l.trim().split_once(' ')?;
+            Some((a.to_string(), b.trim().to_string()))
+        })
+        .collect()
+}
+
+/// fnmatch-style matching of `*`, `?` and `[...]` classes.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+
+    let (mut pi, mut ni) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ni < n.len() {
+        if pi < p.len() {
+            match p[pi] {
+                '*' => {
+                    backtrack = Some((pi, ni));
+                    pi += 1;
+                    continue;
+                }
+                '?' => {
+                    pi += 1;
+                    ni += 1;
+                    continue;
+                }
+                '[' => {
+                    if let Some((true, next)) = match_class(&p, pi, n[ni]) {
+                        pi = next;
+                        ni += 1;
+                        continue;
+                    }
+                }
+                c if c == n[ni] => {
+                    pi += 1;
+                    ni += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        match backtrack {
+            Some((star, at)) => {
+                pi = star + 1;
+                ni = at + 1;
+                backtrack = Some((star, at + 1));
+            }
+            None => return false,
+        }
+    }
+
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+/// Matches `ch` against the class opening at `p[start]`, returning whether
+/// it matched and the index just past the closing `]`.
+fn match_class(p: &[char], start: usize, ch: char) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negate = p.get(i) == Some(&'!');
+    if negate {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+    while let Some(&c) = p.get(i) {
+        if c == ']' && !first {
+            return Some((matched != negate, i + 1));
+        }
+        if p.get(i + 1) == Some(&'-') && p.get(i + 2).is_some_and(|&e| e != ']') {
+            matched |= (c..=p[i + 2]).contains(&ch);
+            i += 3;
+        } else {
+            matched |= c == ch;
+            i += 1;
+        }
+        first = false;
+    }
+    None
+}
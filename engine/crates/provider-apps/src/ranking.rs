@@ -0,0 +1,225 @@
+//! Tiered matching of a normalised query against an app record, or anything
+//! else with a name and keywords.
+//!
+//! Tiers are tried from the most to the least specific: exact name, word
+//! prefix, acronym, all-tokens, then a substring and finally a fuzzy pass
+//! over the search blob. Each tier scores with its own weight.
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::Deserialize;
+
+use crate::norm;
+
+/// Per-word penalty inside a tier, so `code` ranks an app called "Code"
+/// above "Visual Studio Code". Capped well below the gap between tiers.
+const POSITION_PENALTY: f32 = 0.01;
+const MAX_POSITION_PENALTY: f32 = 0.05;
+
+/// Score awarded by each matching tier.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MatchWeights {
+    /// Query equals the whole name.
+    pub exact: f32,
+    /// Query is a prefix of the name starting at a word boundary.
+    pub word_prefix: f32,
+    /// Query is a prefix of the name's word initials, e.g. `vsc`.
+    pub acronym: f32,
+    /// Every query token matches a word by prefix or initials, e.g. `lo w`.
+    pub all_tokens: f32,
+    /// Plain substring of the search blob.
+    pub substring: f32,
+    /// Upper bound for fuzzy hits over the search blob. Every substring
+    /// is also a fuzzy hit, so keep this below `substring`.
+    pub fuzzy: f32,
+    /// Multiplier for exact and word-prefix hits on the generic name and
+    /// keywords rather than the name.
+    pub secondary: f32,
+}
+
+impl Default for MatchWeights {
+    fn default() -> Self {
+        Self {
+            exact: 1.0,
+            word_prefix: 0.9,
+            acronym: 0.8,
+            all_tokens: 0.7,
+            substring: 0.6,
+            fuzzy: 0.5,
+            secondary: 0.8,
+        }
+    }
+}
+
+/// Folded forms of an app's (or anything else's) names, computed once up
+/// front.
+#[derive(Debug, Clone, Default)]
+pub struct MatchTerms {
+    /// `norm(name)`.
+    name: String,
+    /// Name split on separators and camelCase humps, each word folded.
+    words: Vec<String>,
+    /// First char of every entry in `words`.
+    initials: String,
+    /// Folded generic name and keywords.
+    secondary: Vec<String>,
+}
+
+impl MatchTerms {
+    pub fn new(name: &str, generic_name: Option<&str>, keywords: &[String]) -> Self {
+        let words = split_words(name);
+        let initials = words.iter().filter_map(|w| w.chars().next()).collect();
+        let secondary = generic_name
+            .into_iter()
+            .chain(keywords.iter().map(String::as_str))
+            .map(norm)
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        Self {
+            name: norm(name),
+            words,
+            initials,
+            secondary,
+        }
+    }
+
+    /// Scores a query folded with [`crate::normalize_query`]. `blob` is the
+    /// folded text the substring and fuzzy tiers search.
+    pub fn score(
+        &self,
+        blob: &str,
+        q: &str,
+        weights: &MatchWeights,
+        matcher: &SkimMatcherV2,
+    ) -> Option<f32> {
+        let primary = name_tier(self, q, weights);
+        let secondary = self
+            .secondary
+            .iter()
+            .filter_map(|s| {
+                if s == q {
+                    Some(weights.exact)
+                } else {
+                    prefix_at_word(s, q).map(|i| weights.word_prefix - penalty(i))
+                }
+            })
+            .reduce(f32::max)
+            .map(|s| s * weights.secondary);
+
+        if let Some(best) = primary.into_iter().chain(secondary).reduce(f32::max) {
+            return Some(best);
+        }
+
+        if blob.contains(q) {
+            return Some(weights.substring);
+        }
+
+        matcher
+            .fuzzy_match(blob, q)
+            .map(|s| weights.fuzzy * (s as f32 / 100.0).clamp(0.1, 1.0))
+    }
+}
+
+fn name_tier(terms: &MatchTerms, q: &str, weights: &MatchWeights) -> Option<f32> {
+    if terms.name == q {
+        return Some(weights.exact);
+    }
+
+    // Whole-word prefixes of the name as written ("libreoffice w"), or of
+    // its camelCase-split form ("office").
+    let word_prefix = [
+        prefix_at_word(&terms.name, q),
+        prefix_at_word(&terms.words.join(" "), q),
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+    if let Some(i) = word_prefix {
+        return Some(weights.word_prefix - penalty(i));
+    }
+
+    if let Some(i) = acronym_at(&terms.initials, q) {
+        return Some(weights.acronym - penalty(i));
+    }
+
+    let tokens: Vec<&str> = q.split(' ').filter(|t| !t.is_empty()).collect();
+    if tokens.len() > 1 && tokens.iter().all(|t| token_matches(terms, t)) {
+        return Some(weights.all_tokens);
+    }
+
+    None
+}
+
+/// Index of the first word of `hay` from which `hay` starts with `q`.
+fn prefix_at_word(hay: &str, q: &str) -> Option<usize> {
+    let mut start = 0;
+    for (i, word) in hay.split(' ').enumerate() {
+        if hay[start..].starts_with(q) {
+            return Some(i);
+        }
+        start += word.len() + 1;
+    }
+    None
+}
+
+/// Index of the word whose initial starts a run of initials matching `q`.
+fn acronym_at(initials: &str, q: &str) -> Option<usize> {
+    if q.len() < 2 || q.contains(' ') {
+        return None;
+    }
+    (0..initials.len()).find(|&i| initials[i..].starts_with(q))
+}
+
+fn token_matches(terms: &MatchTerms, token: &str) -> bool {
+    terms.words.iter().any(|w| w.starts_with(token)) || acronym_at(&terms.initials, token).is_some()
+}
+
+fn penalty(word_index: usize) -> f32 {
+    (word_index as f32 * POSITION_PENALTY).min(MAX_POSITION_PENALTY)
+}
+
+/// Splits on anything that is not alphanumeric and on camelCase humps
+/// ("LibreOffice" -> libre, office; "VSCode" -> vs, code), folding each
+/// word the same way as `norm`.
+fn split_words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut raw: Vec<String> = Vec::new();
+    let mut cur = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !cur.is_empty() {
+                raw.push(std::mem::take(&mut cur));
+            }
+            continue;
+        }
+
+        if let Some(&prev) = i.checked_sub(1).and_then(|p| chars.get(p)) {
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let hump = ch.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next_lower));
+            if hump && !cur.is_empty() {
+                raw.push(std::mem::take(&mut cur));
+            }
+        }
+        cur.push(ch);
+    }
+    if !cur.is_empty() {
+        raw.push(cur);
+    }
+
+    // Folding can itself introduce spaces (e.g. CJK transliterations).
+    raw.iter()
+        .flat_map(|w| {
+            norm(w)
+                .split(' ')
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
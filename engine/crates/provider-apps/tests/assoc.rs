@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use lancea_provider_apps::{AppsProvider, LaunchArg, MimeApps, MimeDb};
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn file(name: &str) -> LaunchArg {
+    LaunchArg::Path(fixtures().join("files").join(name))
+}
+
+fn provider(lists: &[&str]) -> AppsProvider {
+    let lists: Vec<PathBuf> = lists
+        .iter()
+        .map(|l| fixtures().join("mimeapps").join(l))
+        .collect();
+
+    AppsProvider::from_dirs(&[fixtures().join("applications")])
+        .expect("scan fixtures")
+        .with_mime_db(MimeDb::from_dirs(&[fixtures().join("mime")]))
+        .with_mime_apps(MimeApps::from_files(&lists))
+}
+
+fn default_id(p: &AppsProvider, arg: &LaunchArg) -> Option<String> {
+    p.default_app(arg).map(|a| a.desktop_id.clone())
+}
+
+fn ids(p: &AppsProvider, arg: &LaunchArg) -> Vec<String> {
+    p.associations(arg)
+        .into_iter()
+        .map(|(_, a)| a.desktop_id.clone())
+        .collect()
+}
+
+#[test]
+fn mime_type_keys_are_the_fallback() {
+    let p = provider(&[]);
+
+    assert_eq!(default_id(&p, &file("photo.png")).as_deref(), Some("gimp"));
+    assert_eq!(default_id(&p, &file("notes.txt")).as_deref(), Some("code"));
+    assert_eq!(
+        default_id(&p, &LaunchArg::parse("https://example.org").unwrap()).as_deref(),
+        Some("firefox")
+    );
+}
+
+#[test]
+fn more_important_lists_pick_the_default() {
+    let p = provider(&["system.list"]);
+    assert_eq!(default_id(&p, &file("photo.png")).as_deref(), Some("gimp"));
+
+    let p = provider(&["user.list", "system.list"]);
+    assert_eq!(
+        default_id(&p, &file("photo.png")).as_deref(),
+        Some("org.example.Editeur")
+    );
+}
+
+#[test]
+fn uninstalled_defaults_are_skipped() {
+    let p = provider(&["user.list", "system.list"]);
+    assert_eq!(default_id(&p, &file("notes.txt")).as_deref(), Some("code"));
+}
+
+#[test]
+fn parent_type_defaults_apply_to_subtypes() {
+    // text/rust has no entry of its own; text/plain's default is used.
+    let p = provider(&["user.list", "system.list"]);
+    assert_eq!(default_id(&p, &file("main.rs")).as_deref(), Some("code"));
+
+    // An app added for text/rust itself beats text/plain's default.
+    let p = provider(&["child.list", "user.list", "system.list"]);
+    assert_eq!(
+        default_id(&p, &file("main.rs")).as_deref(),
+        Some("org.example.Editeur")
+    );
+    assert_eq!(default_id(&p, &file("notes.txt")).as_deref(), Some("code"));
+}
+
+#[test]
+fn added_and_declared_associations_are_listed() {
+    let p = provider(&["system.list"]);
+
+    // A default outranks the list's own removal of gimp; writer is added.
+    assert_eq!(
+        ids(&p, &file("photo.png")),
+        vec!["gimp", "libreoffice-writer"]
+    );
+    assert_eq!(
+        ids(&p, &file("notes.txt")),
+        vec!["org.example.Editeur", "code"]
+    );
+}
+
+#[test]
+fn removals_hide_less_important_associations_only() {
+    let p = provider(&["user.list", "system.list"]);
+
+    // user.list removes the association system.list adds.
+    assert!(!ids(&p, &file("notes.txt")).contains(&"org.example.Editeur".to_string()));
+
+    // system.list's removal can't undo user.list's addition.
+    let html = LaunchArg::Path(fixtures().join("files/index.html"));
+    assert!(ids(&p, &html).contains(&"code".to_string()));
+}
+
+#[test]
+fn open_with_search_flags_the_default() {
+    let p = provider(&["user.list", "system.list"]);
+    let path = fixtures().join("files/photo.png");
+    let results = p.search(&path.display().to_string());
+
+    let first = results.first().unwrap();
+    assert_eq!(first.title, "Éditeur Ærø");
+    assert_eq!(first.extras.as_ref().unwrap()["isDefault"], true);
+    assert!(
+        results[1..]
+            .iter()
+            .all(|r| r.extras.as_ref().unwrap()["isDefault"] == false)
+    );
+}
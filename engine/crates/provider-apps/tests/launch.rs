@@ -0,0 +1,212 @@
+use std::path::PathBuf;
+
+use lancea_provider_apps::{AppsProvider, LaunchArg, MimeApps, MimeDb};
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn file(name: &str) -> String {
+    fixtures().join("files").join(name).display().to_string()
+}
+
+fn fixture_provider() -> AppsProvider {
+    AppsProvider::from_dirs(&[fixtures().join("applications")])
+        .expect("scan fixtures")
+        .with_mime_db(MimeDb::from_dirs(&[fixtures().join("mime")]))
+        .with_mime_apps(MimeApps::default())
+}
+
+#[test]
+fn url_field_codes_get_file_urls() {
+    let p = fixture_provider();
+    let argv = p.launch_argv("apps:gimp", &[file("photo.png")]).unwrap();
+
+    assert_eq!(
+        argv,
+        vec![vec![
+            "gimp-2.10".to_string(),
+            format!("file://{}", file("photo.png")),
+        ]]
+    );
+}
+
+#[test]
+fn single_url_code_starts_one_instance_per_argument() {
+    let p = fixture_provider();
+    let args = [
+        "https://example.org/".to_string(),
+        "http://example.com/a".to_string(),
+    ];
+    let argv = p.launch_argv("apps:firefox", &args).unwrap();
+
+    assert_eq!(
+        argv,
+        vec![
+            vec!["firefox".to_string(), "https://example.org/".to_string()],
+            vec!["firefox".to_string(), "http://example.com/a".to_string()],
+        ]
+    );
+}
+
+#[test]
+fn file_list_code_takes_every_path() {
+    let p = fixture_provider();
+    let dir = fixtures().join("files/project").display().to_string();
+    let argv = p
+        .launch_argv("apps:code", &[dir.clone(), file("notes.txt")])
+        .unwrap();
+
+    assert_eq!(
+        argv,
+        vec![vec![
+            "/usr/share/code/code".to_string(),
+            dir,
+            file("notes.txt")
+        ]]
+    );
+}
+
+#[test]
+fn no_arguments_drops_field_codes() {
+    let p = fixture_provider();
+    assert_eq!(
+        p.launch_argv("apps:libreoffice-writer", &[]).unwrap(),
+        vec![vec!["libreoffice".to_string(), "--writer".to_string()]]
+    );
+}
+
+#[test]
+fn file_urls_become_paths_for_file_codes() {
+    let p = fixture_provider();
+    let url = format!("file://{}", file("main.rs"));
+    let argv = p.launch_argv("apps:org.example.Editeur", &[url]).unwrap();
+
+    assert_eq!(argv, vec![vec!["editeur".to_string(), file("main.rs")]]);
+}
+
+#[test]
+fn arguments_are_checked_against_mime_types() {
+    let p = fixture_provider();
+
+    assert!(p.launch_argv("apps:gimp", &[file("notes.txt")]).is_err());
+    // text/rust is a text/plain by way of the implicit text/* parent.
+    assert!(p.launch_argv("apps:code", &[file("main.rs")]).is_ok());
+    // Apps without MimeType= take whatever their Exec line can.
+    assert!(
+        p.launch_argv("apps:org.example.Editeur", &[file("photo.png")])
+            .is_ok()
+    );
+}
+
+#[test]
+fn unsuitable_arguments_are_rejected() {
+    let p = fixture_provider();
+
+    // No field code to put the file in.
+    assert!(
+        p.launch_argv("apps:org.gnome.Calculator", &[file("photo.png")])
+            .is_err()
+    );
+    // %f only takes local files.
+    assert!(
+        p.launch_argv("apps:org.example.Editeur", &["https://example.org/".into()])
+            .is_err()
+    );
+    // Relative paths mean nothing to the engine.
+    assert!(p.launch_argv("apps:code", &["notes.txt".into()]).is_err());
+}
+
+#[test]
+fn search_with_app_and_file_filters_and_carries_args() {
+    let p = fixture_provider();
+    let results = p.search(&format!("/apps gimp {}", file("photo.png")));
+
+    let first = results.first().unwrap();
+    assert_eq!(first.title, "GNU Image Manipulation Program");
+    let extras = first.extras.as_ref().unwrap();
+    assert_eq!(extras["args"], serde_json::json!([file("photo.png")]));
+
+    // Writer matches "writer" but cannot open a PNG.
+    let results = p.search(&format!("/apps writer {}", file("photo.png")));
+    assert!(results.is_empty());
+}
+
+#[test]
+fn file_alone_lists_apps_that_open_it() {
+    let p = fixture_provider();
+
+    let titles: Vec<String> = p
+        .search(&format!("/apps {}", file("photo.png")))
+        .into_iter()
+        .map(|r| r.title)
+        .collect();
+    assert_eq!(titles, vec!["GNU Image Manipulation Program"]);
+
+    // Exact MimeType= listings rank above inherited ones.
+    let results = p.search(&file("notes.txt"));
+    assert_eq!(results.first().unwrap().title, "Visual Studio Code");
+    let exact = results.first().unwrap().score;
+    let results = p.search(&file("main.rs"));
+    assert_eq!(results.first().unwrap().title, "Visual Studio Code");
+    assert!(results.first().unwrap().score < exact);
+}
+
+#[test]
+fn open_with_query_survives_spaces() {
+    let p = fixture_provider();
+    let path = fixtures().join("files/holiday photo.png");
+
+    let query = AppsProvider::open_with_query(&path).unwrap();
+    assert!(query.ends_with("/holiday%20photo.png"), "{query}");
+    let results = p.search(&query);
+    assert_eq!(results[0].title, "GNU Image Manipulation Program");
+    assert_eq!(
+        results[0].extras.as_ref().unwrap()["args"][0],
+        format!(
+            "file://{}",
+            fixtures().join("files/holiday%20photo.png").display()
+        )
+    );
+}
+
+#[test]
+fn missing_paths_stay_part_of_the_query() {
+    let p = fixture_provider();
+    assert!(p.search("/apps /no/such/file.png").is_empty());
+}
+
+#[test]
+fn colon_words_stay_part_of_the_query() {
+    for word in ["localhost:8080", "foo:bar", "c:file", "note:"] {
+        assert_eq!(LaunchArg::parse(word), None, "{word}");
+    }
+    assert!(LaunchArg::parse("mailto:me@example.org").is_some());
+    assert!(LaunchArg::parse("https://example.org").is_some());
+
+    // Not split off as a URL for Firefox to open, so nothing matches.
+    let p = fixture_provider();
+    assert!(p.search("/apps firefox localhost:8080").is_empty());
+    assert!(!p.search("/apps firefox https://example.org").is_empty());
+}
+
+#[test]
+fn case_sensitive_globs_and_directories() {
+    let db = MimeDb::from_dirs(&[fixtures().join("mime")]);
+
+    assert_eq!(
+        db.guess_path(&fixtures().join("files/project/Makefile"))
+            .as_deref(),
+        Some("text/x-makefile")
+    );
+    assert_eq!(
+        db.guess_path(&fixtures().join("files/project")).as_deref(),
+        Some("inode/directory")
+    );
+    assert!(db.is_a("image/x-png", "image/png"));
+    assert!(db.is_a(
+        "application/x-vnd.oasis.opendocument.text",
+        "application/zip"
+    ));
+    assert!(!db.is_a("image/png", "text/plain"));
+}
@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{fs, process, thread};
+
+use lancea_config::EngineConfig;
+use lancea_provider_apps::{AppsConfig, AppsProvider, LaunchStrategy, MimeApps, MimeDb};
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn file(name: &str) -> String {
+    fixtures().join("files").join(name).display().to_string()
+}
+
+fn stub() -> String {
+    fixtures().join("bin/record-argv").display().to_string()
+}
+
+fn provider(launcher: LaunchStrategy) -> AppsProvider {
+    AppsProvider::from_dirs(&[fixtures().join("applications")])
+        .expect("scan fixtures")
+        .with_mime_db(MimeDb::from_dirs(&[fixtures().join("mime")]))
+        .with_mime_apps(MimeApps::default())
+        .with_launch_strategy(launcher)
+}
+
+fn out_file(test: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("lancea-{test}-{}.argv", process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+/// Waits for the stub wrapper, which runs detached, to record its argv.
+fn recorded_argv(path: &PathBuf) -> Vec<String> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !path.exists() {
+        assert!(Instant::now() < deadline, "stub wrapper never ran");
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let txt = fs::read_to_string(path).unwrap();
+    let _ = fs::remove_file(path);
+    txt.lines().map(str::to_string).collect()
+}
+
+#[test]
+fn wrapper_receives_the_app_command_line() {
+    let out = out_file("wrapper");
+    let p = provider(LaunchStrategy::Wrapper {
+        command: vec![
+            stub(),
+            out.display().to_string(),
+            "--app={desktop_id}".into(),
+            "--name={name}".into(),
+            "{argv}".into(),
+            "--after".into(),
+        ],
+    });
+
+    p.execute_launch("apps:gimp", &[file("photo.png")]).unwrap();
+
+    assert_eq!(
+        recorded_argv(&out),
+        vec![
+            "--app=gimp".to_string(),
+            "--name=GNU Image Manipulation Program".into(),
+            "gimp-2.10".into(),
+            format!("file://{}", file("photo.png")),
+            "--after".into(),
+        ]
+    );
+}
+
+#[test]
+fn wrapper_without_argv_placeholder_gets_it_appended() {
+    let out = out_file("append");
+    let p = provider(LaunchStrategy::Wrapper {
+        command: vec![stub(), out.display().to_string()],
+    });
+
+    p.execute_launch("apps:org.gnome.Calculator", &[]).unwrap();
+
+    assert_eq!(recorded_argv(&out), vec!["gnome-calculator".to_string()]);
+}
+
+#[test]
+fn wrapper_runs_once_per_instance() {
+    let p = provider(LaunchStrategy::Wrapper {
+        command: vec![stub(), "/dev/null".into(), "{argv}".into()],
+    });
+
+    let argv = p
+        .launch_argv(
+            "apps:firefox",
+            &["https://a.example/".into(), "https://b.example/".into()],
+        )
+        .unwrap();
+    assert_eq!(argv.len(), 2);
+    assert!(argv.iter().all(|a| a[0] == stub()));
+}
+
+#[test]
+fn empty_wrapper_is_an_error() {
+    let p = provider(LaunchStrategy::Wrapper { command: vec![] });
+    assert!(p.launch_argv("apps:firefox", &[]).is_err());
+}
+
+#[test]
+fn systemd_scope_wraps_in_a_transient_unit() {
+    let p = provider(LaunchStrategy::SystemdScope);
+    let argv = p.launch_argv("apps:org.gnome.Calculator", &[]).unwrap();
+    let argv = &argv[0];
+
+    assert_eq!(argv[..3], ["systemd-run", "--user", "--scope"]);
+    let unit = argv.iter().find(|a| a.starts_with("--unit=")).unwrap();
+    assert!(unit.starts_with("--unit=app-lancea-org.gnome.Calculator-"));
+    assert!(unit.ends_with(".scope"));
+
+    let sep = argv.iter().position(|a| a == "--").unwrap();
+    assert_eq!(argv[sep + 1..], ["gnome-calculator"]);
+}
+
+#[test]
+fn strategy_is_read_from_engine_config() {
+    let config = EngineConfig::parse(
+        r#"
+        [apps.launch]
+        strategy = "wrapper"
+        command = ["uwsm", "app", "--", "{argv}"]
+
+        [apps.weights]
+        acronym = 0.5
+        "#,
+    )
+    .unwrap();
+    let apps: AppsConfig = config.section("apps").unwrap();
+
+    assert_eq!(apps.weights.acronym, 0.5);
+    let p = provider(LaunchStrategy::Direct).with_config(apps);
+    assert_eq!(
+        p.launch_argv("apps:org.gnome.Calculator", &[]).unwrap(),
+        vec![vec!["uwsm", "app", "--", "gnome-calculator"]]
+    );
+
+    let config = EngineConfig::parse("[apps.launch]\nstrategy = \"systemd-scope\"\n").unwrap();
+    let apps: AppsConfig = config.section("apps").unwrap();
+    assert_eq!(apps.launch, LaunchStrategy::SystemdScope);
+
+    let apps: AppsConfig = EngineConfig::default().section("apps").unwrap();
+    assert_eq!(apps.launch, LaunchStrategy::Direct);
+}
+
+#[test]
+fn commands_run_in_the_configured_terminal() {
+    let p = provider(LaunchStrategy::Direct).with_terminal(vec!["foot".into()]);
+    assert_eq!(
+        p.terminal_argv("ssh", "SSH", &["ssh".into(), "web1".into()])
+            .unwrap(),
+        ["foot", "ssh", "web1"]
+    );
+
+    let p = provider(LaunchStrategy::SystemdScope).with_terminal(vec!["kitty".into(), "--".into()]);
+    let argv = p
+        .terminal_argv("ssh", "SSH", &["ssh".into(), "web1".into()])
+        .unwrap();
+    assert!(argv.iter().any(|a| a.starts_with("--unit=app-lancea-ssh-")));
+    let sep = argv.iter().position(|a| a == "--").unwrap();
+    assert_eq!(argv[sep + 1..], ["kitty", "--", "ssh", "web1"]);
+}
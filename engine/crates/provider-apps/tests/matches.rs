@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use lancea_model::MatchRange;
+use lancea_provider_apps::AppsProvider;
+
+fn fixture_provider() -> AppsProvider {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/applications");
+    AppsProvider::from_dirs(&[dir]).expect("scan fixtures")
+}
+
+fn range(start: u32, end: u32) -> MatchRange {
+    MatchRange { start, end }
+}
+
+#[test]
+fn prefix_match_highlights_title_prefix() {
+    let p = fixture_provider();
+    let results = p.search("fire");
+
+    let first = results.first().unwrap();
+    assert_eq!(first.title, "Firefox");
+    assert_eq!(first.matches.as_ref().unwrap().title, vec![range(0, 4)]);
+}
+
+#[test]
+fn fuzzy_match_highlights_scattered_chars() {
+    let p = fixture_provider();
+    let results = p.search("vsc");
+
+    let code = results
+        .iter()
+        .find(|r| r.title == "Visual Studio Code")
+        .unwrap();
+    assert_eq!(
+        code.matches.as_ref().unwrap().title,
+        vec![range(0, 1), range(7, 8), range(14, 15)]
+    );
+}
+
+#[test]
+fn ranges_map_through_unicode_folding() {
+    let p = fixture_provider();
+
+    // "É" folds to "e": the range still covers exactly one source char.
+    let results = p.search("edit");
+    let editeur = results.iter().find(|r| r.title == "Éditeur Ærø").unwrap();
+    assert_eq!(editeur.matches.as_ref().unwrap().title, vec![range(0, 4)]);
+
+    // "Æ" folds to two chars; matching half of it highlights all of it.
+    let results = p.search("aer");
+    let editeur = results.iter().find(|r| r.title == "Éditeur Ærø").unwrap();
+    assert_eq!(editeur.matches.as_ref().unwrap().title, vec![range(8, 10)]);
+}
+
+#[test]
+fn subtitle_ranges_are_reported_when_subtitle_matches() {
+    let p = fixture_provider();
+    let results = p.search("browser");
+
+    let firefox = results.iter().find(|r| r.title == "Firefox").unwrap();
+    let matches = firefox.matches.as_ref().unwrap();
+    assert_eq!(matches.subtitle, Some(vec![range(4, 11)]));
+}
+
+#[test]
+fn nodisplay_entries_are_skipped() {
+    let p = fixture_provider();
+    assert!(p.search("hidden helper").is_empty());
+}
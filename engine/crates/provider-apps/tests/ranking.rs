@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use lancea_model::ResultItem;
+use lancea_provider_apps::{AppsProvider, MatchWeights};
+
+fn fixture_provider() -> AppsProvider {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/applications");
+    AppsProvider::from_dirs(&[dir]).expect("scan fixtures")
+}
+
+fn titles(results: &[ResultItem]) -> Vec<&str> {
+    results.iter().map(|r| r.title.as_str()).collect()
+}
+
+fn assert_first(p: &AppsProvider, query: &str, expected: &str) {
+    let results = p.search(query);
+    assert_eq!(
+        results.first().map(|r| r.title.as_str()),
+        Some(expected),
+        "query {query:?} ranked {:?}",
+        titles(&results)
+    );
+}
+
+#[test]
+fn exact_name_ranks_first() {
+    let p = fixture_provider();
+    assert_first(&p, "firefox", "Firefox");
+    assert_first(&p, "Terminal", "Terminal");
+}
+
+#[test]
+fn word_prefix_of_name() {
+    let p = fixture_provider();
+    assert_first(&p, "fire", "Firefox");
+    assert_first(&p, "visual", "Visual Studio Code");
+    assert_first(&p, "writer", "LibreOffice Writer");
+    assert_first(&p, "studio code", "Visual Studio Code");
+}
+
+#[test]
+fn word_prefix_prefers_earlier_words() {
+    let p = fixture_provider();
+    let results = p.search("calc");
+    assert_eq!(
+        titles(&results)[..2],
+        ["Calculator", "LibreOffice Calc"],
+        "ranked {:?}",
+        titles(&results)
+    );
+}
+
+#[test]
+fn camel_case_humps_are_words() {
+    let p = fixture_provider();
+    let results = p.search("office");
+    let top: Vec<&str> = titles(&results).into_iter().take(3).collect();
+    assert!(top.iter().all(|t| t.starts_with("LibreOffice")), "{top:?}");
+}
+
+#[test]
+fn acronym_matches_initials() {
+    let p = fixture_provider();
+    assert_first(&p, "vsc", "Visual Studio Code");
+    assert_first(&p, "gimp", "GNU Image Manipulation Program");
+}
+
+#[test]
+fn all_tokens_mix_prefixes_and_initials() {
+    let p = fixture_provider();
+    assert_first(&p, "lo w", "LibreOffice Writer");
+    assert_first(&p, "lo calc", "LibreOffice Calc");
+    assert_first(&p, "libre imp", "LibreOffice Impress");
+}
+
+#[test]
+fn generic_name_and_keywords_match_below_name() {
+    let p = fixture_provider();
+    assert_first(&p, "browser", "Firefox");
+    assert_first(&p, "spreadsheet", "LibreOffice Calc");
+    assert_first(&p, "shell", "Terminal");
+}
+
+#[test]
+fn fuzzy_and_substring_are_last_resort() {
+    let p = fixture_provider();
+    let results = p.search("fiefx");
+    assert_eq!(titles(&results).first(), Some(&"Firefox"));
+    assert!(results[0].score <= MatchWeights::default().fuzzy);
+}
+
+#[test]
+fn tiers_rank_in_order() {
+    let p = fixture_provider();
+    let w = MatchWeights::default();
+    let score = |q: &str, title: &str| {
+        p.search(q)
+            .into_iter()
+            .find(|r| r.title == title)
+            .map(|r| r.score)
+            .unwrap()
+    };
+
+    let exact = score("videos", "Videos");
+    let prefix = score("vid", "Videos");
+    let acronym = score("vsc", "Visual Studio Code");
+    let tokens = score("lo w", "LibreOffice Writer");
+    let substring = score("efox", "Firefox");
+    let fuzzy = score("vdeos", "Videos");
+
+    assert_eq!(exact, w.exact);
+    assert_eq!(substring, w.substring);
+    assert!(exact > prefix && prefix > acronym && acronym > tokens);
+    assert!(tokens > substring && substring > fuzzy);
+}
+
+#[test]
+fn weights_are_tunable() {
+    let weights = MatchWeights {
+        all_tokens: 0.25,
+        ..MatchWeights::default()
+    };
+    let p = fixture_provider().with_weights(weights);
+
+    let results = p.search("lo w");
+    let writer = results
+        .iter()
+        .find(|r| r.title == "LibreOffice Writer")
+        .unwrap();
+    assert_eq!(writer.score, 0.25);
+}
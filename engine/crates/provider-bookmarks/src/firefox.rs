@@ -0,0 +1,113 @@
+//! Firefox's `places.sqlite`. The browser keeps the database locked while
+//! it runs, so it is read from a copy, together with its write-ahead log
+//! so that recent changes aren't missed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use crate::Bookmark;
+
+/// `moz_bookmarks.type` of an actual bookmark, as opposed to a folder or
+/// separator.
+const TYPE_BOOKMARK: i64 = 1;
+
+/// Names for the built-in roots, whose stored titles are internal ones
+/// like `toolbar`.
+const ROOTS: &[(&str, &str)] = &[
+    ("menu________", "Bookmarks Menu"),
+    ("toolbar_____", "Bookmarks Toolbar"),
+    ("unfiled_____", "Other Bookmarks"),
+    ("mobile______", "Mobile Bookmarks"),
+];
+
+/// Holds tags, whose entries duplicate real bookmarks.
+const TAGS_ROOT: &str = "tags________";
+
+struct Row {
+    parent: i64,
+    kind: i64,
+    title: String,
+    guid: String,
+    url: Option<String>,
+}
+
+pub fn load(places: &Path) -> Result<Vec<Bookmark>> {
+    let copy = tempfile::tempdir().context("no temporary directory for places.sqlite")?;
+    let db = copy.path().join("places.sqlite");
+    fs::copy(places, &db).with_context(|| format!("failed to copy {}", places.display()))?;
+    let wal = wal_path(places);
+    if wal.exists() {
+        fs::copy(&wal, copy.path().join("places.sqlite-wal"))
+            .with_context(|| format!("failed to copy {}", wal.display()))?;
+    }
+
+    let conn = Connection::open(&db)?;
+    let mut stmt = conn.prepare(
+        "SELECT b.id, b.parent, b.type, IFNULL(b.title, ''), b.guid, p.url
+         FROM moz_bookmarks b LEFT JOIN moz_places p ON p.id = b.fk
+         ORDER BY b.parent, b.position",
+    )?;
+    let rows: Vec<(i64, Row)> = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get(0)?,
+                Row {
+                    parent: r.get(1)?,
+                    kind: r.get(2)?,
+                    title: r.get(3)?,
+                    guid: r.get(4)?,
+                    url: r.get(5)?,
+                },
+            ))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let by_id: HashMap<i64, &Row> = rows.iter().map(|(id, row)| (*id, row)).collect();
+    let mut out = Vec::new();
+    for (_, row) in &rows {
+        let (TYPE_BOOKMARK, Some(url)) = (row.kind, &row.url) else {
+            continue;
+        };
+        if let Some(path) = folder_path(&by_id, row.parent) {
+            let path: Vec<&str> = path.iter().map(String::as_str).collect();
+            out.push(Bookmark::new(&row.title, url, &path));
+        }
+    }
+    Ok(out)
+}
+
+/// `places.sqlite-wal` next to `places`.
+pub(crate) fn wal_path(places: &Path) -> std::path::PathBuf {
+    let mut name = places.as_os_str().to_os_string();
+    name.push("-wal");
+    name.into()
+}
+
+/// Folder titles from the root down to `id`, or `None` for tag folders.
+fn folder_path(by_id: &HashMap<i64, &Row>, mut id: i64) -> Option<Vec<String>> {
+    let mut path = Vec::new();
+    // Parent links form a tree, but don't trust a damaged file to.
+    for _ in 0..by_id.len() {
+        let Some(folder) = by_id.get(&id) else {
+            break;
+        };
+        if folder.guid == TAGS_ROOT {
+            return None;
+        }
+        match ROOTS.iter().find(|(guid, _)| *guid == folder.guid) {
+            Some((_, name)) => {
+                path.push(name.to_string());
+                break;
+            }
+            None if folder.parent == 0 => break,
+            None => path.push(folder.title.clone()),
+        }
+        id = folder.parent;
+    }
+    path.reverse();
+    Some(path)
+}
@@ -0,0 +1,343 @@
+//! Browser bookmarks from Chromium-family `Bookmarks` files and Firefox
+//! `places.sqlite` databases, opened with the default URL handler.
+
+mod chromium;
+mod firefox;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Preview, Provider, ResultItem, command_query};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use chromium::parse as parse_chromium;
+pub use firefox::load as load_firefox;
+
+const PROVIDER_ID: &str = "bookmarks";
+const KEY_PREFIX: &str = "bookmarks:";
+
+/// Chromium-family browsers' config directories, each holding profiles.
+const CHROMIUM_DIRS: &[&str] = &[
+    "google-chrome",
+    "google-chrome-beta",
+    "chromium",
+    "BraveSoftware/Brave-Browser",
+    "microsoft-edge",
+    "vivaldi",
+];
+
+/// The `[bookmarks]` table of the engine config. Paths are glob patterns
+/// and may start with `~/`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BookmarksConfig {
+    /// `Bookmarks` files; defaults to every profile of Chrome, Chromium,
+    /// Brave, Edge and Vivaldi.
+    pub chromium: Vec<String>,
+    /// `places.sqlite` files; defaults to every profile under
+    /// `~/.mozilla/firefox`.
+    pub firefox: Vec<String>,
+}
+
+impl Default for BookmarksConfig {
+    fn default() -> Self {
+        let config = dirs::config_dir().unwrap_or_else(|| PathBuf::from("~/.config"));
+        Self {
+            chromium: CHROMIUM_DIRS
+                .iter()
+                .map(|d| config.join(d).join("*/Bookmarks").display().to_string())
+                .collect(),
+            firefox: vec!["~/.mozilla/firefox/*/places.sqlite".into()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Browser {
+    Firefox,
+    Chromium,
+}
+
+impl Browser {
+    fn name(self) -> &'static str {
+        match self {
+            Browser::Firefox => "Firefox",
+            Browser::Chromium => "Chromium",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    /// The bookmark's name, or its URL when it has none.
+    pub title: String,
+    pub url: String,
+    /// Folder names from the root, e.g. `Bookmarks bar / Dev`.
+    pub folder: String,
+    pub browser: Option<Browser>,
+    /// The profile directory, without Firefox's random prefix.
+    pub profile: String,
+}
+
+impl Bookmark {
+    pub(crate) fn new(title: &str, url: &str, folder: &[&str]) -> Self {
+        Self {
+            title: if title.trim().is_empty() {
+                url.to_string()
+            } else {
+                title.trim().to_string()
+            },
+            url: url.to_string(),
+            folder: folder.join(" / "),
+            browser: None,
+            profile: String::new(),
+        }
+    }
+}
+
+/// Files and the mtimes they had when last read.
+type Fingerprint = Vec<(PathBuf, Option<SystemTime>)>;
+
+pub struct BookmarksProvider {
+    chromium: Vec<String>,
+    firefox: Vec<String>,
+    /// Bookmarks as of the last look at the profile files; reloaded when
+    /// one of them changes, appears or goes away.
+    cache: Mutex<Option<(Fingerprint, Arc<Vec<Bookmark>>)>>,
+    apps: Arc<AppsProvider>,
+}
+
+impl BookmarksProvider {
+    pub fn new(config: BookmarksConfig, apps: Arc<AppsProvider>) -> Self {
+        Self {
+            chromium: config.chromium,
+            firefox: config.firefox,
+            cache: Mutex::new(None),
+            apps,
+        }
+    }
+
+    fn files(&self) -> Vec<(Browser, PathBuf)> {
+        let mut out: Vec<(Browser, PathBuf)> = Vec::new();
+        for (browser, patterns) in [
+            (Browser::Firefox, &self.firefox),
+            (Browser::Chromium, &self.chromium),
+        ] {
+            for pattern in patterns {
+                out.extend(expand(pattern).into_iter().map(|p| (browser, p)));
+            }
+        }
+        out
+    }
+
+    /// Every bookmark, Firefox profiles first, each URL once.
+    pub fn bookmarks(&self) -> Arc<Vec<Bookmark>> {
+        let files = self.files();
+        let fingerprint: Fingerprint = files
+            .iter()
+            .flat_map(|(browser, path)| {
+                let wal = (*browser == Browser::Firefox).then(|| firefox::wal_path(path));
+                std::iter::once(path.clone()).chain(wal)
+            })
+            .map(|p| {
+                let modified = fs::metadata(&p).and_then(|m| m.modified()).ok();
+                (p, modified)
+            })
+            .collect();
+
+        let Ok(mut cache) = self.cache.lock() else {
+            return Arc::default();
+        };
+        if let Some((seen, bookmarks)) = cache.as_ref()
+            && *seen == fingerprint
+        {
+            return bookmarks.clone();
+        }
+
+        let mut all: Vec<Bookmark> = Vec::new();
+        for (browser, path) in &files {
+            // A profile that can't be read just contributes nothing.
+            let Ok(loaded) = load(*browser, path) else {
+                continue;
+            };
+            for mut b in loaded {
+                if b.url.starts_with("place:")
+                    || b.url.starts_with("javascript:")
+                    || all.iter().any(|seen| seen.url == b.url)
+                {
+                    continue;
+                }
+                b.browser = Some(*browser);
+                b.profile = profile_name(*browser, path);
+                all.push(b);
+            }
+        }
+
+        let all = Arc::new(all);
+        *cache = Some((fingerprint, all.clone()));
+        all
+    }
+
+    /// Every bookmark for an empty query; otherwise fuzzy matches on the
+    /// title, URL or folder, with ties kept in browser order.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &["/bookmarks", "/bm"]);
+        let bookmarks = self.bookmarks();
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let mut hits: Vec<(i64, usize, Vec<usize>)> = bookmarks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| {
+                if q.is_empty() {
+                    return Some((0, i, Vec::new()));
+                }
+                let by_title = matcher.fuzzy_indices(&b.title, q);
+                let by_other = [&b.url, &b.folder]
+                    .iter()
+                    .filter_map(|field| matcher.fuzzy_match(field, q))
+                    .max();
+                match by_title {
+                    Some((s, idx)) if by_other.is_none_or(|o| s >= o) => Some((s, i, idx)),
+                    _ => by_other.map(|o| (o, i, Vec::new())),
+                }
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, i, idx))| {
+                to_result_item(&bookmarks[i], 1.0 - rank as f32 / count, idx)
+            })
+            .collect()
+    }
+
+    fn find(&self, key: &str) -> Result<Bookmark> {
+        let url = key
+            .strip_prefix(KEY_PREFIX)
+            .with_context(|| format!("Not a bookmarks key: {key}"))?;
+        self.bookmarks()
+            .iter()
+            .find(|b| b.url == url)
+            .cloned()
+            .with_context(|| format!("No bookmark for {url}"))
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let b = self.find(key).ok()?;
+        let mut comment = location(&b);
+        if !b.folder.is_empty() {
+            comment = format!("{} · {comment}", b.folder);
+        }
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": b.title,
+                "comment": comment,
+                "snippet": b.url,
+            }),
+        })
+    }
+
+    pub fn execute_open(&self, key: &str) -> Result<()> {
+        let b = self.find(key)?;
+        self.apps.open_default(&[b.url])
+    }
+}
+
+fn load(browser: Browser, path: &Path) -> Result<Vec<Bookmark>> {
+    match browser {
+        Browser::Firefox => load_firefox(path),
+        Browser::Chromium => parse_chromium(&fs::read_to_string(path)?),
+    }
+}
+
+/// Files matching `pattern`, with a leading `~/` meaning the home
+/// directory.
+fn expand(pattern: &str) -> Vec<PathBuf> {
+    let pattern = match (pattern.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).display().to_string(),
+        _ => pattern.to_string(),
+    };
+    let Ok(paths) = glob::glob(&pattern) else {
+        return Vec::new();
+    };
+    paths.flatten().filter(|p| p.is_file()).collect()
+}
+
+/// `Default`, `Profile 1`, or `default-release` for Firefox's
+/// `x1y2z3ab.default-release`.
+fn profile_name(browser: Browser, path: &Path) -> String {
+    let dir = path
+        .parent()
+        .and_then(Path::file_name)
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match (browser, dir.split_once('.')) {
+        (Browser::Firefox, Some((_, name))) => name.to_string(),
+        _ => dir,
+    }
+}
+
+/// `Firefox (default-release)`.
+fn location(b: &Bookmark) -> String {
+    let browser = b.browser.map_or("", Browser::name);
+    if b.profile.is_empty() {
+        browser.to_string()
+    } else {
+        format!("{browser} ({})", b.profile)
+    }
+}
+
+fn to_result_item(b: &Bookmark, score: f32, indices: Vec<usize>) -> ResultItem {
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}", b.url),
+        title: b.title.clone(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": b.url,
+            "folder": b.folder,
+            "browser": location(b),
+            "copyText": b.url,
+            "defaultAction": "open",
+            "actions": ["open", "copy"],
+        })),
+        matches: (!indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(&b.title, indices),
+            subtitle: None,
+        }),
+    }
+}
+
+impl Provider for BookmarksProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "open" => self.execute_open(key).is_ok(),
+            "copy" => self.find(key).is_ok(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use lancea_model::Provider;
+use lancea_provider_bookmarks::{
+    BookmarksConfig, BookmarksProvider, Browser, load_firefox, parse_chromium,
+};
+use lancea_testkit::{no_apps, titles};
+use rusqlite::Connection;
+use tempfile::TempDir;
+
+const CHROMIUM: &str = r#"{
+  "checksum": "0",
+  "roots": {
+    "bookmark_bar": {
+      "name": "Bookmarks bar", "type": "folder", "children": [
+        { "name": "Rust docs", "type": "url", "url": "https://doc.rust-lang.org/std/" },
+        { "name": "Dev", "type": "folder", "children": [
+          { "name": "crates.io", "type": "url", "url": "https://crates.io/" },
+          { "name": "Duplicate of Firefox", "type": "url", "url": "https://news.ycombinator.com/" }
+        ] }
+      ]
+    },
+    "other": { "name": "Other bookmarks", "type": "folder", "children": [
+      { "name": "", "type": "url", "url": "https://example.org/untitled" }
+    ] },
+    "synced": { "name": "Mobile bookmarks", "type": "folder", "children": [] }
+  },
+  "sync_transaction_version": "7",
+  "version": 1
+}"#;
+
+/// Enough of Firefox's schema for the provider, with the built-in roots.
+fn create_places(path: &Path) -> Connection {
+    let conn = Connection::open(path).unwrap();
+    conn.execute_batch(
+        "PRAGMA journal_mode = WAL;
+         PRAGMA wal_autocheckpoint = 0;
+         CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT);
+         CREATE TABLE moz_bookmarks (
+           id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, parent INTEGER,
+           position INTEGER, title TEXT, guid TEXT);
+         INSERT INTO moz_bookmarks VALUES
+           (1, 2, NULL, 0, 0, '', 'root________'),
+           (2, 2, NULL, 1, 0, 'menu', 'menu________'),
+           (3, 2, NULL, 1, 1, 'toolbar', 'toolbar_____'),
+           (4, 2, NULL, 1, 2, 'tags', 'tags________'),
+           (5, 2, NULL, 1, 3, 'unfiled', 'unfiled_____'),
+           (10, 2, NULL, 3, 0, 'News', 'folder000001'),
+           (11, 1, 1, 10, 0, 'Hacker News', 'bookmark0001'),
+           (12, 1, 2, 2, 0, 'Firefox Add-ons', 'bookmark0002'),
+           (13, 1, 3, 2, 1, 'Most Visited', 'bookmark0003'),
+           (14, 2, NULL, 4, 0, 'reading', 'tagfolder001'),
+           (15, 1, 1, 14, 0, NULL, 'tagentry0001');
+         INSERT INTO moz_places VALUES
+           (1, 'https://news.ycombinator.com/'),
+           (2, 'https://addons.mozilla.org/'),
+           (3, 'place:sort=8&maxResults=10');",
+    )
+    .unwrap();
+    conn
+}
+
+fn setup() -> (TempDir, BookmarksProvider, Connection) {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    let chrome = root.join("chromium/Default");
+    fs::create_dir_all(&chrome).unwrap();
+    fs::write(chrome.join("Bookmarks"), CHROMIUM).unwrap();
+
+    let profile = root.join("firefox/x1y2z3ab.default-release");
+    fs::create_dir_all(&profile).unwrap();
+    let conn = create_places(&profile.join("places.sqlite"));
+
+    let config = BookmarksConfig {
+        chromium: vec![format!("{}/chromium/*/Bookmarks", root.display())],
+        firefox: vec![format!("{}/firefox/*/places.sqlite", root.display())],
+    };
+    (dir, BookmarksProvider::new(config, no_apps()), conn)
+}
+
+#[test]
+fn parses_chromium_folders() {
+    let bookmarks = parse_chromium(CHROMIUM).unwrap();
+    let summary: Vec<(&str, &str)> = bookmarks
+        .iter()
+        .map(|b| (b.title.as_str(), b.folder.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Rust docs", "Bookmarks bar"),
+            ("crates.io", "Bookmarks bar / Dev"),
+            ("Duplicate of Firefox", "Bookmarks bar / Dev"),
+            ("https://example.org/untitled", "Other bookmarks"),
+        ]
+    );
+    assert!(parse_chromium("{}").is_err());
+}
+
+#[test]
+fn reads_firefox_while_the_browser_holds_its_lock() {
+    let (dir, _p, conn) = setup();
+    // Like a running Firefox: nobody else may read the original.
+    conn.execute_batch("PRAGMA locking_mode = EXCLUSIVE; BEGIN EXCLUSIVE;")
+        .unwrap();
+
+    let places = dir
+        .path()
+        .join("firefox/x1y2z3ab.default-release/places.sqlite");
+    let bookmarks = load_firefox(&places).unwrap();
+    let summary: Vec<(&str, &str)> = bookmarks
+        .iter()
+        .map(|b| (b.title.as_str(), b.folder.as_str()))
+        .collect();
+    // Tag entries are left out; `place:` queries are dropped by the provider.
+    assert_eq!(
+        summary,
+        vec![
+            ("Firefox Add-ons", "Bookmarks Menu"),
+            ("Most Visited", "Bookmarks Menu"),
+            ("Hacker News", "Bookmarks Toolbar / News"),
+        ]
+    );
+}
+
+#[test]
+fn searches_titles_urls_and_folders() {
+    let (_dir, p, _conn) = setup();
+
+    assert_eq!(
+        titles(&p, ""),
+        vec![
+            "Firefox Add-ons",
+            "Hacker News",
+            "Rust docs",
+            "crates.io",
+            "https://example.org/untitled",
+        ]
+    );
+    assert_eq!(titles(&p, "/bm rust docs"), vec!["Rust docs"]);
+    assert_eq!(titles(&p, "/bookmarks crates")[0], "crates.io");
+    assert_eq!(titles(&p, "ycombinator"), vec!["Hacker News"]);
+    assert_eq!(titles(&p, "bookmarks bar / dev"), vec!["crates.io"]);
+
+    let results = p.search("hacker");
+    let item = &results[0];
+    assert_eq!(item.key, "bookmarks:https://news.ycombinator.com/");
+    let extras = item.extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], "https://news.ycombinator.com/");
+    assert_eq!(extras["folder"], "Bookmarks Toolbar / News");
+    assert_eq!(extras["browser"], "Firefox (default-release)");
+    assert_eq!(extras["copyText"], "https://news.ycombinator.com/");
+    assert_eq!(extras["defaultAction"], "open");
+    let ranges = &item.matches.as_ref().unwrap().title;
+    assert_eq!((ranges[0].start, ranges[0].end), (0, 6));
+
+    let preview = p.preview("bookmarks:https://crates.io/").unwrap();
+    assert_eq!(preview.data["title"], "crates.io");
+    assert_eq!(
+        preview.data["comment"],
+        "Bookmarks bar / Dev · Chromium (Default)"
+    );
+    assert_eq!(preview.data["snippet"], "https://crates.io/");
+}
+
+#[test]
+fn reloads_when_profiles_change() {
+    let (dir, p, conn) = setup();
+    assert_eq!(titles(&p, "").len(), 5);
+
+    // Firefox writes go to the write-ahead log first.
+    conn.execute_batch(
+        "INSERT INTO moz_places VALUES (4, 'https://lwn.net/');
+         INSERT INTO moz_bookmarks VALUES (16, 1, 4, 10, 1, 'LWN', 'bookmark0004');",
+    )
+    .unwrap();
+    assert_eq!(titles(&p, "lwn"), vec!["LWN"]);
+
+    let chrome = dir.path().join("chromium/Default/Bookmarks");
+    fs::write(&chrome, CHROMIUM.replace("Rust docs", "Rust std")).unwrap();
+    // Make sure the mtime moves even on coarse-grained filesystems.
+    let later = fs::metadata(&chrome).unwrap().modified().unwrap() + Duration::from_secs(5);
+    fs::File::options()
+        .write(true)
+        .open(&chrome)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    assert_eq!(titles(&p, "rust"), vec!["Rust std"]);
+
+    fs::remove_dir_all(dir.path().join("chromium")).unwrap();
+    assert_eq!(titles(&p, "").len(), 3);
+    assert!(
+        p.bookmarks()
+            .iter()
+            .all(|b| b.browser == Some(Browser::Firefox))
+    );
+}
+
+#[test]
+fn actions_check_the_bookmark_still_exists() {
+    let (_dir, p, _conn) = setup();
+
+    assert!(p.execute("copy", "bookmarks:https://crates.io/"));
+    assert!(!p.execute("copy", "bookmarks:https://gone.example/"));
+    // No apps are installed, so there is no URL handler.
+    assert!(!p.execute("open", "bookmarks:https://crates.io/"));
+    assert!(!p.execute("open", "apps:firefox.desktop"));
+}
@@ -0,0 +1,397 @@
+//! A small recursive-descent evaluator for calculator queries.
+//!
+//! Precedence, loosest first: `|`, `xor`, `&`, `<<` `>>`, `+` `-`,
+//! `*` `/` `%` `mod`, unary `-` `+` `~`, then `^` / `**` (right
+//! associative, so `-2^2` is `-4` and `2^3^2` is `512`). Bitwise operators
+//! only accept integral operands.
+
+use anyhow::{Result, anyhow, bail};
+
+/// Largest magnitude at which every integer is exact in an `f64`.
+const MAX_EXACT_INT: f64 = 9_007_199_254_740_992.0;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// Evaluates `expr`, e.g. `2*(3+4)`, `sqrt(2)/2` or `0xff & 0b1010`.
+pub fn evaluate(expr: &str) -> Result<f64> {
+    let tokens = tokenize(expr)?;
+    if tokens.is_empty() {
+        bail!("empty expression");
+    }
+
+    let mut parser = Parser { tokens, pos: 0 };
+    let value = parser.bit_or()?;
+    if let Some(tok) = parser.peek() {
+        bail!("unexpected {tok:?}");
+    }
+    if !value.is_finite() {
+        bail!("result is not a finite number");
+    }
+    Ok(value)
+}
+
+/// Whether `x` can be shown as an exact integer (and in hex, bin, oct).
+pub fn as_integer(x: f64) -> Option<i64> {
+    (x.fract() == 0.0 && x.abs() <= MAX_EXACT_INT).then_some(x as i64)
+}
+
+/// Renders a result with at most 12 significant digits, without trailing
+/// zeros, so `0.1 + 0.2` shows as `0.3`.
+pub fn format_number(x: f64) -> String {
+    if let Some(i) = as_integer(x) {
+        return i.to_string();
+    }
+
+    let magnitude = x.abs().log10().floor() as i32;
+    if (-6..15).contains(&magnitude) {
+        let decimals = (11 - magnitude).max(0) as usize;
+        let s = format!("{x:.decimals$}");
+        let s = s.trim_end_matches('0').trim_end_matches('.');
+        return if s == "-0" { "0".into() } else { s.into() };
+    }
+
+    let s = format!("{x:.11e}");
+    match s.split_once('e') {
+        Some((mantissa, exp)) => {
+            let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+            format!("{mantissa}e{exp}")
+        }
+        None => s,
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    while let Some(&c) = chars.get(i) {
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+            let (num, next) = number(&chars, i)?;
+            out.push(Token::Num(num));
+            i = next;
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+            {
+                i += 1;
+            }
+            let ident: String = chars[start..i].iter().collect();
+            out.push(match ident.to_lowercase().as_str() {
+                "xor" => Token::Op("xor"),
+                "mod" => Token::Op("%"),
+                lower => Token::Ident(lower.to_string()),
+            });
+            continue;
+        }
+
+        let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+        let (tok, len) = match (two.as_str(), c) {
+            ("**", _) => (Token::Op("^"), 2),
+            ("<<", _) => (Token::Op("<<"), 2),
+            (">>", _) => (Token::Op(">>"), 2),
+            (_, '+') => (Token::Op("+"), 1),
+            (_, '-' | '−') => (Token::Op("-"), 1),
+            (_, '*' | '×') => (Token::Op("*"), 1),
+            (_, '/' | '÷') => (Token::Op("/"), 1),
+            (_, '%') => (Token::Op("%"), 1),
+            (_, '^') => (Token::Op("^"), 1),
+            (_, '&') => (Token::Op("&"), 1),
+            (_, '|') => (Token::Op("|"), 1),
+            (_, '~') => (Token::Op("~"), 1),
+            (_, '(') => (Token::LParen, 1),
+            (_, ')') => (Token::RParen, 1),
+            (_, ',') => (Token::Comma, 1),
+            _ => bail!("unexpected character {c:?}"),
+        };
+        out.push(tok);
+        i += len;
+    }
+
+    Ok(out)
+}
+
+/// Reads a literal starting at `chars[start]`: decimal with optional
+/// fraction and exponent, or `0x`, `0b`, `0o` integers. `_` separators are
+/// allowed between digits.
+fn number(chars: &[char], start: usize) -> Result<(f64, usize)> {
+    let radix = match (
+        chars[start],
+        chars.get(start + 1).map(|c| c.to_ascii_lowercase()),
+    ) {
+        ('0', Some('x')) => Some(16),
+        ('0', Some('b')) => Some(2),
+        ('0', Some('o')) => Some(8),
+        _ => None,
+    };
+
+    if let Some(radix) = radix {
+        let mut i = start + 2;
+        while chars.get(i).is_some_and(|c| c.is_digit(radix) || *c == '_') {
+            i += 1;
+        }
+        let digits: String = chars[start + 2..i].iter().filter(|c| **c != '_').collect();
+        let value = u64::from_str_radix(&digits, radix)
+            .map_err(|_| anyhow!("invalid base-{radix} literal"))?;
+        return Ok((value as f64, i));
+    }
+
+    let digits_from = |mut i: usize| {
+        while chars
+            .get(i)
+            .is_some_and(|c| c.is_ascii_digit() || *c == '_')
+        {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = digits_from(start);
+    if chars.get(i) == Some(&'.') {
+        i = digits_from(i + 1);
+    }
+    // Only treat `e` as an exponent when digits follow, so `2e` stays an
+    // error rather than silently becoming `2`.
+    if chars.get(i).is_some_and(|c| *c == 'e' || *c == 'E') {
+        let mut j = i + 1;
+        if chars.get(j).is_some_and(|c| *c == '+' || *c == '-') {
+            j += 1;
+        }
+        if chars.get(j).is_some_and(char::is_ascii_digit) {
+            i = digits_from(j);
+        }
+    }
+
+    let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+    let value = text
+        .parse()
+        .map_err(|_| anyhow!("invalid number {text:?}"))?;
+    Ok((value, i))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    /// Consumes the next token if it is one of `ops`.
+    fn eat_op(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, tok: Token) -> Result<()> {
+        match self.next() {
+            Some(t) if t == tok => Ok(()),
+            Some(t) => bail!("expected {tok:?}, found {t:?}"),
+            None => bail!("expected {tok:?}"),
+        }
+    }
+
+    fn bit_or(&mut self) -> Result<f64> {
+        let mut lhs = self.bit_xor()?;
+        while self.eat_op(&["|"]).is_some() {
+            let rhs = self.bit_xor()?;
+            lhs = (integer(lhs)? | integer(rhs)?) as f64;
+        }
+        Ok(lhs)
+    }
+
+    fn bit_xor(&mut self) -> Result<f64> {
+        let mut lhs = self.bit_and()?;
+        while self.eat_op(&["xor"]).is_some() {
+            let rhs = self.bit_and()?;
+            lhs = (integer(lhs)? ^ integer(rhs)?) as f64;
+        }
+        Ok(lhs)
+    }
+
+    fn bit_and(&mut self) -> Result<f64> {
+        let mut lhs = self.shift()?;
+        while self.eat_op(&["&"]).is_some() {
+            let rhs = self.shift()?;
+            lhs = (integer(lhs)? & integer(rhs)?) as f64;
+        }
+        Ok(lhs)
+    }
+
+    fn shift(&mut self) -> Result<f64> {
+        let mut lhs = self.additive()?;
+        while let Some(op) = self.eat_op(&["<<", ">>"]) {
+            let rhs = integer(self.additive()?)?;
+            let by = u32::try_from(rhs)
+                .ok()
+                .filter(|b| *b < 64)
+                .ok_or_else(|| anyhow!("shift amount out of range"))?;
+            let value = integer(lhs)?;
+            lhs = if op == "<<" { value << by } else { value >> by } as f64;
+        }
+        Ok(lhs)
+    }
+
+    fn additive(&mut self) -> Result<f64> {
+        let mut lhs = self.term()?;
+        while let Some(op) = self.eat_op(&["+", "-"]) {
+            let rhs = self.term()?;
+            lhs = if op == "+" { lhs + rhs } else { lhs - rhs };
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<f64> {
+        let mut lhs = self.unary()?;
+        while let Some(op) = self.eat_op(&["*", "/", "%"]) {
+            let rhs = self.unary()?;
+            lhs = match op {
+                "*" => lhs * rhs,
+                _ if rhs == 0.0 => bail!("division by zero"),
+                "/" => lhs / rhs,
+                _ => lhs % rhs,
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<f64> {
+        match self.eat_op(&["-", "+", "~"]) {
+            Some("-") => Ok(-self.unary()?),
+            Some("~") => Ok(!integer(self.unary()?)? as f64),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<f64> {
+        let base = self.atom()?;
+        if self.eat_op(&["^"]).is_some() {
+            let exp = self.unary()?;
+            return Ok(base.powf(exp));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(n),
+            Some(Token::LParen) => {
+                let value = self.bit_or()?;
+                self.expect(Token::RParen)?;
+                Ok(value)
+            }
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+                self.pos += 1;
+                let args = self.args()?;
+                call(&name, &args)
+            }
+            Some(Token::Ident(name)) => constant(&name),
+            Some(tok) => bail!("unexpected {tok:?}"),
+            None => bail!("unexpected end of expression"),
+        }
+    }
+
+    /// Comma-separated arguments up to and including the closing paren.
+    fn args(&mut self) -> Result<Vec<f64>> {
+        let mut args = Vec::new();
+        if self.peek() == Some(&Token::RParen) {
+            self.pos += 1;
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.bit_or()?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RParen) => return Ok(args),
+                _ => bail!("expected ',' or ')'"),
+            }
+        }
+    }
+}
+
+fn integer(x: f64) -> Result<i64> {
+    as_integer(x).ok_or_else(|| anyhow!("bitwise operators need integers, got {x}"))
+}
+
+fn constant(name: &str) -> Result<f64> {
+    match name {
+        "pi" | "π" => Ok(std::f64::consts::PI),
+        "tau" | "τ" => Ok(std::f64::consts::TAU),
+        "e" => Ok(std::f64::consts::E),
+        _ => bail!("unknown constant {name:?}"),
+    }
+}
+
+fn call(name: &str, args: &[f64]) -> Result<f64> {
+    let one = |f: fn(f64) -> f64| match args {
+        [x] => Ok(f(*x)),
+        _ => bail!("{name}() takes one argument"),
+    };
+
+    match name {
+        "sqrt" => one(f64::sqrt),
+        "cbrt" => one(f64::cbrt),
+        "abs" => one(f64::abs),
+        "exp" => one(f64::exp),
+        "ln" => one(f64::ln),
+        "log" | "log10" => one(f64::log10),
+        "log2" => one(f64::log2),
+        "sin" => one(f64::sin),
+        "cos" => one(f64::cos),
+        "tan" => one(f64::tan),
+        "asin" => one(f64::asin),
+        "acos" => one(f64::acos),
+        "atan" => one(f64::atan),
+        "sinh" => one(f64::sinh),
+        "cosh" => one(f64::cosh),
+        "tanh" => one(f64::tanh),
+        "floor" => one(f64::floor),
+        "ceil" => one(f64::ceil),
+        "round" => one(f64::round),
+        "trunc" => one(f64::trunc),
+        "pow" => match args {
+            [b, e] => Ok(b.powf(*e)),
+            _ => bail!("pow() takes two arguments"),
+        },
+        "min" | "max" if !args.is_empty() => {
+            let pick = if name == "min" { f64::min } else { f64::max };
+            Ok(args.iter().copied().reduce(pick).unwrap_or_default())
+        }
+        "min" | "max" => bail!("{name}() needs at least one argument"),
+        _ => bail!("unknown function {name:?}"),
+    }
+}
@@ -0,0 +1,103 @@
+//! Calculator provider: evaluates arithmetic typed straight into the
+//! launcher, either after `/calc` or when the registry detects a `calc`
+//! intent.
+
+mod eval;
+
+use anyhow::Result;
+use lancea_model::{Preview, Provider, ResultItem, command_query};
+
+pub use eval::{evaluate, format_number};
+
+const PROVIDER_ID: &str = "calc";
+const KEY_PREFIX: &str = "calc:";
+
+pub struct CalcProvider;
+
+impl CalcProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// A single result for a well-formed expression; nothing while the
+    /// user is still typing one.
+    pub fn search(&self, query: &str) -> Vec<ResultItem> {
+        // A leading `=` forces calculator mode, as `/calc` does.
+        let expr = command_query(query, &["/calc", "="]);
+        let Ok(value) = evaluate(expr) else {
+            return Vec::new();
+        };
+        let text = format_number(value);
+
+        vec![ResultItem {
+            key: format!("{KEY_PREFIX}{expr}"),
+            title: text.clone(),
+            provider_id: PROVIDER_ID.into(),
+            score: 1.0,
+            extras: Some(serde_json::json!({
+                "expression": expr,
+                "value": text,
+                "copyText": text,
+                "defaultAction": "copy",
+            })),
+            matches: None,
+        }]
+    }
+
+    /// The result in every base it can be written in.
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let expr = key.strip_prefix(KEY_PREFIX)?;
+        let value = evaluate(expr).ok()?;
+
+        let mut data = serde_json::json!({
+            "title": format_number(value),
+            "expression": expr,
+        });
+        if let Some(i) = eval::as_integer(value) {
+            data["hex"] = format!("{i:#x}").into();
+            data["oct"] = format!("{i:#o}").into();
+            data["bin"] = format!("{i:#b}").into();
+        }
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data,
+        })
+    }
+
+    /// Only checks the key still evaluates.
+    pub fn execute_copy(&self, key: &str) -> Result<bool> {
+        let expr = key
+            .strip_prefix(KEY_PREFIX)
+            .ok_or_else(|| anyhow::anyhow!("Not a calc key: {key}"))?;
+        evaluate(expr)?;
+        Ok(true)
+    }
+}
+
+impl Default for CalcProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Provider for CalcProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "copy" => self.execute_copy(key).unwrap_or(false),
+            _ => false,
+        }
+    }
+}
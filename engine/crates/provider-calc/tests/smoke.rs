@@ -0,0 +1,81 @@
+use lancea_model::Provider;
+use lancea_provider_calc::{CalcProvider, evaluate, format_number};
+
+fn eval(expr: &str) -> String {
+    format_number(evaluate(expr).unwrap_or_else(|e| panic!("{expr}: {e}")))
+}
+
+#[test]
+fn respects_precedence_and_parentheses() {
+    assert_eq!(eval("2*(3+4)"), "14");
+    assert_eq!(eval("2+3*4"), "14");
+    assert_eq!(eval("10 - 4 - 3"), "3");
+    assert_eq!(eval("-2^2"), "-4");
+    assert_eq!(eval("2^3^2"), "512");
+    assert_eq!(eval("2 ** -1"), "0.5");
+    assert_eq!(eval("7 mod 3"), "1");
+}
+
+#[test]
+fn functions_and_constants() {
+    assert_eq!(eval("sqrt(16) + abs(-2)"), "6");
+    assert_eq!(eval("max(1, 5, 3)"), "5");
+    assert_eq!(eval("round(pi * 100)"), "314");
+    assert_eq!(eval("log(1000)"), "3");
+    assert_eq!(eval("cos(0)"), "1");
+}
+
+#[test]
+fn radix_literals_and_bitwise_ops() {
+    assert_eq!(eval("0xff"), "255");
+    assert_eq!(eval("0b1010 | 0b0101"), "15");
+    assert_eq!(eval("0o17 & 0xc"), "12");
+    assert_eq!(eval("6 xor 3"), "5");
+    assert_eq!(eval("1 << 4 + 1"), "32");
+    assert_eq!(eval("~0"), "-1");
+    assert!(evaluate("1.5 & 1").is_err());
+}
+
+#[test]
+fn formats_without_float_noise() {
+    assert_eq!(eval("0.1 + 0.2"), "0.3");
+    assert_eq!(eval("1/3"), "0.333333333333");
+    assert_eq!(eval("2^70"), "1.18059162072e21");
+}
+
+#[test]
+fn rejects_malformed_input() {
+    for expr in ["", "2 +", "(1", "1/0", "foo(2)", "2e", "vsc"] {
+        assert!(evaluate(expr).is_err(), "{expr} should not evaluate");
+    }
+}
+
+#[test]
+fn search_returns_copyable_result() {
+    let p = CalcProvider::new();
+
+    let results = p.search("2*(3+4)");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, "14");
+    assert_eq!(results[0].key, "calc:2*(3+4)");
+    let extras = results[0].extras.as_ref().unwrap();
+    assert_eq!(extras["copyText"], "14");
+    assert_eq!(extras["defaultAction"], "copy");
+
+    assert_eq!(p.search("/calc 1+1")[0].title, "2");
+    assert_eq!(p.search("= 1+1")[0].title, "2");
+    assert!(p.search("2 +").is_empty());
+
+    assert!(p.execute("copy", &results[0].key));
+    assert!(!p.execute("copy", "calc:2 +"));
+}
+
+#[test]
+fn preview_shows_other_bases() {
+    let p = CalcProvider::new();
+    let preview = p.preview("calc:0xff").unwrap();
+
+    assert_eq!(preview.data["title"], "255");
+    assert_eq!(preview.data["hex"], "0xff");
+    assert_eq!(preview.data["bin"], "0b11111111");
+}
@@ -0,0 +1,243 @@
+//! Unicode characters: `/char arrow` (or `/u`) searches names and aliases
+//! in the Unicode Character Database, and `/u U+2192`, `/u 0x2192` or
+//! `/u →` looks one up. Ranked by the search emoji use.
+//!
+//! The database is read from the system on first use, e.g. Debian's
+//! `unicode-data` package; `data_dir` points elsewhere.
+
+mod entities;
+mod ucd;
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use lancea_model::{Matches, Preview, Provider, ResultItem, command_query};
+use lancea_provider_emoji::search::{self, Searchable, normalize_query};
+use serde::Deserialize;
+
+pub use ucd::{CharRecord, Ucd};
+
+const PROVIDER_ID: &str = "char";
+const KEY_PREFIX: &str = "char:";
+const PREFIXES: [&str; 2] = ["/char", "/u"];
+
+/// Where distributions install `UnicodeData.txt` and `NamesList.txt`.
+const DATA_DIRS: [&str; 3] = [
+    "/usr/share/unicode",
+    "/usr/share/unicode/ucd",
+    "/usr/share/unicode-data",
+];
+
+/// The `[char]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CharConfig {
+    /// The directory holding `UnicodeData.txt`; searched for when unset.
+    pub data_dir: Option<PathBuf>,
+    pub max_results: usize,
+}
+
+impl Default for CharConfig {
+    fn default() -> Self {
+        Self {
+            data_dir: None,
+            max_results: 50,
+        }
+    }
+}
+
+impl Searchable for CharRecord {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn codes(&self) -> &[String] {
+        &self.entities
+    }
+
+    fn keywords(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+pub struct CharProvider {
+    config: CharConfig,
+    /// Loaded on the first search; empty when there is no database.
+    ucd: OnceLock<Ucd>,
+}
+
+impl CharProvider {
+    pub fn new(config: CharConfig) -> Self {
+        Self {
+            config,
+            ucd: OnceLock::new(),
+        }
+    }
+
+    pub fn ucd(&self) -> &Ucd {
+        self.ucd.get_or_init(|| {
+            let dirs = match &self.config.data_dir {
+                Some(dir) => vec![dir.clone()],
+                None => DATA_DIRS.iter().map(PathBuf::from).collect(),
+            };
+            dirs.iter()
+                .find_map(|dir| Ucd::load(dir).ok())
+                .unwrap_or_default()
+        })
+    }
+
+    /// The character a codepoint or the character itself stands for, then
+    /// characters whose name, alias or HTML entity name matches.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let query = command_query(raw_query, &PREFIXES);
+        let q = normalize_query(query);
+        if q.is_empty() {
+            return Vec::new();
+        }
+        let ucd = self.ucd();
+
+        let exact = parse_codepoint(query).and_then(|c| ucd.get(c));
+        let mut items: Vec<ResultItem> = exact
+            .iter()
+            .map(|rec| self.to_result_item(rec, 1.0, None))
+            .collect();
+        let limit = self.config.max_results.saturating_sub(items.len());
+        items.extend(
+            search::search(&ucd.chars, &q, limit)
+                .into_iter()
+                .filter(|hit| exact.as_ref() != Some(hit.record))
+                .map(|hit| self.to_result_item(hit.record, hit.score, hit.matches)),
+        );
+        items
+    }
+
+    fn to_result_item(&self, rec: &CharRecord, score: f32, matches: Option<Matches>) -> ResultItem {
+        let subtitle = match self.ucd().block(rec.codepoint) {
+            Some(block) => format!("{} · {block}", codepoint(rec.codepoint)),
+            None => codepoint(rec.codepoint),
+        };
+        let glyph = glyph(rec.codepoint);
+
+        ResultItem {
+            key: format!("{KEY_PREFIX}{:04X}", rec.codepoint),
+            title: rec.name.clone(),
+            provider_id: PROVIDER_ID.into(),
+            score,
+            extras: Some(serde_json::json!({
+                "subtitle": subtitle,
+                "glyph": display_glyph(rec),
+                "copyText": glyph,
+                "copyTexts": {
+                    "copy_glyph": glyph,
+                    "copy_codepoint": codepoint(rec.codepoint),
+                    "copy_html": html_entity(rec),
+                    "copy_rust": rust_escape(rec.codepoint),
+                },
+                "defaultAction": "copy_glyph",
+                "actions": ["copy_glyph", "copy_codepoint", "copy_html", "copy_rust"],
+            })),
+            matches,
+        }
+    }
+
+    fn record(&self, key: &str) -> Option<CharRecord> {
+        let hex = key.strip_prefix(KEY_PREFIX)?;
+        self.ucd().get(u32::from_str_radix(hex, 16).ok()?)
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let rec = self.record(key)?;
+        let ch = char::from_u32(rec.codepoint)?;
+        let utf8: Vec<String> = ch.to_string().bytes().map(|b| format!("{b:02X}")).collect();
+        let mut lines = vec![
+            format!("Codepoint: {}", codepoint(rec.codepoint)),
+            format!("Category: {}", rec.category),
+            format!("UTF-8: {}", utf8.join(" ")),
+            format!("HTML: {}", html_entity(&rec)),
+            format!("Rust: {}", rust_escape(rec.codepoint)),
+        ];
+        if !rec.aliases.is_empty() {
+            lines.push(format!("Also: {}", rec.aliases.join(", ")));
+        }
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "glyph": display_glyph(&rec),
+                "title": rec.name,
+                "comment": self.ucd().block(rec.codepoint),
+                "snippet": lines.join("\n"),
+            }),
+        })
+    }
+}
+
+impl Provider for CharProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "copy_glyph" | "copy_codepoint" | "copy_html" | "copy_rust" => {
+                self.record(key).is_some()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `U+2192`, `0x2192` or a single character.
+fn parse_codepoint(query: &str) -> Option<u32> {
+    let mut chars = query.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(ch as u32);
+    }
+    let hex = ["U+", "u+", "0x", "0X"]
+        .iter()
+        .find_map(|p| query.strip_prefix(p))?;
+    if hex.is_empty() || hex.len() > 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .filter(|c| char::from_u32(*c).is_some())
+}
+
+fn codepoint(c: u32) -> String {
+    format!("U+{c:04X}")
+}
+
+fn glyph(c: u32) -> String {
+    char::from_u32(c).map(String::from).unwrap_or_default()
+}
+
+/// What to show for the character: combining marks on a dotted circle,
+/// C0 controls as their control pictures.
+fn display_glyph(rec: &CharRecord) -> String {
+    match (rec.category.as_str(), rec.codepoint) {
+        ("Mn" | "Me", c) => format!("\u{25CC}{}", glyph(c)),
+        ("Cc", c @ 0..=0x1F) => glyph(0x2400 + c),
+        ("Cc", 0x7F) => glyph(0x2421),
+        (_, c) => glyph(c),
+    }
+}
+
+fn html_entity(rec: &CharRecord) -> String {
+    match rec.entities.first() {
+        Some(name) => format!("&{name};"),
+        None => format!("&#x{:X};", rec.codepoint),
+    }
+}
+
+fn rust_escape(c: u32) -> String {
+    format!("\\u{{{c:X}}}")
+}
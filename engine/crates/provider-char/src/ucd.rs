@@ -0,0 +1,243 @@
+//! The Unicode Character Database files `/char` reads: names and categories
+//! from `UnicodeData.txt`, aliases and block names from `NamesList.txt`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharRecord {
+    pub codepoint: u32,
+    pub name: String,
+    /// General category, like `Sm` or `Mn`.
+    pub category: String,
+    /// Other names: informal aliases, corrections, the Unicode 1 name.
+    pub aliases: Vec<String>,
+    /// HTML 4 entity names.
+    pub entities: Vec<String>,
+}
+
+/// A range given as `<Name, First>` and `<Name, Last>` lines, whose
+/// characters aren't listed one by one.
+#[derive(Debug, Clone)]
+struct Range {
+    first: u32,
+    last: u32,
+    label: String,
+    category: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Ucd {
+    pub chars: Vec<CharRecord>,
+    ranges: Vec<Range>,
+    /// `(first, last, name)`, in order.
+    blocks: Vec<(u32, u32, String)>,
+}
+
+impl Ucd {
+    /// Reads `UnicodeData.txt` from `dir`, and `NamesList.txt` too where
+    /// it is there.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join("UnicodeData.txt");
+        let data = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        let names_list = fs::read_to_string(dir.join("NamesList.txt")).unwrap_or_default();
+        Ok(Self::parse(&data, &names_list))
+    }
+
+    pub fn parse(unicode_data: &str, names_list: &str) -> Self {
+        let NamesList {
+            mut aliases,
+            blocks,
+        } = parse_names_list(names_list);
+        let mut chars = Vec::new();
+        let mut ranges = Vec::new();
+        let mut first: Option<(u32, String)> = None;
+
+        for line in unicode_data.lines() {
+            let fields: Vec<&str> = line.split(';').collect();
+            if fields.len() < 11 {
+                continue;
+            }
+            let Ok(codepoint) = u32::from_str_radix(fields[0], 16) else {
+                continue;
+            };
+            let (name, category) = (fields[1], fields[2]);
+
+            if let Some(label) = name
+                .strip_prefix('<')
+                .and_then(|n| n.strip_suffix(", First>"))
+            {
+                first = Some((codepoint, label.to_string()));
+                continue;
+            }
+            if name.ends_with(", Last>") {
+                if let Some((start, label)) = first.take() {
+                    ranges.push(Range {
+                        first: start,
+                        last: codepoint,
+                        label,
+                        category: category.to_string(),
+                    });
+                }
+                continue;
+            }
+
+            let mut names = aliases.remove(&codepoint).unwrap_or_default();
+            let unicode1 = fields[10];
+            if !unicode1.is_empty() && !names.iter().any(|n| n == unicode1) {
+                names.push(unicode1.to_string());
+            }
+            // Controls are `<control>`; their aliases say what they are.
+            let name = if name.starts_with('<') && !names.is_empty() {
+                names.remove(0)
+            } else {
+                name.to_string()
+            };
+            chars.push(CharRecord {
+                codepoint,
+                name,
+                category: category.to_string(),
+                aliases: names,
+                entities: entity(codepoint).map(String::from).into_iter().collect(),
+            });
+        }
+
+        Self {
+            chars,
+            ranges,
+            blocks,
+        }
+    }
+
+    /// The character at `codepoint`, including those of ranges such as
+    /// CJK ideographs and Hangul syllables, whose names are derived.
+    /// Surrogates and private use characters have none.
+    pub fn get(&self, codepoint: u32) -> Option<CharRecord> {
+        if let Ok(i) = self.chars.binary_search_by_key(&codepoint, |c| c.codepoint) {
+            return Some(self.chars[i].clone());
+        }
+        let range = self
+            .ranges
+            .iter()
+            .find(|r| (r.first..=r.last).contains(&codepoint))?;
+        Some(CharRecord {
+            codepoint,
+            name: range_name(&range.label, codepoint)?,
+            category: range.category.clone(),
+            aliases: Vec::new(),
+            entities: Vec::new(),
+        })
+    }
+
+    pub fn block(&self, codepoint: u32) -> Option<&str> {
+        self.blocks
+            .iter()
+            .find(|(first, last, _)| (*first..=*last).contains(&codepoint))
+            .map(|(_, _, name)| name.as_str())
+    }
+}
+
+/// What `/char` takes from `NamesList.txt`.
+#[derive(Debug)]
+struct NamesList {
+    /// Aliases by codepoint.
+    aliases: HashMap<u32, Vec<String>>,
+    /// `(first, last, name)`, in order.
+    blocks: Vec<(u32, u32, String)>,
+}
+
+/// Aliases by codepoint, and the blocks, from `NamesList.txt`, whose
+/// fields are separated by tabs and whose annotations start with one
+/// (shown here as spaces):
+///
+/// ```text
+/// @@    2190    Arrows    21FF
+/// 2192    RIGHTWARDS ARROW
+///     = z notation total function
+/// ```
+///
+/// `=` lines are informal aliases, `%` lines formal ones; the other
+/// annotations are left out.
+fn parse_names_list(text: &str) -> NamesList {
+    let mut aliases: HashMap<u32, Vec<String>> = HashMap::new();
+    let mut blocks = Vec::new();
+    let mut current = None;
+
+    for line in text.lines() {
+        if let Some(block) = line.strip_prefix("@@\t") {
+            let parts: Vec<&str> = block.split('\t').collect();
+            if let [first, name, last] = parts[..]
+                && let (Ok(first), Ok(last)) = (
+                    u32::from_str_radix(first, 16),
+                    u32::from_str_radix(last, 16),
+                )
+            {
+                blocks.push((first, last, name.to_string()));
+            }
+        } else if let Some(note) = line.strip_prefix('\t') {
+            if let (Some(codepoint), Some(alias)) = (
+                current,
+                note.strip_prefix("= ").or_else(|| note.strip_prefix("% ")),
+            ) {
+                aliases
+                    .entry(codepoint)
+                    .or_default()
+                    .push(alias.trim().to_string());
+            }
+        } else {
+            current = line
+                .split_once('\t')
+                .and_then(|(hex, _)| u32::from_str_radix(hex, 16).ok());
+        }
+    }
+    NamesList { aliases, blocks }
+}
+
+fn entity(codepoint: u32) -> Option<&'static str> {
+    let entities = crate::entities::ENTITIES;
+    entities
+        .binary_search_by_key(&codepoint, |(c, _)| *c)
+        .ok()
+        .map(|i| entities[i].1)
+}
+
+/// The name of a character in a range, as Unicode derives it.
+fn range_name(label: &str, codepoint: u32) -> Option<String> {
+    if label.starts_with("CJK Ideograph") {
+        Some(format!("CJK UNIFIED IDEOGRAPH-{codepoint:04X}"))
+    } else if label.starts_with("Hangul Syllable") {
+        hangul_syllable_name(codepoint)
+    } else if label.contains("Surrogate") || label.contains("Private Use") {
+        None
+    } else {
+        // Tangut ideographs and the like.
+        Some(format!("{}-{codepoint:04X}", label.to_uppercase()))
+    }
+}
+
+/// Hangul syllables are named after their jamo.
+fn hangul_syllable_name(codepoint: u32) -> Option<String> {
+    const LEAD: [&str; 19] = [
+        "G", "GG", "N", "D", "DD", "R", "M", "B", "BB", "S", "SS", "", "J", "JJ", "C", "K", "T",
+        "P", "H",
+    ];
+    const VOWEL: [&str; 21] = [
+        "A", "AE", "YA", "YAE", "EO", "E", "YEO", "YE", "O", "WA", "WAE", "OE", "YO", "U", "WEO",
+        "WE", "WI", "YU", "EU", "YI", "I",
+    ];
+    const TRAIL: [&str; 28] = [
+        "", "G", "GG", "GS", "N", "NJ", "NH", "D", "L", "LG", "LM", "LB", "LS", "LT", "LP", "LH",
+        "M", "B", "BS", "S", "SS", "NG", "J", "C", "K", "T", "P", "H",
+    ];
+    let s = codepoint.checked_sub(0xAC00)? as usize;
+    let (l, v, t) = (s / 588, s % 588 / 28, s % 28);
+    Some(format!(
+        "HANGUL SYLLABLE {}{}{}",
+        LEAD.get(l)?,
+        VOWEL[v],
+        TRAIL[t]
+    ))
+}
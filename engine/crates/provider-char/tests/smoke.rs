@@ -0,0 +1,116 @@
+use std::fs;
+
+use lancea_model::Provider;
+use lancea_provider_char::{CharConfig, CharProvider};
+use lancea_testkit::titles;
+
+const UNICODE_DATA: &str = "\
+0009;<control>;Cc;0;S;;;;;N;CHARACTER TABULATION;;;;
+0026;AMPERSAND;Po;0;ON;;;;;N;;;;;
+0041;LATIN CAPITAL LETTER A;Lu;0;L;;;;;N;;;;0061;
+0301;COMBINING ACUTE ACCENT;Mn;230;NSM;;;;;N;NON-SPACING ACUTE;;;;
+2190;LEFTWARDS ARROW;Sm;0;ON;;;;;N;LEFT ARROW;;;;
+2192;RIGHTWARDS ARROW;Sm;0;ON;;;;;N;RIGHT ARROW;;;;
+21D2;RIGHTWARDS DOUBLE ARROW;Sm;0;ON;;;;;N;RIGHT DOUBLE ARROW;;;;
+2500;BOX DRAWINGS LIGHT HORIZONTAL;So;0;ON;;;;;N;FORMS LIGHT HORIZONTAL;;;;
+4E00;<CJK Ideograph, First>;Lo;0;L;;;;;N;;;;;
+9FFF;<CJK Ideograph, Last>;Lo;0;L;;;;;N;;;;;
+AC00;<Hangul Syllable, First>;Lo;0;L;;;;;N;;;;;
+D7A3;<Hangul Syllable, Last>;Lo;0;L;;;;;N;;;;;
+E000;<Private Use, First>;Co;0;L;;;;;N;;;;;
+F8FF;<Private Use, Last>;Co;0;L;;;;;N;;;;;
+";
+
+const NAMES_LIST: &str = "\
+@@\t0000\tC0 Controls and Basic Latin\t007F
+0009\t<control>
+\t= CHARACTER TABULATION
+\t= horizontal tabulation (HT), tab
+0026\tAMPERSAND
+@@\t2190\tArrows\t21FF
+2192\tRIGHTWARDS ARROW
+\t= z notation total function
+\tx 21E8 rightwards white arrow
+21D2\tRIGHTWARDS DOUBLE ARROW
+\t* implies
+";
+
+fn setup() -> (tempfile::TempDir, CharProvider) {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("UnicodeData.txt"), UNICODE_DATA).unwrap();
+    fs::write(dir.path().join("NamesList.txt"), NAMES_LIST).unwrap();
+    let p = CharProvider::new(CharConfig {
+        data_dir: Some(dir.path().to_path_buf()),
+        ..CharConfig::default()
+    });
+    (dir, p)
+}
+
+#[test]
+fn searches_names_aliases_and_entities() {
+    let (_dir, p) = setup();
+
+    assert_eq!(
+        titles(&p, "/char rightwards"),
+        vec!["RIGHTWARDS ARROW", "RIGHTWARDS DOUBLE ARROW"]
+    );
+    assert_eq!(titles(&p, "/u total function"), vec!["RIGHTWARDS ARROW"]);
+    assert_eq!(titles(&p, "/u right arrow")[0], "RIGHTWARDS ARROW");
+    assert_eq!(titles(&p, "/char box")[0], "BOX DRAWINGS LIGHT HORIZONTAL");
+    assert_eq!(titles(&p, "/u tab"), vec!["CHARACTER TABULATION"]);
+    // HTML entity names match whole, like emoji shortcodes.
+    let rarr = p.search("/u rarr");
+    assert_eq!(rarr[0].title, "RIGHTWARDS ARROW");
+    assert_eq!(rarr[0].score, 1.0);
+    assert!(p.search("/u").is_empty());
+}
+
+#[test]
+fn looks_up_codepoints_and_characters() {
+    let (_dir, p) = setup();
+
+    for q in ["/u U+2192", "/u u+2192", "/char 0x2192", "/u →"] {
+        let items = p.search(q);
+        assert_eq!(items[0].title, "RIGHTWARDS ARROW", "{q}");
+        assert_eq!(items[0].key, "char:2192");
+    }
+    assert_eq!(titles(&p, "/u U+4E2D"), vec!["CJK UNIFIED IDEOGRAPH-4E2D"]);
+    assert_eq!(titles(&p, "/u U+D55C"), vec!["HANGUL SYLLABLE HAN"]);
+    assert!(p.search("/u U+E000").is_empty());
+    assert!(p.search("/u U+110000").is_empty());
+}
+
+#[test]
+fn offers_every_way_to_copy_it() {
+    let (_dir, p) = setup();
+
+    let items = p.search("/u U+2192");
+    let extras = items[0].extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], "U+2192 · Arrows");
+    assert_eq!(extras["copyText"], "→");
+    assert_eq!(extras["copyTexts"]["copy_codepoint"], "U+2192");
+    assert_eq!(extras["copyTexts"]["copy_html"], "&rarr;");
+    assert_eq!(extras["copyTexts"]["copy_rust"], "\\u{2192}");
+
+    let extras = p.search("/u U+21D2")[0].extras.clone().unwrap();
+    assert_eq!(extras["copyTexts"]["copy_html"], "&rArr;");
+    let extras = p.search("/u U+2500")[0].extras.clone().unwrap();
+    assert_eq!(extras["copyTexts"]["copy_html"], "&#x2500;");
+    let extras = p.search("/u U+0301")[0].extras.clone().unwrap();
+    assert_eq!(extras["glyph"], "\u{25CC}\u{301}");
+    assert_eq!(extras["copyText"], "\u{301}");
+
+    let preview = p.preview("char:2192").unwrap();
+    assert_eq!(preview.data["comment"], "Arrows");
+    assert_eq!(
+        preview.data["snippet"],
+        "Codepoint: U+2192\nCategory: Sm\nUTF-8: E2 86 92\nHTML: &rarr;\n\
+         Rust: \\u{2192}\nAlso: z notation total function, RIGHT ARROW"
+    );
+
+    for action in ["copy_glyph", "copy_codepoint", "copy_html", "copy_rust"] {
+        assert!(p.execute(action, "char:2192"));
+    }
+    assert!(!p.execute("copy_glyph", "char:E000"));
+    assert!(!p.execute("type", "char:2192"));
+}
@@ -0,0 +1,129 @@
+//! The clipboard history itself: newest first, pinned entries exempt from
+//! eviction, optionally mirrored to a JSON file.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lancea_model::{Stored, load_state, save_state, unix_now};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipEntry {
+    pub id: u64,
+    pub text: String,
+    #[serde(default)]
+    pub pinned: bool,
+    /// When the text was last copied, as Unix seconds.
+    pub copied_at: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct ClipHistory {
+    /// Newest first.
+    entries: Vec<ClipEntry>,
+    next_id: u64,
+    path: Option<PathBuf>,
+}
+
+impl ClipHistory {
+    /// An in-memory history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the history kept at `path`, which need not exist yet, and saves
+    /// every change back to it.
+    pub fn open(path: &Path) -> Result<Self> {
+        let stored: Stored<ClipEntry> =
+            load_state(path).with_context(|| format!("load {}", path.display()))?;
+
+        Ok(Self {
+            next_id: stored.entries.iter().map(|e| e.id + 1).max().unwrap_or(0),
+            entries: stored.entries,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    pub fn entries(&self) -> &[ClipEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ClipEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Adds `text` as the newest entry. With `dedupe`, an existing entry
+    /// with the same text moves to the front instead, keeping its pin.
+    /// Unpinned entries beyond `max_entries` are dropped, oldest first.
+    pub fn push(&mut self, text: &str, dedupe: bool, max_entries: usize) -> Result<u64> {
+        let existing = dedupe
+            .then(|| self.entries.iter().position(|e| e.text == text))
+            .flatten();
+        let mut entry = match existing {
+            Some(i) => self.entries.remove(i),
+            None => {
+                self.next_id += 1;
+                ClipEntry {
+                    id: self.next_id - 1,
+                    text: text.to_string(),
+                    pinned: false,
+                    copied_at: 0,
+                }
+            }
+        };
+        entry.copied_at = unix_now();
+        let id = entry.id;
+        self.entries.insert(0, entry);
+
+        let mut unpinned = 0;
+        self.entries.retain(|e| {
+            unpinned += usize::from(!e.pinned);
+            e.pinned || unpinned <= max_entries
+        });
+
+        self.save()?;
+        Ok(id)
+    }
+
+    /// Marks `id` as just copied again, moving it to the front.
+    pub fn touch(&mut self, id: u64) -> Result<bool> {
+        let Some(i) = self.entries.iter().position(|e| e.id == id) else {
+            return Ok(false);
+        };
+        let mut entry = self.entries.remove(i);
+        entry.copied_at = unix_now();
+        self.entries.insert(0, entry);
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn set_pinned(&mut self, id: u64, pinned: bool) -> Result<bool> {
+        let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) else {
+            return Ok(false);
+        };
+        entry.pinned = pinned;
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn remove(&mut self, id: u64) -> Result<bool> {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        if self.entries.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Mirrors the history to its file, when it has one.
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let stored = Stored {
+            entries: self.entries.clone(),
+        };
+        save_state(path, &stored).with_context(|| format!("save {}", path.display()))
+    }
+}
@@ -0,0 +1,263 @@
+//! Clipboard history. The UI reports every clipboard change through the
+//! engine's `RecordClipboard` method; `/clip` searches what was recorded and
+//! copies entries back.
+
+mod history;
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Preview, Provider, ResultItem, command_query};
+use serde::Deserialize;
+
+pub use history::{ClipEntry, ClipHistory};
+
+const PROVIDER_ID: &str = "clip";
+const KEY_PREFIX: &str = "clip:";
+
+/// Titles are the first line of an entry, cut to this many chars.
+const TITLE_CHARS: usize = 80;
+const PREVIEW_CHARS: usize = 2000;
+
+/// The `[clip]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClipConfig {
+    /// Unpinned entries to keep.
+    pub max_entries: usize,
+    /// Text longer than this many bytes is not recorded.
+    pub max_bytes: Option<usize>,
+    /// Copying text already in the history moves it to the top instead of
+    /// adding it again.
+    pub dedupe: bool,
+    /// Keep the history across restarts.
+    pub persist: bool,
+    /// Defaults to `$XDG_STATE_HOME/lancea/clipboard.json`.
+    pub file: Option<PathBuf>,
+    /// Clipboard contents offering any of these MIME types are not
+    /// recorded. The default skips what password managers mark as secret.
+    pub exclude_mime_types: Vec<String>,
+}
+
+impl Default for ClipConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 200,
+            max_bytes: None,
+            dedupe: true,
+            persist: true,
+            file: None,
+            exclude_mime_types: vec!["x-kde-passwordManagerHint".into()],
+        }
+    }
+}
+
+pub struct ClipProvider {
+    history: Mutex<ClipHistory>,
+    config: ClipConfig,
+}
+
+impl ClipProvider {
+    /// Loads the persisted history, starting empty if it can't be read.
+    pub fn new(config: ClipConfig) -> Self {
+        let path = config.persist.then(|| {
+            config
+                .file
+                .clone()
+                .or_else(|| dirs::state_dir().map(|d| d.join("lancea").join("clipboard.json")))
+        });
+        let history = path
+            .flatten()
+            .and_then(|p| ClipHistory::open(&p).ok())
+            .unwrap_or_default();
+
+        Self::with_history(history, config)
+    }
+
+    pub fn with_history(history: ClipHistory, config: ClipConfig) -> Self {
+        Self {
+            history: Mutex::new(history),
+            config,
+        }
+    }
+
+    /// Records new clipboard text. Returns whether it was kept: empty text,
+    /// text over the byte cap and excluded MIME types are not.
+    pub fn record(&self, text: &str, mime_types: &[String]) -> Result<bool> {
+        if text.trim().is_empty()
+            || self.config.max_bytes.is_some_and(|max| text.len() > max)
+            || mime_types
+                .iter()
+                .any(|m| self.config.exclude_mime_types.contains(m))
+        {
+            return Ok(false);
+        }
+
+        self.lock()?
+            .push(text, self.config.dedupe, self.config.max_entries)?;
+        Ok(true)
+    }
+
+    /// Pinned entries first, then newest first; a query fuzzy-matches the
+    /// whole text.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &["/clip"]);
+        let Ok(history) = self.lock() else {
+            return Vec::new();
+        };
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let mut hits: Vec<(i64, &ClipEntry)> = history
+            .entries()
+            .iter()
+            .filter_map(|e| {
+                if q.is_empty() {
+                    return Some((0, e));
+                }
+                matcher.fuzzy_match(&e.text, q).map(|s| (s, e))
+            })
+            .collect();
+        // Stable, so recency breaks ties.
+        hits.sort_by_key(|(score, e)| (!e.pinned, -score));
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, e))| {
+                let title = title(&e.text);
+                let matches = (!q.is_empty())
+                    .then(|| matcher.fuzzy_indices(&title, q))
+                    .flatten()
+                    .map(|(_, idx)| Matches {
+                        title: MatchRange::from_char_indices(&title, idx),
+                        subtitle: None,
+                    });
+                to_result_item(e, title, 1.0 - rank as f32 / count, matches)
+            })
+            .collect()
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let id = parse_key(key).ok()?;
+        let history = self.lock().ok()?;
+        let entry = history.get(id)?;
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": title(&entry.text),
+                "comment": describe(entry),
+                "snippet": entry.text.chars().take(PREVIEW_CHARS).collect::<String>(),
+                "timestamp": entry.copied_at,
+            }),
+        })
+    }
+
+    /// Moves the entry back to the top.
+    pub fn execute_copy(&self, key: &str) -> Result<bool> {
+        self.lock()?.touch(parse_key(key)?)
+    }
+
+    pub fn execute_pin(&self, key: &str, pinned: bool) -> Result<bool> {
+        self.lock()?.set_pinned(parse_key(key)?, pinned)
+    }
+
+    pub fn execute_delete(&self, key: &str) -> Result<bool> {
+        self.lock()?.remove(parse_key(key)?)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, ClipHistory>> {
+        self.history
+            .lock()
+            .map_err(|_| anyhow::anyhow!("clipboard history lock poisoned"))
+    }
+}
+
+fn parse_key(key: &str) -> Result<u64> {
+    key.strip_prefix(KEY_PREFIX)
+        .and_then(|id| id.parse().ok())
+        .with_context(|| format!("Not a clip key: {key}"))
+}
+
+/// The first non-blank line, trimmed and shortened.
+fn title(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or_default();
+    if line.chars().count() <= TITLE_CHARS {
+        return line.to_string();
+    }
+    let mut short: String = line.chars().take(TITLE_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+/// `3 lines · 120 chars · Pinned`.
+fn describe(entry: &ClipEntry) -> String {
+    let lines = entry.text.lines().count().max(1);
+    let chars = entry.text.chars().count();
+    let mut out = format!(
+        "{lines} {} · {chars} {}",
+        if lines == 1 { "line" } else { "lines" },
+        if chars == 1 { "char" } else { "chars" },
+    );
+    if entry.pinned {
+        out.push_str(" · Pinned");
+    }
+    out
+}
+
+fn to_result_item(
+    entry: &ClipEntry,
+    title: String,
+    score: f32,
+    matches: Option<Matches>,
+) -> ResultItem {
+    let pin = if entry.pinned { "unpin" } else { "pin" };
+
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}", entry.id),
+        title,
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": describe(entry),
+            "pinned": entry.pinned,
+            "timestamp": entry.copied_at,
+            "copyText": entry.text,
+            "defaultAction": "copy",
+            "actions": ["copy", pin, "delete"],
+        })),
+        matches,
+    }
+}
+
+impl Provider for ClipProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        let done = match action {
+            "copy" => self.execute_copy(key),
+            "pin" => self.execute_pin(key, true),
+            "unpin" => self.execute_pin(key, false),
+            "delete" => self.execute_delete(key),
+            _ => return false,
+        };
+        done.unwrap_or(false)
+    }
+}
@@ -0,0 +1,145 @@
+use std::os::unix::fs::PermissionsExt;
+
+use lancea_model::Provider;
+use lancea_provider_clip::{ClipConfig, ClipHistory, ClipProvider};
+use lancea_testkit::titles;
+
+fn provider(config: ClipConfig) -> ClipProvider {
+    ClipProvider::with_history(ClipHistory::new(), config)
+}
+
+fn key_of(p: &ClipProvider, title: &str) -> String {
+    p.search("")
+        .into_iter()
+        .find(|r| r.title == title)
+        .unwrap()
+        .key
+}
+
+#[test]
+fn records_newest_first_and_dedupes() {
+    let p = provider(ClipConfig::default());
+    for text in ["alpha", "beta", "gamma", "alpha"] {
+        assert!(p.record(text, &[]).unwrap());
+    }
+    assert_eq!(titles(&p, ""), vec!["alpha", "gamma", "beta"]);
+
+    let p = provider(ClipConfig {
+        dedupe: false,
+        ..ClipConfig::default()
+    });
+    for text in ["alpha", "beta", "alpha"] {
+        p.record(text, &[]).unwrap();
+    }
+    assert_eq!(titles(&p, ""), vec!["alpha", "beta", "alpha"]);
+}
+
+#[test]
+fn skips_blank_oversized_and_secret_text() {
+    let p = provider(ClipConfig {
+        max_bytes: Some(8),
+        ..ClipConfig::default()
+    });
+
+    assert!(!p.record("  \n", &[]).unwrap());
+    assert!(!p.record("much too long", &[]).unwrap());
+    let secret = [
+        "text/plain".to_string(),
+        "x-kde-passwordManagerHint".to_string(),
+    ];
+    assert!(!p.record("hunter2", &secret).unwrap());
+    assert!(p.record("short", &["text/plain".to_string()]).unwrap());
+    assert_eq!(titles(&p, ""), vec!["short"]);
+
+    let p = provider(ClipConfig {
+        exclude_mime_types: Vec::new(),
+        ..ClipConfig::default()
+    });
+    assert!(p.record("hunter2", &secret).unwrap());
+}
+
+#[test]
+fn pins_survive_eviction_and_sort_first() {
+    let p = provider(ClipConfig {
+        max_entries: 2,
+        ..ClipConfig::default()
+    });
+    p.record("keep me", &[]).unwrap();
+    assert!(p.execute("pin", &key_of(&p, "keep me")));
+    for text in ["one", "two", "three"] {
+        p.record(text, &[]).unwrap();
+    }
+    assert_eq!(titles(&p, ""), vec!["keep me", "three", "two"]);
+
+    let results = p.search("keep");
+    let extras = results[0].extras.as_ref().unwrap();
+    assert_eq!(extras["actions"][1], "unpin");
+    assert!(p.execute("unpin", &results[0].key));
+    assert_eq!(titles(&p, ""), vec!["three", "two", "keep me"]);
+}
+
+#[test]
+fn search_copy_and_delete() {
+    let p = provider(ClipConfig::default());
+    p.record("fn main() {\n    println!(\"hi\");\n}\n", &[])
+        .unwrap();
+    p.record("https://example.org/docs", &[]).unwrap();
+
+    let results = p.search("/clip println");
+    assert_eq!(results.len(), 1);
+    let code = &results[0];
+    assert_eq!(code.title, "fn main() {");
+    let extras = code.extras.as_ref().unwrap();
+    assert_eq!(
+        extras["copyText"],
+        "fn main() {\n    println!(\"hi\");\n}\n"
+    );
+    assert_eq!(extras["defaultAction"], "copy");
+    assert_eq!(extras["subtitle"], "3 lines · 34 chars");
+
+    let preview = p.preview(&code.key).unwrap();
+    assert_eq!(preview.data["snippet"], extras["copyText"]);
+
+    // Copying an entry again brings it back to the top.
+    assert!(p.execute("copy", &code.key));
+    assert_eq!(titles(&p, "")[0], "fn main() {");
+
+    let matched = p.search("exmpl");
+    let ranges = &matched[0].matches.as_ref().unwrap().title;
+    assert_eq!((ranges[0].start, ranges[0].end), (8, 10));
+
+    assert!(p.execute("delete", &code.key));
+    assert!(!p.execute("delete", &code.key));
+    assert!(!p.execute("copy", "clip:nope"));
+    assert_eq!(titles(&p, ""), vec!["https://example.org/docs"]);
+}
+
+#[test]
+fn history_persists_to_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("state/clipboard.json");
+    let config = ClipConfig {
+        file: Some(file.clone()),
+        ..ClipConfig::default()
+    };
+
+    let p = ClipProvider::new(config.clone());
+    p.record("first", &[]).unwrap();
+    p.record("second", &[]).unwrap();
+    assert!(p.execute("pin", &key_of(&p, "first")));
+    // Copied passwords and tokens stay private to the owner.
+    let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let reopened = ClipProvider::new(config.clone());
+    assert_eq!(titles(&reopened, ""), vec!["first", "second"]);
+    // New ids don't collide with the persisted ones.
+    reopened.record("third", &[]).unwrap();
+    assert_eq!(titles(&reopened, ""), vec!["first", "third", "second"]);
+
+    let in_memory = ClipProvider::new(ClipConfig {
+        persist: false,
+        ..config
+    });
+    assert!(titles(&in_memory, "").is_empty());
+}
@@ -0,0 +1,177 @@
+//! Colors as sRGB, and their notations: hex, `rgb()`, `hsl()` and
+//! `oklch()`.
+
+/// A color in sRGB, channels from 0 to 1. Colors read from `oklch()` may
+/// fall outside that range; notations bound to sRGB clamp them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub alpha: f64,
+}
+
+impl Color {
+    pub fn from_rgb8(r: u8, g: u8, b: u8, alpha: f64) -> Self {
+        Self {
+            r: f64::from(r) / 255.0,
+            g: f64::from(g) / 255.0,
+            b: f64::from(b) / 255.0,
+            alpha,
+        }
+    }
+
+    /// `h` in degrees, `s` and `l` from 0 to 1.
+    pub fn from_hsl(h: f64, s: f64, l: f64, alpha: f64) -> Self {
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let channel = |n: f64| {
+            let k = (n + h.rem_euclid(360.0) / 30.0) % 12.0;
+            l - chroma / 2.0 * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Self {
+            r: channel(0.0),
+            g: channel(8.0),
+            b: channel(4.0),
+            alpha,
+        }
+    }
+
+    /// `l` from 0 to 1, `c` the chroma, `h` in degrees.
+    pub fn from_oklch(l: f64, c: f64, h: f64, alpha: f64) -> Self {
+        let (a, b) = (c * h.to_radians().cos(), c * h.to_radians().sin());
+        let l_ = (l + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+        let m_ = (l - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+        let s_ = (l - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+        Self {
+            r: to_gamma(4.076_741_662_1 * l_ - 3.307_711_591_3 * m_ + 0.230_969_929_2 * s_),
+            g: to_gamma(-1.268_438_004_6 * l_ + 2.609_757_401_1 * m_ - 0.341_319_396_5 * s_),
+            b: to_gamma(-0.004_196_086_3 * l_ - 0.703_418_614_7 * m_ + 1.707_614_701_0 * s_),
+            alpha,
+        }
+    }
+
+    /// Whether it fits in sRGB, give or take rounding to 8 bits.
+    pub fn in_gamut(&self) -> bool {
+        [self.r, self.g, self.b]
+            .iter()
+            .all(|c| (-0.5 / 255.0..=1.0 + 0.5 / 255.0).contains(c))
+    }
+
+    /// The channels as bytes, clamped to sRGB.
+    pub fn rgb8(&self) -> [u8; 3] {
+        [self.r, self.g, self.b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// Hue in degrees, saturation and lightness from 0 to 1, of the
+    /// clamped color.
+    pub fn hsl(&self) -> (f64, f64, f64) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c.clamp(0.0, 1.0));
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let l = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return (0.0, 0.0, l);
+        }
+        let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / delta + 2.0
+        } else {
+            (r - g) / delta + 4.0
+        };
+        (h * 60.0, s, l)
+    }
+
+    /// Lightness from 0 to 1, chroma, and hue in degrees.
+    pub fn oklch(&self) -> (f64, f64, f64) {
+        let [r, g, b] = [self.r, self.g, self.b].map(to_linear);
+        let l_ = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+        let m_ = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+        let s_ = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+        let l = 0.210_454_255_3 * l_ + 0.793_617_785_0 * m_ - 0.004_072_046_8 * s_;
+        let a = 1.977_998_495_1 * l_ - 2.428_592_205_0 * m_ + 0.450_593_709_9 * s_;
+        let b = 0.025_904_037_1 * l_ + 0.782_771_766_2 * m_ - 0.808_675_766_0 * s_;
+        let c = a.hypot(b);
+        // Greys have no hue; rounding noise would otherwise make one up.
+        let h = if c < 1e-4 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        };
+        (l, c, h)
+    }
+
+    /// `#ff8800`, or `#ff880080` when not opaque.
+    pub fn hex(&self) -> String {
+        let [r, g, b] = self.rgb8();
+        let mut hex = format!("#{r:02x}{g:02x}{b:02x}");
+        if self.alpha < 1.0 {
+            hex.push_str(&format!("{:02x}", (self.alpha * 255.0).round() as u8));
+        }
+        hex
+    }
+
+    /// `rgb(255, 136, 0)`, or `rgba(255, 136, 0, 0.5)`.
+    pub fn css_rgb(&self) -> String {
+        let [r, g, b] = self.rgb8();
+        match self.alpha_suffix() {
+            Some(alpha) => format!("rgba({r}, {g}, {b}, {alpha})"),
+            None => format!("rgb({r}, {g}, {b})"),
+        }
+    }
+
+    /// `hsl(32, 100%, 50%)`, or `hsla(32, 100%, 50%, 0.5)`.
+    pub fn css_hsl(&self) -> String {
+        let (h, s, l) = self.hsl();
+        let (h, s, l) = (number(h, 1), number(s * 100.0, 1), number(l * 100.0, 1));
+        match self.alpha_suffix() {
+            Some(alpha) => format!("hsla({h}, {s}%, {l}%, {alpha})"),
+            None => format!("hsl({h}, {s}%, {l}%)"),
+        }
+    }
+
+    /// `oklch(79.3% 0.171 62.6)`, or with `/ 0.5` when not opaque.
+    pub fn css_oklch(&self) -> String {
+        let (l, c, h) = self.oklch();
+        let (l, c, h) = (number(l * 100.0, 1), number(c, 3), number(h, 1));
+        match self.alpha_suffix() {
+            Some(alpha) => format!("oklch({l}% {c} {h} / {alpha})"),
+            None => format!("oklch({l}% {c} {h})"),
+        }
+    }
+
+    fn alpha_suffix(&self) -> Option<String> {
+        (self.alpha < 1.0).then(|| number(self.alpha, 3))
+    }
+}
+
+/// `x` with at most `decimals` decimals, without trailing zeros.
+fn number(x: f64, decimals: usize) -> String {
+    let text = format!("{x:.decimals$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    match text {
+        "-0" => "0".into(),
+        text => text.into(),
+    }
+}
+
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_gamma(c: f64) -> f64 {
+    if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
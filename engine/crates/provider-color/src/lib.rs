@@ -0,0 +1,151 @@
+//! Colors converted inline: `#ff8800`, `rgb(255, 136, 0)`,
+//! `hsl(32, 100%, 50%)` or `oklch(79% 0.17 62)` typed plainly, when the
+//! registry detects a `color` intent, or after `/color`. Each notation
+//! copies on its own, and the preview carries a swatch to paint.
+
+mod color;
+mod parse;
+
+use lancea_model::{Preview, Provider, ResultItem, strip_command};
+
+pub use color::Color;
+pub use parse::parse;
+
+const PROVIDER_ID: &str = "color";
+const KEY_PREFIX: &str = "color:";
+const PREFIXES: [&str; 2] = ["/colour", "/color"];
+
+/// A color in every notation, ready to show and copy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub color: Color,
+    /// Action and text, hex first.
+    pub copy: Vec<(&'static str, String)>,
+}
+
+impl Answer {
+    fn new(color: Color) -> Self {
+        Self {
+            copy: vec![
+                ("copy_hex", color.hex()),
+                ("copy_rgb", color.css_rgb()),
+                ("copy_hsl", color.css_hsl()),
+                ("copy_oklch", color.css_oklch()),
+            ],
+            color,
+        }
+    }
+
+    fn title(&self) -> &str {
+        &self.copy[0].1
+    }
+
+    /// The notations other than hex, and whether sRGB had to clamp.
+    fn subtitle(&self) -> String {
+        let mut parts: Vec<&str> = self.copy[1..]
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect();
+        if !self.color.in_gamut() {
+            parts.push("outside sRGB, clamped");
+        }
+        parts.join(" · ")
+    }
+}
+
+#[derive(Default)]
+pub struct ColorProvider;
+
+impl ColorProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The color `raw_query` names, and the text a key carries for it:
+    /// after `/color`, hex digits don't need their `#`.
+    fn effective_query(&self, raw_query: &str) -> Option<(String, Color)> {
+        let trimmed = raw_query.trim();
+        let q = match strip_command(trimmed, &PREFIXES) {
+            Some(q) if !q.is_empty() && q.chars().all(|c| c.is_ascii_hexdigit()) => {
+                format!("#{q}")
+            }
+            Some(q) => q.to_string(),
+            None => trimmed.to_string(),
+        };
+        let q = q.to_lowercase();
+        parse(&q).map(|color| (q, color))
+    }
+
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let Some((q, color)) = self.effective_query(raw_query) else {
+            return Vec::new();
+        };
+        let answer = Answer::new(color);
+        let copy_texts: serde_json::Map<String, serde_json::Value> = answer
+            .copy
+            .iter()
+            .map(|(action, text)| (action.to_string(), text.clone().into()))
+            .collect();
+        let actions: Vec<&str> = answer.copy.iter().map(|(action, _)| *action).collect();
+
+        vec![ResultItem {
+            key: format!("{KEY_PREFIX}{q}"),
+            title: answer.title().to_string(),
+            provider_id: PROVIDER_ID.into(),
+            score: 1.0,
+            extras: Some(serde_json::json!({
+                "subtitle": answer.subtitle(),
+                "icon": "preferences-desktop-color",
+                "copyText": answer.title(),
+                "copyTexts": copy_texts,
+                "defaultAction": "copy_hex",
+                "actions": actions,
+            })),
+            matches: None,
+        }]
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let color = parse(key.strip_prefix(KEY_PREFIX)?)?;
+        let answer = Answer::new(color);
+        let [r, g, b] = color.rgb8();
+        let labels = ["Hex", "RGB", "HSL", "OKLCH"];
+        let snippet: Vec<String> = labels
+            .iter()
+            .zip(&answer.copy)
+            .map(|(label, (_, text))| format!("{label}: {text}"))
+            .collect();
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": answer.title(),
+                "comment": answer.subtitle(),
+                "snippet": snippet.join("\n"),
+                // What the UI paints: sRGB bytes, clamped, and alpha
+                // from 0 to 1.
+                "swatch": { "r": r, "g": g, "b": b, "a": color.alpha },
+            }),
+        })
+    }
+}
+
+impl Provider for ColorProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        let Some(color) = key.strip_prefix(KEY_PREFIX).and_then(parse) else {
+            return false;
+        };
+        Answer::new(color).copy.iter().any(|(a, _)| *a == action)
+    }
+}
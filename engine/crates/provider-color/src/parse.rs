@@ -0,0 +1,115 @@
+//! Reading colors as CSS writes them: `#f80`, `#ff8800cc`,
+//! `rgb(255, 136, 0)`, `rgb(100% 53% 0% / 50%)`, `hsl(32deg 100% 50%)`
+//! and `oklch(0.79 0.17 62.6)`. Out-of-range channels clamp, as in CSS.
+
+use crate::color::Color;
+
+/// `text` as a color, or `None` when it isn't one.
+pub fn parse(text: &str) -> Option<Color> {
+    let text = text.trim().to_lowercase();
+    if let Some(hex) = text.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+    let (channels, alpha) = split_args(args)?;
+    let alpha = match alpha {
+        Some(alpha) => amount(alpha, 1.0)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+    let [x, y, z] = channels;
+    match name.trim() {
+        "rgb" | "rgba" => {
+            let channel = |c: &str| Some(amount(c, 255.0)?.clamp(0.0, 255.0).round() as u8);
+            Some(Color::from_rgb8(
+                channel(x)?,
+                channel(y)?,
+                channel(z)?,
+                alpha,
+            ))
+        }
+        "hsl" | "hsla" => Some(Color::from_hsl(
+            angle(x)?,
+            percentage(y)?.clamp(0.0, 1.0),
+            percentage(z)?.clamp(0.0, 1.0),
+            alpha,
+        )),
+        "oklch" => Some(Color::from_oklch(
+            amount(x, 1.0)?.clamp(0.0, 1.0),
+            amount(y, 0.4)?.max(0.0),
+            angle(z)?,
+            alpha,
+        )),
+        _ => None,
+    }
+}
+
+/// `rgb`, `rgba`, `rrggbb` or `rrggbbaa` hex digits.
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: Vec<u8> = match hex.len() {
+        3 | 4 => hex
+            .chars()
+            .map(|c| u8::from_str_radix(&c.to_string().repeat(2), 16).ok())
+            .collect::<Option<_>>()?,
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let alpha = digits.get(3).map_or(1.0, |a| f64::from(*a) / 255.0);
+    Some(Color::from_rgb8(digits[0], digits[1], digits[2], alpha))
+}
+
+/// Three channels and an optional alpha, whether comma separated
+/// (`255, 136, 0, 0.5`) or space separated (`255 136 0 / 50%`).
+fn split_args(args: &str) -> Option<([&str; 3], Option<&str>)> {
+    let (channels, alpha) = match args.split_once('/') {
+        Some((channels, alpha)) => (channels, Some(alpha.trim())),
+        None => (args, None),
+    };
+    let parts: Vec<&str> = channels
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    match (parts.as_slice(), alpha) {
+        ([x, y, z], alpha) => Some(([x, y, z], alpha)),
+        ([x, y, z, a], None) if channels.contains(',') => Some(([x, y, z], Some(a))),
+        _ => None,
+    }
+}
+
+fn float(text: &str) -> Option<f64> {
+    text.parse::<f64>().ok().filter(|x| x.is_finite())
+}
+
+/// A number, or a percentage of `full`.
+fn amount(text: &str, full: f64) -> Option<f64> {
+    match text.strip_suffix('%') {
+        Some(percent) => Some(float(percent)? / 100.0 * full),
+        None => float(text),
+    }
+}
+
+/// `50%` as 0.5; `hsl()` also takes a bare `50`.
+fn percentage(text: &str) -> Option<f64> {
+    Some(float(text.strip_suffix('%').unwrap_or(text))? / 100.0)
+}
+
+/// An angle in degrees: bare, or in `deg`, `rad`, `grad` or `turn`.
+fn angle(text: &str) -> Option<f64> {
+    let units = [
+        ("deg", 1.0),
+        ("grad", 0.9),
+        ("rad", 180.0 / std::f64::consts::PI),
+        ("turn", 360.0),
+    ];
+    for (unit, degrees) in units {
+        if let Some(value) = text.strip_suffix(unit) {
+            return Some(float(value)? * degrees);
+        }
+    }
+    float(text)
+}
@@ -0,0 +1,123 @@
+use lancea_model::{Provider, ResultItem};
+use lancea_provider_color::ColorProvider;
+
+fn one(p: &ColorProvider, query: &str) -> ResultItem {
+    let mut results = p.search(query);
+    assert_eq!(results.len(), 1, "{query}");
+    results.remove(0)
+}
+
+fn copy_texts(item: &ResultItem) -> [String; 4] {
+    let texts = &item.extras.as_ref().unwrap()["copyTexts"];
+    ["copy_hex", "copy_rgb", "copy_hsl", "copy_oklch"]
+        .map(|action| texts[action].as_str().unwrap().to_string())
+}
+
+#[test]
+fn converts_hex_to_every_notation() {
+    let p = ColorProvider::new();
+
+    let item = one(&p, "#FF8800");
+    assert_eq!(item.key, "color:#ff8800");
+    assert_eq!(item.title, "#ff8800");
+    let [hex, rgb, hsl, oklch] = copy_texts(&item);
+    assert_eq!(hex, "#ff8800");
+    assert_eq!(rgb, "rgb(255, 136, 0)");
+    assert_eq!(hsl, "hsl(32, 100%, 50%)");
+    assert!(oklch.starts_with("oklch("), "{oklch}");
+    let extras = item.extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], format!("{rgb} · {hsl} · {oklch}"));
+    assert_eq!(extras["defaultAction"], "copy_hex");
+    assert_eq!(
+        extras["actions"],
+        serde_json::json!(["copy_hex", "copy_rgb", "copy_hsl", "copy_oklch"])
+    );
+    // Every notation reads back as the same color.
+    for text in [&rgb, &hsl, &oklch] {
+        assert_eq!(one(&p, text).title, "#ff8800", "{text}");
+    }
+
+    assert_eq!(copy_texts(&one(&p, "#f00"))[3], "oklch(62.8% 0.258 29.2)");
+    assert_eq!(copy_texts(&one(&p, "#fff"))[3], "oklch(100% 0 0)");
+    assert_eq!(copy_texts(&one(&p, "#000"))[2], "hsl(0, 0%, 0%)");
+
+    let item = one(&p, "#00800080");
+    let [_, rgb, hsl, oklch] = copy_texts(&item);
+    assert_eq!(rgb, "rgba(0, 128, 0, 0.502)");
+    assert_eq!(hsl, "hsla(120, 100%, 25.1%, 0.502)");
+    assert!(oklch.ends_with(" / 0.502)"), "{oklch}");
+}
+
+#[test]
+fn reads_css_functions() {
+    let p = ColorProvider::new();
+
+    assert_eq!(one(&p, "rgb(1,2,3)").title, "#010203");
+    assert_eq!(one(&p, "RGB(100% 50% 0%)").title, "#ff8000");
+    assert_eq!(one(&p, "rgba(0, 0, 255, 0.5)").title, "#0000ff80");
+    assert_eq!(one(&p, "rgb(0 0 255 / 50%)").title, "#0000ff80");
+    assert_eq!(one(&p, "rgb(300, -4, 0)").title, "#ff0000");
+    assert_eq!(one(&p, "hsl(120deg 100% 25%)").title, "#008000");
+    assert_eq!(one(&p, "hsla(0.5turn, 100%, 50%, 1)").title, "#00ffff");
+    assert_eq!(one(&p, "oklch(62.8% 0.2577 29.23)").title, "#ff0000");
+    assert_eq!(one(&p, "oklch(0.5 0 0)").title, "#636363");
+
+    // Too vivid for sRGB: shown clamped, and said so.
+    let item = one(&p, "oklch(0.7 0.4 150)");
+    let subtitle = item.extras.as_ref().unwrap()["subtitle"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(subtitle.ends_with(" · outside sRGB, clamped"), "{subtitle}");
+    assert!(copy_texts(&item)[3].starts_with("oklch(70% 0.4 150"));
+
+    for text in [
+        "firefox",
+        "#ggg",
+        "#12345",
+        "rgb(1, 2)",
+        "rgb(1, 2, 3, 4, 5)",
+        "hsl(red, 1, 2)",
+        "cmyk(0, 0, 0, 0)",
+        "ff8800",
+    ] {
+        assert!(p.search(text).is_empty(), "{text}");
+    }
+}
+
+#[test]
+fn color_command_takes_bare_hex() {
+    let p = ColorProvider::new();
+
+    let item = one(&p, "/color FF8800");
+    assert_eq!(item.key, "color:#ff8800");
+    assert_eq!(
+        one(&p, "/colour rgb(255, 136, 0)").key,
+        "color:rgb(255, 136, 0)"
+    );
+    assert_eq!(one(&p, "/COLOR #abc").title, "#aabbcc");
+    assert!(p.search("/color").is_empty());
+    assert!(p.search("/color orange").is_empty());
+}
+
+#[test]
+fn preview_carries_the_swatch() {
+    let p = ColorProvider::new();
+
+    let item = one(&p, "hsl(32 100% 50% / 0.5)");
+    let preview = p.preview(&item.key).unwrap();
+    assert_eq!(preview.preview_kind, "card");
+    assert_eq!(preview.data["title"], "#ff880080");
+    assert_eq!(
+        preview.data["swatch"],
+        serde_json::json!({ "r": 255, "g": 136, "b": 0, "a": 0.5 })
+    );
+    let snippet = preview.data["snippet"].as_str().unwrap();
+    assert!(snippet.starts_with("Hex: #ff880080\nRGB: rgba(255, 136, 0, 0.5)\nHSL: "));
+    assert!(snippet.contains("\nOKLCH: oklch("));
+
+    assert!(p.execute("copy_oklch", &item.key));
+    assert!(!p.execute("copy_cmyk", &item.key));
+    assert!(!p.execute("copy_hex", "color:nope"));
+    assert!(p.preview("color:nope").is_none());
+}
@@ -0,0 +1,186 @@
+//! Unit and currency conversion: `10 km in miles`, `72f to c`,
+//! `5 GiB in MB`, `20 usd to eur`. Units come from a built-in registry;
+//! currency rates from a local file (see [`Rates`]).
+
+mod rates;
+mod units;
+
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+use lancea_model::{Preview, Provider, ResultItem, command_query, expand_home};
+use lancea_provider_calc::format_number;
+use regex::Regex;
+use serde::Deserialize;
+
+pub use rates::Rates;
+pub use units::{Dimension, Unit};
+
+use rates::RatesFile;
+
+const PROVIDER_ID: &str = "convert";
+const KEY_PREFIX: &str = "convert:";
+
+/// `<amount> <unit> in|to|as|into <unit>`; the space before the first unit
+/// is optional so `72f to c` works.
+static QUERY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^([-+]?(?:\d[\d_]*(?:\.\d*)?|\.\d+)(?:e[-+]?\d+)?)\s*(\S.*?)\s+(?:in|to|as|into)\s+(\S.*?)$")
+        .unwrap()
+});
+
+/// The `[convert]` table of the engine config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ConvertConfig {
+    /// Currency rates file; defaults to `$XDG_DATA_HOME/lancea/rates.toml`.
+    pub rates_file: Option<PathBuf>,
+}
+
+/// A parsed query with its answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub amount: f64,
+    pub from: String,
+    pub to: String,
+    pub value: f64,
+    /// Symbol shown after the value (`mi`, `°C`, `EUR`).
+    pub to_symbol: String,
+    pub subtitle: String,
+}
+
+pub struct ConvertProvider {
+    rates: RatesFile,
+}
+
+impl ConvertProvider {
+    pub fn new() -> Self {
+        Self {
+            rates: RatesFile::new(default_rates_path()),
+        }
+    }
+
+    pub fn with_config(self, config: ConvertConfig) -> Self {
+        match config.rates_file {
+            Some(path) => self.with_rates_file(expand_home(path)),
+            None => self,
+        }
+    }
+
+    pub fn with_rates_file(mut self, path: PathBuf) -> Self {
+        self.rates = RatesFile::new(Some(path));
+        self
+    }
+
+    /// Answers `query`, or `None` when it is not a conversion we know.
+    pub fn convert(&self, query: &str) -> Option<Conversion> {
+        let caps = QUERY.captures(command_query(query, &["/convert", "/conv"]))?;
+        let amount: f64 = caps[1].replace('_', "").parse().ok()?;
+        let (from, to) = (caps[2].trim(), caps[3].trim());
+
+        if let (Some(f), Some(t)) = (units::lookup(from), units::lookup(to)) {
+            let value = units::convert(amount, f, t)?;
+            return Some(Conversion {
+                amount,
+                from: f.symbol.into(),
+                to: t.symbol.into(),
+                value,
+                to_symbol: t.symbol.into(),
+                subtitle: format!("{} {} → {}", format_number(amount), f.name, t.name),
+            });
+        }
+
+        let rates = self.rates.get()?;
+        let value = rates.convert(amount, from, to)?;
+        let (from, to) = (from.to_ascii_uppercase(), to.to_ascii_uppercase());
+        let subtitle = match &rates.updated {
+            Some(date) => format!(
+                "{} {from} → {to} · rates from {date}",
+                format_number(amount)
+            ),
+            None => format!("{} {from} → {to}", format_number(amount)),
+        };
+        Some(Conversion {
+            amount,
+            to_symbol: to.clone(),
+            from,
+            to,
+            value,
+            subtitle,
+        })
+    }
+
+    pub fn search(&self, query: &str) -> Vec<ResultItem> {
+        let Some(c) = self.convert(query) else {
+            return Vec::new();
+        };
+        let value = format_number(c.value);
+
+        vec![ResultItem {
+            key: format!("{KEY_PREFIX}{} {} in {}", c.amount, c.from, c.to),
+            title: format!("{value} {}", c.to_symbol),
+            provider_id: PROVIDER_ID.into(),
+            score: 1.0,
+            extras: Some(serde_json::json!({
+                "subtitle": c.subtitle,
+                "value": value,
+                "unit": c.to_symbol,
+                "copyText": value,
+                "defaultAction": "copy",
+            })),
+            matches: None,
+        }]
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let c = self.convert(key.strip_prefix(KEY_PREFIX)?)?;
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": format!("{} {}", format_number(c.value), c.to_symbol),
+                "from": format!("{} {}", format_number(c.amount), c.from),
+                "comment": c.subtitle,
+            }),
+        })
+    }
+
+    /// Only checks the key still converts.
+    pub fn execute_copy(&self, key: &str) -> Result<bool> {
+        key.strip_prefix(KEY_PREFIX)
+            .and_then(|q| self.convert(q))
+            .map(|_| true)
+            .ok_or_else(|| anyhow!("Not a conversion: {key}"))
+    }
+}
+
+impl Default for ConvertProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn default_rates_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("lancea").join("rates.toml"))
+}
+
+impl Provider for ConvertProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "copy" => self.execute_copy(key).unwrap_or(false),
+            _ => false,
+        }
+    }
+}
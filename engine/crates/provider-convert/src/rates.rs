@@ -0,0 +1,103 @@
+//! Offline currency rates, read from a TOML file the user keeps up to date:
+//!
+//! ```toml
+//! base = "EUR"
+//! updated = 2026-10-01
+//!
+//! [rates]
+//! USD = 1.0842
+//! GBP = 0.8571
+//! ```
+//!
+//! Each rate is the number of units of that currency per one `base`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rates {
+    pub base: String,
+    /// When the rates were last refreshed, as written in the file.
+    #[serde(default)]
+    pub updated: Option<toml::value::Datetime>,
+    rates: HashMap<String, f64>,
+}
+
+impl Rates {
+    pub fn parse(txt: &str) -> Result<Self> {
+        let mut rates: Rates = toml::from_str(txt)?;
+
+        rates.base = rates.base.to_ascii_uppercase();
+        rates.rates = rates
+            .rates
+            .into_iter()
+            .map(|(code, rate)| (code.to_ascii_uppercase(), rate))
+            .collect();
+        if let Some((code, _)) = rates
+            .rates
+            .iter()
+            .find(|(_, r)| !(r.is_finite() && **r > 0.0))
+        {
+            bail!("rate for {code} must be a positive number");
+        }
+        rates.rates.insert(rates.base.clone(), 1.0);
+        Ok(rates)
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let txt = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        Self::parse(&txt).with_context(|| format!("parse {}", path.display()))
+    }
+
+    /// Whether `code` (any case) is a known currency.
+    pub fn knows(&self, code: &str) -> bool {
+        self.rates.contains_key(&code.to_ascii_uppercase())
+    }
+
+    /// `amount` of `from` in `to`, via the base currency.
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        let from = self.rates.get(&from.to_ascii_uppercase())?;
+        let to = self.rates.get(&to.to_ascii_uppercase())?;
+        Some(amount / from * to)
+    }
+}
+
+/// A rates file that is re-read whenever it changes on disk, so users can
+/// refresh it without restarting the engine.
+#[derive(Debug, Default)]
+pub(crate) struct RatesFile {
+    path: Option<PathBuf>,
+    cache: Mutex<Option<(SystemTime, Arc<Rates>)>>,
+}
+
+impl RatesFile {
+    pub(crate) fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// The current rates, or `None` when there is no usable file.
+    pub(crate) fn get(&self) -> Option<Arc<Rates>> {
+        let path = self.path.as_deref()?;
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+
+        let mut cache = self.cache.lock().ok()?;
+        if let Some((seen, rates)) = cache.as_ref()
+            && *seen == modified
+        {
+            return Some(rates.clone());
+        }
+
+        let rates = Arc::new(Rates::from_path(path).ok()?);
+        *cache = Some((modified, rates.clone()));
+        Some(rates)
+    }
+}
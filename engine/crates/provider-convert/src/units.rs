@@ -0,0 +1,188 @@
+//! The unit registry. Every unit converts through its dimension's base
+//! unit (metre, kilogram, kelvin, byte, second, metre per second) as
+//! `base = value * factor + offset`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Temperature,
+    Data,
+    Time,
+    Speed,
+}
+
+#[derive(Debug)]
+pub struct Unit {
+    /// Shown next to converted values, e.g. `km`.
+    pub symbol: &'static str,
+    /// Plural name for subtitles, e.g. `kilometres`.
+    pub name: &'static str,
+    pub dimension: Dimension,
+    factor: f64,
+    offset: f64,
+    /// Other spellings accepted in queries, besides `symbol` and `name`.
+    aliases: &'static [&'static str],
+}
+
+impl Unit {
+    const fn linear(
+        symbol: &'static str,
+        name: &'static str,
+        dimension: Dimension,
+        factor: f64,
+        aliases: &'static [&'static str],
+    ) -> Self {
+        Self {
+            symbol,
+            name,
+            dimension,
+            factor,
+            offset: 0.0,
+            aliases,
+        }
+    }
+
+    fn spellings(&self) -> impl Iterator<Item = &'static str> {
+        [self.symbol, self.name]
+            .into_iter()
+            .chain(self.aliases.iter().copied())
+    }
+}
+
+use Dimension::*;
+
+const KIB: f64 = 1024.0;
+
+/// Order matters for case-insensitive lookups: the first unit with a
+/// matching spelling wins, so bytes come before bits (`mb` is MB).
+static UNITS: &[Unit] = &[
+    // Length
+    Unit::linear(
+        "mm",
+        "millimetres",
+        Length,
+        1e-3,
+        &["millimetre", "millimeter", "millimeters"],
+    ),
+    Unit::linear(
+        "cm",
+        "centimetres",
+        Length,
+        1e-2,
+        &["centimetre", "centimeter", "centimeters"],
+    ),
+    Unit::linear("m", "metres", Length, 1.0, &["metre", "meter", "meters"]),
+    Unit::linear(
+        "km",
+        "kilometres",
+        Length,
+        1e3,
+        &["kilometre", "kilometer", "kilometers"],
+    ),
+    Unit::linear("in", "inches", Length, 0.0254, &["inch", "\""]),
+    Unit::linear("ft", "feet", Length, 0.3048, &["foot", "'"]),
+    Unit::linear("yd", "yards", Length, 0.9144, &["yard"]),
+    Unit::linear("mi", "miles", Length, 1609.344, &["mile"]),
+    Unit::linear("nmi", "nautical miles", Length, 1852.0, &["nautical mile"]),
+    // Mass
+    Unit::linear("mg", "milligrams", Mass, 1e-6, &["milligram"]),
+    Unit::linear("g", "grams", Mass, 1e-3, &["gram"]),
+    Unit::linear("kg", "kilograms", Mass, 1.0, &["kilogram", "kilo", "kilos"]),
+    Unit::linear("t", "tonnes", Mass, 1e3, &["tonne", "ton", "tons"]),
+    Unit::linear("oz", "ounces", Mass, 0.028_349_523_125, &["ounce"]),
+    Unit::linear("lb", "pounds", Mass, 0.453_592_37, &["lbs", "pound"]),
+    Unit::linear("st", "stone", Mass, 6.350_293_18, &["stones"]),
+    // Temperature
+    Unit {
+        symbol: "°C",
+        name: "degrees Celsius",
+        dimension: Temperature,
+        factor: 1.0,
+        offset: 273.15,
+        aliases: &["C", "celsius", "degC", "℃"],
+    },
+    Unit {
+        symbol: "°F",
+        name: "degrees Fahrenheit",
+        dimension: Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+        aliases: &["F", "fahrenheit", "degF", "℉"],
+    },
+    Unit::linear("K", "kelvin", Temperature, 1.0, &["kelvins"]),
+    // Data sizes
+    Unit::linear("B", "bytes", Data, 1.0, &["byte"]),
+    Unit::linear("kB", "kilobytes", Data, 1e3, &["KB", "kilobyte"]),
+    Unit::linear("MB", "megabytes", Data, 1e6, &["megabyte"]),
+    Unit::linear("GB", "gigabytes", Data, 1e9, &["gigabyte"]),
+    Unit::linear("TB", "terabytes", Data, 1e12, &["terabyte"]),
+    Unit::linear("PB", "petabytes", Data, 1e15, &["petabyte"]),
+    Unit::linear("KiB", "kibibytes", Data, KIB, &["kibibyte"]),
+    Unit::linear("MiB", "mebibytes", Data, KIB * KIB, &["mebibyte"]),
+    Unit::linear("GiB", "gibibytes", Data, KIB * KIB * KIB, &["gibibyte"]),
+    Unit::linear(
+        "TiB",
+        "tebibytes",
+        Data,
+        KIB * KIB * KIB * KIB,
+        &["tebibyte"],
+    ),
+    Unit::linear(
+        "PiB",
+        "pebibytes",
+        Data,
+        KIB * KIB * KIB * KIB * KIB,
+        &["pebibyte"],
+    ),
+    Unit::linear("bit", "bits", Data, 0.125, &["b"]),
+    Unit::linear("kbit", "kilobits", Data, 125.0, &["kilobit"]),
+    Unit::linear("Mbit", "megabits", Data, 125e3, &["Mb", "megabit"]),
+    Unit::linear("Gbit", "gigabits", Data, 125e6, &["Gb", "gigabit"]),
+    // Time
+    Unit::linear("ms", "milliseconds", Time, 1e-3, &["millisecond"]),
+    Unit::linear("s", "seconds", Time, 1.0, &["sec", "secs", "second"]),
+    Unit::linear("min", "minutes", Time, 60.0, &["mins", "minute"]),
+    Unit::linear("h", "hours", Time, 3600.0, &["hr", "hrs", "hour"]),
+    Unit::linear("d", "days", Time, 86_400.0, &["day"]),
+    Unit::linear("wk", "weeks", Time, 604_800.0, &["week"]),
+    Unit::linear("yr", "years", Time, 31_557_600.0, &["year"]),
+    // Speed
+    Unit::linear("m/s", "metres per second", Speed, 1.0, &["mps"]),
+    Unit::linear(
+        "km/h",
+        "kilometres per hour",
+        Speed,
+        1.0 / 3.6,
+        &["kmh", "kph"],
+    ),
+    Unit::linear("mph", "miles per hour", Speed, 0.447_04, &["mi/h"]),
+    Unit::linear("ft/s", "feet per second", Speed, 0.3048, &["fps"]),
+    Unit::linear("kn", "knots", Speed, 1852.0 / 3600.0, &["knot", "kt"]),
+];
+
+/// Finds a unit by symbol, name or alias. Exact spellings win, so `Mb` is a
+/// megabit while `mb` falls back to the first case-insensitive match, MB.
+pub fn lookup(s: &str) -> Option<&'static Unit> {
+    let s = s.trim();
+    let s = s.strip_prefix('°').map_or(s, |rest| match rest {
+        "C" | "c" => "°C",
+        "F" | "f" => "°F",
+        _ => s,
+    });
+
+    UNITS
+        .iter()
+        .find(|u| u.spellings().any(|sp| sp == s))
+        .or_else(|| {
+            UNITS
+                .iter()
+                .find(|u| u.spellings().any(|sp| sp.eq_ignore_ascii_case(s)))
+        })
+}
+
+/// `value` in `from` expressed in `to`, or `None` across dimensions.
+pub fn convert(value: f64, from: &Unit, to: &Unit) -> Option<f64> {
+    (from.dimension == to.dimension)
+        .then(|| (value * from.factor + from.offset - to.offset) / to.factor)
+}
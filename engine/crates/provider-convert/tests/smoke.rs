@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use lancea_model::Provider;
+use lancea_provider_convert::{ConvertProvider, Rates};
+
+fn fixture_rates() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rates.toml")
+}
+
+fn title(p: &ConvertProvider, q: &str) -> String {
+    p.search(q)
+        .first()
+        .unwrap_or_else(|| panic!("no result for {q}"))
+        .title
+        .clone()
+}
+
+#[test]
+fn converts_units_across_the_registry() {
+    let p = ConvertProvider::new();
+
+    assert_eq!(title(&p, "10 km in miles"), "6.21371192237 mi");
+    assert_eq!(title(&p, "72f to c"), "22.2222222222 °C");
+    assert_eq!(title(&p, "5 GiB in MB"), "5368.70912 MB");
+    assert_eq!(title(&p, "100 Mb in MB"), "12.5 MB");
+    assert_eq!(title(&p, "2 lbs in kg"), "0.90718474 kg");
+    assert_eq!(title(&p, "90 min in h"), "1.5 h");
+    assert_eq!(title(&p, "100 km/h to mph"), "62.1371192237 mph");
+    assert_eq!(title(&p, "0 K as °C"), "-273.15 °C");
+    assert_eq!(title(&p, "12 in in cm"), "30.48 cm");
+}
+
+#[test]
+fn rejects_mismatched_or_unknown_units() {
+    let p = ConvertProvider::new();
+
+    assert!(p.search("10 km in kg").is_empty());
+    assert!(p.search("10 parsecs in km").is_empty());
+    assert!(p.search("10 km").is_empty());
+}
+
+#[test]
+fn currencies_use_the_rates_file() {
+    let p = ConvertProvider::new().with_rates_file(fixture_rates());
+
+    let results = p.search("20 usd to gbp");
+    assert_eq!(results[0].title, "12.8 GBP");
+    let extras = results[0].extras.as_ref().unwrap();
+    assert_eq!(extras["copyText"], "12.8");
+    assert_eq!(extras["subtitle"], "20 USD → GBP · rates from 2026-10-01");
+
+    assert_eq!(title(&p, "/convert 1 eur in jpy"), "160 JPY");
+    assert!(p.search("1 eur in chf").is_empty());
+
+    let missing = ConvertProvider::new().with_rates_file(PathBuf::from("/nonexistent/rates.toml"));
+    assert!(missing.search("20 usd to gbp").is_empty());
+}
+
+#[test]
+fn rates_must_be_positive() {
+    assert!(Rates::parse("base = \"EUR\"\n[rates]\nUSD = 0\n").is_err());
+    assert!(
+        Rates::parse("base = \"eur\"\n[rates]\n")
+            .unwrap()
+            .knows("EUR")
+    );
+}
+
+#[test]
+fn copy_action_and_preview_round_trip_the_key() {
+    let p = ConvertProvider::new();
+    let key = p.search("10 km in mi")[0].key.clone();
+
+    assert_eq!(key, "convert:10 km in mi");
+    assert!(p.execute("copy", &key));
+    assert!(!p.execute("copy", "convert:nonsense"));
+    assert_eq!(p.preview(&key).unwrap().data["title"], "6.21371192237 mi");
+}
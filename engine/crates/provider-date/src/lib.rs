@@ -0,0 +1,408 @@
+//! Dates and times answered inline: `time in tokyo`, `now utc`,
+//! `1700000000`, `2026-10-17 + 45d`, `week number`. Typed after `/date`
+//! or `/time`, or plainly when the registry detects a `date` intent.
+//! Answers copy as ISO 8601, RFC 2822 or a unix timestamp.
+
+mod query;
+mod zones;
+
+use chrono::{
+    DateTime, Datelike, Days, NaiveDate, NaiveTime, Offset, SecondsFormat, Timelike, Utc,
+};
+use chrono_tz::{OffsetName, Tz};
+use lancea_model::{Preview, Provider, ResultItem, strip_command};
+use serde::Deserialize;
+
+pub use query::{Expr, Moment, Query};
+pub use zones::lookup as lookup_zone;
+
+const PROVIDER_ID: &str = "date";
+const KEY_PREFIX: &str = "date:";
+/// Years ISO 8601 and RFC 2822 both write as four digits.
+const WRITABLE_YEARS: std::ops::RangeInclusive<i32> = 0..=9999;
+
+/// The `[date]` table of the engine config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DateConfig {
+    /// The zone answers are shown in unless the query names one; `$TZ` or
+    /// the system's when unset.
+    pub timezone: Option<String>,
+}
+
+/// An answer, ready to show and copy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub title: String,
+    pub subtitle: String,
+    /// Action and text, the plain title first.
+    pub copy: Vec<(&'static str, String)>,
+    /// Lines of the preview.
+    pub details: Vec<String>,
+}
+
+pub struct DateProvider {
+    local: Tz,
+    /// Fixed in tests; the clock otherwise.
+    now: Option<DateTime<Utc>>,
+}
+
+impl DateProvider {
+    pub fn new(config: DateConfig) -> Self {
+        Self {
+            local: zones::local(config.timezone.as_deref()),
+            now: None,
+        }
+    }
+
+    /// Answers as if it were `now`.
+    pub fn with_now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.now.unwrap_or_else(Utc::now)
+    }
+
+    /// The query `raw_query` stands for, as the text a key carries: after
+    /// `/time` or `/date` a bare zone means the time or date there.
+    fn effective_query(&self, raw_query: &str) -> Option<String> {
+        let trimmed = raw_query.trim();
+        let (command, q) = split_command(trimmed);
+        let candidates = match command {
+            None => vec![q.to_string()],
+            Some(word) if q.is_empty() => vec![word.to_string()],
+            Some(word) => vec![q.to_string(), format!("{word} {q}")],
+        };
+        candidates.into_iter().find(|c| Query::parse(c).is_some())
+    }
+
+    /// Answers a query without its command, or `None` for anything that
+    /// isn't one.
+    pub fn answer(&self, q: &str) -> Option<Answer> {
+        let now = self.now();
+        let local = self.local;
+        match Query::parse(q)? {
+            Query::Now { zone, date_only } => {
+                let time = now.with_timezone(&zone.unwrap_or(local));
+                if date_only {
+                    let mut answer = self.date_answer(time.date_naive(), now)?;
+                    answer.subtitle =
+                        format!("Week {} · {}", time.iso_week().week(), zone_label(&time));
+                    return Some(answer);
+                }
+                let mut subtitle =
+                    format!("{} · {}", time.format("%A, %-d %B %Y"), zone_label(&time));
+                if zone.is_some() {
+                    subtitle.push_str(&format!(" · {}", offset_from(&time, local)));
+                }
+                self.time_answer(time, time.format("%H:%M").to_string(), subtitle)
+            }
+            Query::Timestamp { secs } => {
+                let time = DateTime::from_timestamp(secs, 0)?.with_timezone(&local);
+                let subtitle = format!(
+                    "Unix time · {} · {}",
+                    relative((time.timestamp() - now.timestamp()).into()),
+                    zone_label(&time)
+                );
+                self.time_answer(time, long_time(&time), subtitle)
+            }
+            Query::Week(of) => {
+                let date = match of.map(|e| e.eval(now, local)) {
+                    Some(Some(Moment::Date(date))) => date,
+                    Some(Some(Moment::Time(time))) => time.date_naive(),
+                    Some(None) => return None,
+                    None => now.with_timezone(&local).date_naive(),
+                };
+                week_answer(date)
+            }
+            Query::Expr { expr, zone } => {
+                let shown = zone.unwrap_or(local);
+                match expr.eval(now, local)? {
+                    Moment::Date(date) if zone.is_none() => {
+                        let mut answer = self.date_answer(date, now)?;
+                        if expr.is_arithmetic() {
+                            answer.subtitle = format!(
+                                "{} · {}",
+                                q.trim(),
+                                relative_days(date, now.with_timezone(&local).date_naive())
+                            );
+                        }
+                        Some(answer)
+                    }
+                    Moment::Date(date) => {
+                        let time = query::resolve(local, date.and_time(NaiveTime::MIN))?;
+                        let time = time.with_timezone(&shown);
+                        let subtitle = format!("{} · {}", q.trim(), zone_label(&time));
+                        self.time_answer(time, long_time(&time), subtitle)
+                    }
+                    Moment::Time(time) => {
+                        let time = time.with_timezone(&shown);
+                        let subtitle = format!(
+                            "{} · {} · {}",
+                            q.trim(),
+                            relative((time.timestamp() - now.timestamp()).into()),
+                            zone_label(&time)
+                        );
+                        self.time_answer(time, long_time(&time), subtitle)
+                    }
+                }
+            }
+        }
+    }
+
+    /// `None` outside years 0 to 9999, which RFC 2822 can't write.
+    fn time_answer(&self, time: DateTime<Tz>, title: String, subtitle: String) -> Option<Answer> {
+        if !WRITABLE_YEARS.contains(&time.year()) {
+            return None;
+        }
+        let iso = time.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let rfc2822 = time.to_rfc2822();
+        let unix = time.timestamp().to_string();
+        let week = time.iso_week();
+        Some(Answer {
+            details: vec![
+                format!("ISO 8601: {iso}"),
+                format!("RFC 2822: {rfc2822}"),
+                format!("Unix: {unix}"),
+                format!(
+                    "Week: {}-W{:02}, day {}",
+                    week.year(),
+                    week.week(),
+                    time.ordinal()
+                ),
+                format!(
+                    "Zone: {} ({}, UTC{})",
+                    time.timezone().name(),
+                    abbreviation(&time),
+                    time.format("%:z")
+                ),
+            ],
+            copy: vec![
+                ("copy", title.clone()),
+                ("copy_iso", iso),
+                ("copy_rfc2822", rfc2822),
+                ("copy_unix", unix),
+            ],
+            title,
+            subtitle,
+        })
+    }
+
+    /// A day: ISO 8601 is the date alone; RFC 2822 and unix are its start
+    /// in the local zone.
+    fn date_answer(&self, date: NaiveDate, now: DateTime<Utc>) -> Option<Answer> {
+        if !WRITABLE_YEARS.contains(&date.year()) {
+            return None;
+        }
+        let title = date.format("%A, %-d %B %Y").to_string();
+        let today = now.with_timezone(&self.local).date_naive();
+        let week = date.iso_week();
+        let mut details = vec![format!("ISO 8601: {date}")];
+        let mut copy = vec![("copy", title.clone()), ("copy_iso", date.to_string())];
+        if let Some(start) = query::resolve(self.local, date.and_time(NaiveTime::MIN)) {
+            details.push(format!("RFC 2822: {}", start.to_rfc2822()));
+            details.push(format!("Unix: {} (start of day)", start.timestamp()));
+            copy.push(("copy_rfc2822", start.to_rfc2822()));
+            copy.push(("copy_unix", start.timestamp().to_string()));
+        }
+        details.push(format!(
+            "Week: {}-W{:02}, day {}",
+            week.year(),
+            week.week(),
+            date.ordinal()
+        ));
+        Some(Answer {
+            subtitle: relative_days(date, today),
+            title,
+            copy,
+            details,
+        })
+    }
+
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let Some(q) = self.effective_query(raw_query) else {
+            return Vec::new();
+        };
+        let Some(answer) = self.answer(&q) else {
+            return Vec::new();
+        };
+        let copy_texts: serde_json::Map<String, serde_json::Value> = answer
+            .copy
+            .iter()
+            .map(|(action, text)| (action.to_string(), text.clone().into()))
+            .collect();
+        let actions: Vec<&str> = answer.copy.iter().map(|(action, _)| *action).collect();
+
+        vec![ResultItem {
+            key: format!("{KEY_PREFIX}{q}"),
+            title: answer.title.clone(),
+            provider_id: PROVIDER_ID.into(),
+            score: 1.0,
+            extras: Some(serde_json::json!({
+                "subtitle": answer.subtitle,
+                "icon": "preferences-system-time",
+                "copyText": answer.title,
+                "copyTexts": copy_texts,
+                "defaultAction": "copy",
+                "actions": actions,
+            })),
+            matches: None,
+        }]
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let answer = self.answer(key.strip_prefix(KEY_PREFIX)?)?;
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": answer.title,
+                "comment": answer.subtitle,
+                "snippet": answer.details.join("\n"),
+            }),
+        })
+    }
+}
+
+impl Provider for DateProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        let Some(answer) = key.strip_prefix(KEY_PREFIX).and_then(|q| self.answer(q)) else {
+            return false;
+        };
+        answer.copy.iter().any(|(a, _)| *a == action)
+    }
+}
+
+/// `None` when the week reaches outside years 0 to 9999.
+fn week_answer(date: NaiveDate) -> Option<Answer> {
+    let week = date.iso_week();
+    let monday =
+        NaiveDate::from_isoywd_opt(week.year(), week.week(), chrono::Weekday::Mon).unwrap_or(date);
+    let sunday = monday.checked_add_days(Days::new(6))?;
+    if !WRITABLE_YEARS.contains(&monday.year()) || !WRITABLE_YEARS.contains(&sunday.year()) {
+        return None;
+    }
+    let iso = format!("{}-W{:02}", week.year(), week.week());
+    Some(Answer {
+        title: format!("Week {}", week.week()),
+        subtitle: format!(
+            "{} – {} · {iso}",
+            monday.format("%a %-d %b"),
+            sunday.format("%a %-d %b %Y")
+        ),
+        copy: vec![("copy", week.week().to_string()), ("copy_iso", iso.clone())],
+        details: vec![
+            format!("ISO 8601: {iso}"),
+            format!("Monday: {monday}"),
+            format!("Sunday: {sunday}"),
+        ],
+    })
+}
+
+/// `Sat, 17 Oct 2026 14:30`, with seconds when there are some.
+fn long_time(time: &DateTime<Tz>) -> String {
+    if time.second() == 0 {
+        time.format("%a, %-d %b %Y %H:%M").to_string()
+    } else {
+        time.format("%a, %-d %b %Y %H:%M:%S").to_string()
+    }
+}
+
+/// `Asia/Tokyo (JST)`, or `UTC` alone.
+fn zone_label(time: &DateTime<Tz>) -> String {
+    let name = time.timezone().name();
+    match abbreviation(time) {
+        abbr if abbr == name => abbr,
+        abbr => format!("{name} ({abbr})"),
+    }
+}
+
+/// `JST`; `UTC+05:45` for zones whose abbreviation is just the offset.
+fn abbreviation(time: &DateTime<Tz>) -> String {
+    match time.offset().abbreviation() {
+        Some(abbr) if abbr.starts_with(|c: char| c.is_ascii_alphabetic()) => abbr.to_string(),
+        _ => format!("UTC{}", time.format("%:z")),
+    }
+}
+
+/// How far ahead of the local zone `time`'s zone is.
+fn offset_from(time: &DateTime<Tz>, local: Tz) -> String {
+    let here = time.with_timezone(&local).offset().fix().local_minus_utc();
+    let there = time.offset().fix().local_minus_utc();
+    let minutes = (there - here) / 60;
+    if minutes == 0 {
+        return "same time as here".into();
+    }
+    let (hours, rest) = (minutes.abs() / 60, minutes.abs() % 60);
+    let mut amount = match hours {
+        0 => String::new(),
+        1 => "1 hour".into(),
+        n => format!("{n} hours"),
+    };
+    if rest > 0 {
+        if !amount.is_empty() {
+            amount.push(' ');
+        }
+        amount.push_str(&format!("{rest} minutes"));
+    }
+    let direction = if minutes > 0 { "ahead" } else { "behind" };
+    format!("{amount} {direction}")
+}
+
+/// `in 3 hours`, `2 years ago`, `now`.
+fn relative(secs: i128) -> String {
+    let abs = secs.unsigned_abs();
+    let (n, unit) = match abs {
+        0..45 => return "now".into(),
+        45..3600 => (abs.div_ceil(60).max(1), "minute"),
+        3600..86_400 => (abs / 3600, "hour"),
+        86_400..2_592_000 => (abs / 86_400, "day"),
+        2_592_000..31_536_000 => (abs / 2_592_000, "month"),
+        _ => (abs / 31_536_000, "year"),
+    };
+    let amount = if n == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{n} {unit}s")
+    };
+    if secs > 0 {
+        format!("in {amount}")
+    } else {
+        format!("{amount} ago")
+    }
+}
+
+/// `today`, `tomorrow`, `in 45 days`, `3 days ago`.
+fn relative_days(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "today".into(),
+        1 => "tomorrow".into(),
+        -1 => "yesterday".into(),
+        n if n > 0 => format!("in {n} days"),
+        n => format!("{} days ago", -n),
+    }
+}
+
+/// The command word, as a query of its own (`now` for `/time`, `today`
+/// for `/date`), and the rest.
+fn split_command(query: &str) -> (Option<&'static str>, &str) {
+    for (prefix, word) in [("/time", "now"), ("/date", "today")] {
+        if let Some(rest) = strip_command(query, &[prefix]) {
+            return (Some(word), rest);
+        }
+    }
+    (None, query)
+}
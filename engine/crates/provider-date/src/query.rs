@@ -0,0 +1,271 @@
+//! What a date query asks for, and date arithmetic on the answer.
+
+use std::sync::LazyLock;
+
+use chrono::{
+    DateTime, Days, Duration, FixedOffset, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Utc,
+};
+use chrono_tz::Tz;
+use regex::Regex;
+
+use crate::zones;
+
+static NOW: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(now|time|date|today)(?:\s+(?:in|at)\s+(.+)|\s+([^-+\s].*))?$").unwrap()
+});
+
+static TIMESTAMP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:@(-?\d{1,13})|(\d{10}|\d{13}))$").unwrap());
+
+static WEEK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^week(?:\s+(?:number|num|no\.?))?(?:\s+(?:of\s+|for\s+)?(.+))?$").unwrap()
+});
+
+/// A base date or time, then steps like `+ 45d`, then `in <zone>`.
+static EXPR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(now|today|tomorrow|yesterday|\d{4}-\d{2}-\d{2}(?:[t ]\d{1,2}:\d{2}(?::\d{2})?(?:z|[-+]\d{2}:?\d{2})?)?)((?:\s*[-+]\s*\d+\s*[a-z]+)*)(?:\s+in\s+(.+))?$",
+    )
+    .unwrap()
+});
+
+static STEP: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"([-+])\s*(\d+)\s*([a-z]+)").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// `now`, `time in tokyo`, `now utc`, `today`.
+    Now { zone: Option<Tz>, date_only: bool },
+    /// `1700000000`, or milliseconds with 13 digits; anything after `@`.
+    Timestamp { secs: i64 },
+    /// `week number`, `week number of 2026-01-01`.
+    Week(Option<Expr>),
+    /// `2026-10-17 + 45d`, `tomorrow`, `now - 90min in utc`.
+    Expr { expr: Expr, zone: Option<Tz> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    base: Base,
+    steps: Vec<(i64, Unit)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Base {
+    Now,
+    /// Days from today.
+    Today(i64),
+    Date(NaiveDate),
+    /// In the local zone unless it has an offset.
+    DateTime(NaiveDateTime, Option<FixedOffset>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+/// An answer: a calendar day, or an instant shown in some zone.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Moment {
+    Date(NaiveDate),
+    Time(DateTime<Tz>),
+}
+
+impl Query {
+    /// `text` as a date query, or `None` when it is something else or
+    /// names a zone or date that doesn't exist.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_lowercase();
+        if let Some(caps) = NOW.captures(&text) {
+            let zone = match caps.get(2).or(caps.get(3)) {
+                Some(name) => Some(zones::lookup(name.as_str())?),
+                None => None,
+            };
+            let date_only = matches!(&caps[1], "date" | "today");
+            return Some(Self::Now { zone, date_only });
+        }
+        if let Some(caps) = TIMESTAMP.captures(&text) {
+            let secs = match (caps.get(1), caps.get(2)) {
+                (Some(secs), _) => secs.as_str().parse().ok()?,
+                (None, Some(digits)) if digits.len() == 13 => {
+                    digits.as_str().parse::<i64>().ok()? / 1000
+                }
+                (None, Some(digits)) => digits.as_str().parse().ok()?,
+                (None, None) => return None,
+            };
+            return Some(Self::Timestamp { secs });
+        }
+        if let Some(caps) = WEEK.captures(&text) {
+            let of = match caps.get(1) {
+                Some(date) => match Self::parse(date.as_str())? {
+                    Self::Expr { expr, zone: None } => Some(expr),
+                    _ => return None,
+                },
+                None => None,
+            };
+            return Some(Self::Week(of));
+        }
+
+        let caps = EXPR.captures(&text)?;
+        let base = parse_base(&caps[1])?;
+        let steps = STEP
+            .captures_iter(&caps[2])
+            .map(|step| {
+                let amount: i64 = step[2].parse().ok()?;
+                let sign = if &step[1] == "-" { -1 } else { 1 };
+                Some((sign * amount, parse_unit(&step[3])?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let zone = match caps.get(3) {
+            Some(name) => Some(zones::lookup(name.as_str())?),
+            None => None,
+        };
+        Some(Self::Expr {
+            expr: Expr { base, steps },
+            zone,
+        })
+    }
+}
+
+impl Expr {
+    /// Whether it has steps, so isn't just a date.
+    pub fn is_arithmetic(&self) -> bool {
+        !self.steps.is_empty()
+    }
+
+    /// The answer, reckoned in `local`. Days, weeks, months and years
+    /// move the calendar (so `+ 1d` across a DST change keeps the time of
+    /// day); seconds, minutes and hours move the clock.
+    pub fn eval(&self, now: DateTime<Utc>, local: Tz) -> Option<Moment> {
+        let mut moment = match &self.base {
+            Base::Now => Moment::Time(now.with_timezone(&local)),
+            Base::Today(days) => {
+                Moment::Date(add_days(now.with_timezone(&local).date_naive(), *days)?)
+            }
+            Base::Date(date) => Moment::Date(*date),
+            Base::DateTime(naive, None) => Moment::Time(resolve(local, *naive)?),
+            Base::DateTime(naive, Some(offset)) => Moment::Time(
+                offset
+                    .from_local_datetime(naive)
+                    .single()?
+                    .with_timezone(&local),
+            ),
+        };
+        for &(amount, unit) in &self.steps {
+            moment = step(moment, amount, unit, local)?;
+        }
+        Some(moment)
+    }
+}
+
+fn step(moment: Moment, amount: i64, unit: Unit, local: Tz) -> Option<Moment> {
+    let calendar = |date: NaiveDate| match unit {
+        Unit::Days => add_days(date, amount),
+        Unit::Weeks => add_days(date, amount.checked_mul(7)?),
+        Unit::Months => add_months(date, amount),
+        Unit::Years => add_months(date, amount.checked_mul(12)?),
+        _ => None,
+    };
+    let clock = match unit {
+        Unit::Seconds => Duration::try_seconds(amount),
+        Unit::Minutes => Duration::try_minutes(amount),
+        Unit::Hours => Duration::try_hours(amount),
+        _ => None,
+    };
+
+    Some(match (moment, clock) {
+        (Moment::Date(date), None) => Moment::Date(calendar(date)?),
+        (Moment::Date(date), Some(clock)) => {
+            Moment::Time(resolve(local, date.and_time(NaiveTime::MIN))?.checked_add_signed(clock)?)
+        }
+        (Moment::Time(time), Some(clock)) => Moment::Time(time.checked_add_signed(clock)?),
+        (Moment::Time(time), None) => {
+            let naive = time.naive_local();
+            let moved = calendar(naive.date())?.and_time(naive.time());
+            Moment::Time(resolve(time.timezone(), moved)?)
+        }
+    })
+}
+
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    let n = Days::new(days.unsigned_abs());
+    if days < 0 {
+        date.checked_sub_days(n)
+    } else {
+        date.checked_add_days(n)
+    }
+}
+
+/// Clamps to the end of shorter months, so Jan 31 + 1 month is Feb 28.
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let n = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months < 0 {
+        date.checked_sub_months(n)
+    } else {
+        date.checked_add_months(n)
+    }
+}
+
+/// `naive` on the wall clock in `tz`; in a DST gap, an hour later, as
+/// clocks there would show.
+pub fn resolve(tz: Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+    tz.from_local_datetime(&naive).earliest().or_else(|| {
+        tz.from_local_datetime(&naive.checked_add_signed(Duration::hours(1))?)
+            .earliest()
+    })
+}
+
+fn parse_base(text: &str) -> Option<Base> {
+    Some(match text {
+        "now" => Base::Now,
+        "today" => Base::Today(0),
+        "tomorrow" => Base::Today(1),
+        "yesterday" => Base::Today(-1),
+        _ => {
+            let date = NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()?;
+            let Some(rest) = text.get(11..) else {
+                return Some(Base::Date(date));
+            };
+            let (time, offset) = match rest.strip_suffix('z') {
+                Some(time) => (time, Some(FixedOffset::east_opt(0)?)),
+                None => match rest.find(['+', '-']) {
+                    Some(at) => (&rest[..at], Some(parse_offset(&rest[at..])?)),
+                    None => (rest, None),
+                },
+            };
+            let time = NaiveTime::parse_from_str(time, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+                .ok()?;
+            Base::DateTime(date.and_time(time), offset)
+        }
+    })
+}
+
+/// `+02:00` or `-0530`.
+fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let digits = text[1..].replace(':', "");
+    let hours: i32 = digits.get(..2)?.parse().ok()?;
+    let minutes: i32 = digits.get(2..4)?.parse().ok()?;
+    let secs = (hours * 60 + minutes) * 60;
+    FixedOffset::east_opt(if text.starts_with('-') { -secs } else { secs })
+}
+
+fn parse_unit(text: &str) -> Option<Unit> {
+    Some(match text {
+        "s" | "sec" | "secs" | "second" | "seconds" => Unit::Seconds,
+        "m" | "min" | "mins" | "minute" | "minutes" => Unit::Minutes,
+        "h" | "hr" | "hrs" | "hour" | "hours" => Unit::Hours,
+        "d" | "day" | "days" => Unit::Days,
+        "w" | "wk" | "wks" | "week" | "weeks" => Unit::Weeks,
+        "mo" | "mon" | "mons" | "month" | "months" => Unit::Months,
+        "y" | "yr" | "yrs" | "year" | "years" => Unit::Years,
+        _ => return None,
+    })
+}
@@ -0,0 +1,130 @@
+//! Time zones by what people type: IANA names (`Europe/Berlin`), cities
+//! (`tokyo`, `new york`), common abbreviations (`pst`) and whole-hour UTC
+//! offsets (`utc+2`). The zones themselves are the tz database bundled
+//! with chrono-tz, so lookups don't depend on the system's zoneinfo.
+
+use std::str::FromStr;
+
+use chrono_tz::{TZ_VARIANTS, Tz};
+
+/// Abbreviations and big cities the tz database doesn't name a zone after.
+/// Abbreviations go to the zone most people mean, DST included, so `est`
+/// in July is New York's EDT.
+const ALIASES: &[(&str, &str)] = &[
+    ("z", "UTC"),
+    ("zulu", "UTC"),
+    ("pst", "America/Los_Angeles"),
+    ("pdt", "America/Los_Angeles"),
+    ("pt", "America/Los_Angeles"),
+    ("mst", "America/Denver"),
+    ("mdt", "America/Denver"),
+    ("cst", "America/Chicago"),
+    ("cdt", "America/Chicago"),
+    ("est", "America/New_York"),
+    ("edt", "America/New_York"),
+    ("et", "America/New_York"),
+    ("bst", "Europe/London"),
+    ("cet", "Europe/Paris"),
+    ("cest", "Europe/Paris"),
+    ("eet", "Europe/Athens"),
+    ("eest", "Europe/Athens"),
+    ("msk", "Europe/Moscow"),
+    ("ist", "Asia/Kolkata"),
+    ("jst", "Asia/Tokyo"),
+    ("kst", "Asia/Seoul"),
+    ("aest", "Australia/Sydney"),
+    ("aedt", "Australia/Sydney"),
+    ("nzst", "Pacific/Auckland"),
+    ("nzdt", "Pacific/Auckland"),
+    ("san francisco", "America/Los_Angeles"),
+    ("seattle", "America/Los_Angeles"),
+    ("boston", "America/New_York"),
+    ("washington", "America/New_York"),
+    ("miami", "America/New_York"),
+    ("montreal", "America/Toronto"),
+    ("munich", "Europe/Berlin"),
+    ("frankfurt", "Europe/Berlin"),
+    ("hamburg", "Europe/Berlin"),
+    ("barcelona", "Europe/Madrid"),
+    ("milan", "Europe/Rome"),
+    ("geneva", "Europe/Zurich"),
+    ("st petersburg", "Europe/Moscow"),
+    ("beijing", "Asia/Shanghai"),
+    ("shenzhen", "Asia/Shanghai"),
+    ("delhi", "Asia/Kolkata"),
+    ("new delhi", "Asia/Kolkata"),
+    ("mumbai", "Asia/Kolkata"),
+    ("bangalore", "Asia/Kolkata"),
+    ("bengaluru", "Asia/Kolkata"),
+    ("osaka", "Asia/Tokyo"),
+    ("hanoi", "Asia/Ho_Chi_Minh"),
+    ("rio", "America/Sao_Paulo"),
+    ("rio de janeiro", "America/Sao_Paulo"),
+    ("wellington", "Pacific/Auckland"),
+];
+
+/// The zone `name` stands for: an IANA name, then an alias, then the city
+/// an IANA name ends with, then a whole-hour offset from UTC or GMT.
+pub fn lookup(name: &str) -> Option<Tz> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    if let Some(tz) = TZ_VARIANTS
+        .iter()
+        .find(|tz| tz.name().eq_ignore_ascii_case(name))
+    {
+        return Some(*tz);
+    }
+
+    let lower = name.to_lowercase().replace(['_', '-'], " ");
+    let lower = lower.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((_, zone)) = ALIASES.iter().find(|(alias, _)| *alias == lower) {
+        return Tz::from_str(zone).ok();
+    }
+    let city = lower.replace(' ', "_");
+    if let Some(tz) = TZ_VARIANTS.iter().find(|tz| {
+        tz.name()
+            .rsplit('/')
+            .next()
+            .is_some_and(|c| c.eq_ignore_ascii_case(&city))
+    }) {
+        return Some(*tz);
+    }
+    utc_offset(&lower)
+}
+
+/// `utc+2`, `gmt-5`: the `Etc/GMT` zones, whose signs are the other way
+/// round (POSIX style), so `utc+2` is `Etc/GMT-2`.
+fn utc_offset(name: &str) -> Option<Tz> {
+    let rest = name
+        .strip_prefix("utc")
+        .or_else(|| name.strip_prefix("gmt"))?
+        .replace(' ', "");
+    let (sign, hours) = match rest.split_at_checked(1)? {
+        ("+", hours) => ('-', hours),
+        ("-", hours) => ('+', hours),
+        _ => return None,
+    };
+    let hours: u8 = hours.strip_suffix(":00").unwrap_or(hours).parse().ok()?;
+    if hours == 0 {
+        return Some(Tz::UTC);
+    }
+    Tz::from_str(&format!("Etc/GMT{sign}{hours}")).ok()
+}
+
+/// The zone times are shown in unless a query names one: the configured
+/// one, else `$TZ`, else the system's, else UTC.
+pub fn local(configured: Option<&str>) -> Tz {
+    let from_env = std::env::var("TZ").ok();
+    let from_env = from_env.as_deref().map(|tz| tz.trim_start_matches(':'));
+    configured
+        .and_then(lookup)
+        .or_else(|| from_env.and_then(lookup))
+        .or_else(|| {
+            iana_time_zone::get_timezone()
+                .ok()
+                .and_then(|name| Tz::from_str(&name).ok())
+        })
+        .unwrap_or(Tz::UTC)
+}
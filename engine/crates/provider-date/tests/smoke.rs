@@ -0,0 +1,205 @@
+use chrono::{DateTime, Utc};
+use lancea_model::{Provider, ResultItem};
+use lancea_provider_date::{DateConfig, DateProvider};
+
+/// Noon UTC on a Saturday, two in the afternoon in Berlin (CEST), a week
+/// before the clocks there go back.
+fn provider() -> DateProvider {
+    let now: DateTime<Utc> = "2026-10-17T12:00:00Z".parse().unwrap();
+    DateProvider::new(DateConfig {
+        timezone: Some("Europe/Berlin".into()),
+    })
+    .with_now(now)
+}
+
+fn one(p: &DateProvider, query: &str) -> ResultItem {
+    let mut results = p.search(query);
+    assert_eq!(results.len(), 1, "{query}");
+    results.remove(0)
+}
+
+fn extra(item: &ResultItem, name: &str) -> String {
+    let extras = item.extras.as_ref().unwrap();
+    let value = extras
+        .get(name)
+        .or_else(|| extras["copyTexts"].get(name))
+        .unwrap_or_else(|| panic!("no {name} in {extras}"));
+    value.as_str().unwrap().to_string()
+}
+
+#[test]
+fn tells_the_time_in_other_zones() {
+    let p = provider();
+
+    let item = one(&p, "time in tokyo");
+    assert_eq!(item.title, "21:00");
+    assert_eq!(
+        extra(&item, "subtitle"),
+        "Saturday, 17 October 2026 · Asia/Tokyo (JST) · 7 hours ahead"
+    );
+
+    let item = one(&p, "now utc");
+    assert_eq!(item.title, "12:00");
+    assert_eq!(
+        extra(&item, "subtitle"),
+        "Saturday, 17 October 2026 · UTC · 2 hours behind"
+    );
+    assert_eq!(extra(&item, "copy_iso"), "2026-10-17T12:00:00Z");
+
+    let item = one(&p, "now in Kathmandu");
+    assert_eq!(item.title, "17:45");
+    assert!(
+        extra(&item, "subtitle").ends_with("Asia/Kathmandu (UTC+05:45) · 3 hours 45 minutes ahead")
+    );
+    assert_eq!(one(&p, "now pst").title, "05:00");
+    assert_eq!(one(&p, "now utc+3").title, "15:00");
+    assert_eq!(one(&p, "now").title, "14:00");
+
+    // After the command, a bare zone is enough.
+    let item = one(&p, "/time new york");
+    assert_eq!(item.title, "08:00");
+    assert_eq!(item.key, "date:now new york");
+    assert_eq!(one(&p, "/TIME").title, "14:00");
+
+    let item = one(&p, "today");
+    assert_eq!(item.title, "Saturday, 17 October 2026");
+    assert_eq!(extra(&item, "subtitle"), "Week 42 · Europe/Berlin (CEST)");
+    assert_eq!(one(&p, "/date").title, "Saturday, 17 October 2026");
+
+    assert!(p.search("time in atlantis").is_empty());
+    assert!(p.search("firefox").is_empty());
+}
+
+#[test]
+fn reads_unix_timestamps_and_copies_every_format() {
+    let p = provider();
+
+    let item = one(&p, "1700000000");
+    assert_eq!(item.title, "Tue, 14 Nov 2023 23:13:20");
+    assert_eq!(
+        extra(&item, "subtitle"),
+        "Unix time · 2 years ago · Europe/Berlin (CET)"
+    );
+    assert_eq!(extra(&item, "defaultAction"), "copy");
+    assert_eq!(
+        item.extras.as_ref().unwrap()["actions"],
+        serde_json::json!(["copy", "copy_iso", "copy_rfc2822", "copy_unix"])
+    );
+    assert_eq!(extra(&item, "copy"), "Tue, 14 Nov 2023 23:13:20");
+    assert_eq!(extra(&item, "copy_iso"), "2023-11-14T23:13:20+01:00");
+    assert_eq!(
+        extra(&item, "copy_rfc2822"),
+        "Tue, 14 Nov 2023 23:13:20 +0100"
+    );
+    assert_eq!(extra(&item, "copy_unix"), "1700000000");
+    assert_eq!(one(&p, "1700000000123").title, item.title);
+    assert_eq!(one(&p, "@0").title, "Thu, 1 Jan 1970 01:00");
+    // Years RFC 2822 can't write: past 9999, even only in the local
+    // zone, or before year 0.
+    for text in ["@999999999999", "@253402300799", "@-99999999999"] {
+        assert!(p.search(text).is_empty(), "{text}");
+    }
+
+    let preview = p.preview(&item.key).unwrap();
+    let snippet = preview.data["snippet"].as_str().unwrap();
+    assert!(snippet.contains("RFC 2822: Tue, 14 Nov 2023 23:13:20 +0100\n"));
+    assert!(snippet.ends_with("Zone: Europe/Berlin (CET, UTC+01:00)"));
+
+    assert!(p.execute("copy_iso", &item.key));
+    assert!(!p.execute("copy_iso", "date:bogus"));
+    assert!(!p.execute("open", &item.key));
+}
+
+#[test]
+fn does_date_arithmetic_on_the_calendar_or_the_clock() {
+    let p = provider();
+
+    let item = one(&p, "2026-10-17 + 45d");
+    assert_eq!(item.title, "Tuesday, 1 December 2026");
+    assert_eq!(extra(&item, "subtitle"), "2026-10-17 + 45d · in 45 days");
+    assert_eq!(extra(&item, "copy_iso"), "2026-12-01");
+    assert_eq!(
+        extra(&item, "copy_rfc2822"),
+        "Tue, 1 Dec 2026 00:00:00 +0100"
+    );
+
+    assert_eq!(
+        one(&p, "2026-01-31 + 1mo").title,
+        "Saturday, 28 February 2026"
+    );
+    assert_eq!(
+        one(&p, "tomorrow - 2 weeks").title,
+        "Sunday, 4 October 2026"
+    );
+    assert_eq!(
+        one(&p, "2026-10-17 + 1y - 1d").title,
+        "Saturday, 16 October 2027"
+    );
+
+    let item = one(&p, "now + 90min");
+    assert_eq!(item.title, "Sat, 17 Oct 2026 15:30");
+    assert!(extra(&item, "subtitle").contains(" · in 1 hour · "));
+
+    // The clocks go back overnight on the 25th: a day later is the same
+    // time of day, 24 hours later isn't.
+    let day = one(&p, "2026-10-24 12:00 + 1d");
+    assert_eq!(extra(&day, "copy_iso"), "2026-10-25T12:00:00+01:00");
+    let hours = one(&p, "2026-10-24 12:00 + 24h");
+    assert_eq!(extra(&hours, "copy_iso"), "2026-10-25T11:00:00+01:00");
+
+    assert_eq!(
+        one(&p, "2026-10-17 10:00 in tokyo").title,
+        "Sat, 17 Oct 2026 17:00"
+    );
+    assert_eq!(one(&p, "2026-10-17T10:00Z").title, "Sat, 17 Oct 2026 12:00");
+
+    assert!(p.search("2026-02-30").is_empty());
+    assert!(p.search("2026-10-17 + 3 parsecs").is_empty());
+    // Beyond what RFC 2822 can write: no answer, rather than a panic.
+    assert!(p.search("2026-10-17 + 9000y").is_empty());
+    assert!(p.search("now + 9000y in utc").is_empty());
+    assert_eq!(
+        one(&p, "2026-10-17 + 7973y").title,
+        "Sunday, 17 October 9999"
+    );
+
+    // West of UTC, the last day chrono knows has no UTC instant late in
+    // the evening; that's no answer, not an overflow.
+    let west = DateProvider::new(DateConfig {
+        timezone: Some("America/New_York".into()),
+    });
+    assert!(west.search("2026-01-01t23:30 + 95005782d").is_empty());
+}
+
+#[test]
+fn gives_iso_week_numbers() {
+    let p = provider();
+
+    let item = one(&p, "week number");
+    assert_eq!(item.title, "Week 42");
+    assert_eq!(
+        extra(&item, "subtitle"),
+        "Mon 12 Oct – Sun 18 Oct 2026 · 2026-W42"
+    );
+    assert_eq!(extra(&item, "copy"), "42");
+    assert_eq!(extra(&item, "copy_iso"), "2026-W42");
+    assert_eq!(
+        item.extras.as_ref().unwrap()["actions"],
+        serde_json::json!(["copy", "copy_iso"])
+    );
+    let preview = p.preview(&item.key).unwrap();
+    assert_eq!(
+        preview.data["snippet"],
+        "ISO 8601: 2026-W42\nMonday: 2026-10-12\nSunday: 2026-10-18"
+    );
+
+    // 2027 starts on a Friday, in the last week of 2026.
+    let item = one(&p, "week number of 2027-01-01");
+    assert_eq!(item.title, "Week 53");
+    assert_eq!(extra(&item, "copy_iso"), "2026-W53");
+
+    // The last day chrono knows, and a week that runs past it.
+    assert!(p.search("week of 2026-01-01 + 95005782d").is_empty());
+    assert!(p.search("week of 9999-12-31").is_empty());
+    assert_eq!(one(&p, "week of 9999-12-26").title, "Week 51");
+}
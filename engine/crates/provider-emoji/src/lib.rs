@@ -1,7 +1,11 @@
+pub mod search;
+
 use anyhow::Result;
-use lancea_model::{Preview, ResultItem, Provider};
+use lancea_model::{Preview, Provider, ResultItem};
 use serde::Deserialize;
 
+use search::{Searchable, normalize_query};
+
 const PROVIDER_ID: &str = "emoji";
 
 #[derive(Debug, Deserialize)]
@@ -13,6 +17,20 @@ struct EmojiRec {
     keywords: Vec<String>,
 }
 
+impl Searchable for EmojiRec {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn codes(&self) -> &[String] {
+        &self.shortcodes
+    }
+
+    fn keywords(&self) -> &[String] {
+        &self.keywords
+    }
+}
+
 pub struct EmojiProvider {
     data: Vec<EmojiRec>,
 }
@@ -41,48 +59,23 @@ impl EmojiProvider {
             .map(|s| s.trim())
             .unwrap_or(&q);
 
-        let mut items: Vec<(f32, ResultItem)> = Vec::new();
-        for rec in &self.data {
-            let mut score = None::<f32>;
-
-            if q.is_empty() {
-                score = Some(0.1);
-            } else if rec.shortcodes.iter().any(|s| normalize_string(s) == q) {
-                score = Some(1.0);
-            } else if starts_with_normalized(&rec.name, &q)
-                || rec.keywords.iter().any(|k| starts_with_normalized(k, &q))
-            {
-                score = Some(0.8);
-            } else if contains_normalized(&rec.name, &q)
-                || rec.keywords.iter().any(|k| contains_normalized(k, &q))
-            {
-                score = Some(0.4);
-            }
-
-            if let Some(s) = score {
-                items.push((
-                    s,
-                    ResultItem {
-                        key: rec.key.clone(),
-                        title: rec.name.clone(),
-                        provider_id: PROVIDER_ID.into(),
-                        score: s,
-                        extras: Some(serde_json::json!({
-                            "glyph": rec.glyph,
-                            "shortcodes": rec.shortcodes.get(0),
-                        })),
-                    },
-                ))
-            }
-        }
-
-        items.sort_by(|a, b| {
-            b.0.partial_cmp(&a.0)
-                .unwrap_or(std::cmp::Ordering::Equal)
-                .then_with(|| a.1.title.cmp(&b.1.title))
-        });
-
-        return items.into_iter().map(|(_, ri)| ri).take(20).collect();
+        search::search(&self.data, q, 20)
+            .into_iter()
+            .map(|hit| {
+                let rec = hit.record;
+                ResultItem {
+                    key: rec.key.clone(),
+                    title: rec.name.clone(),
+                    provider_id: PROVIDER_ID.into(),
+                    score: hit.score,
+                    extras: Some(serde_json::json!({
+                        "glyph": rec.glyph,
+                        "shortcodes": rec.shortcodes.first(),
+                    })),
+                    matches: hit.matches,
+                }
+            })
+            .collect()
     }
 
     pub fn preview(&self, key: &str) -> Option<Preview> {
@@ -107,22 +100,6 @@ impl EmojiProvider {
     }
 }
 
-fn normalize_query(query: &str) -> String {
-    query.trim().to_lowercase()
-}
-
-fn normalize_string(s: &str) -> String {
-    s.trim().to_lowercase()
-}
-
-fn starts_with_normalized(haystack: &str, needle: &str) -> bool {
-    normalize_string(haystack).starts_with(needle)
-}
-
-fn contains_normalized(haystack: &str, needle: &str) -> bool {
-    normalize_string(haystack).contains(needle)
-}
-
 impl Provider for EmojiProvider {
     fn id(&self) -> &str {
         PROVIDER_ID
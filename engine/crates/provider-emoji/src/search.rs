@@ -0,0 +1,105 @@
+//! The search behind both emoji and `/char`: exact codes first, then
+//! names and keywords by prefix, then anywhere in them.
+
+use lancea_model::{MatchRange, Matches};
+
+/// Something the search can match, such as an emoji or a character.
+pub trait Searchable {
+    fn name(&self) -> &str;
+    /// Only matched whole, like emoji shortcodes.
+    fn codes(&self) -> &[String];
+    /// Matched like the name, without highlighting.
+    fn keywords(&self) -> &[String];
+}
+
+pub struct Hit<'a, T> {
+    pub record: &'a T,
+    pub score: f32,
+    /// Where the query is in the name; `None` without a query.
+    pub matches: Option<Matches>,
+}
+
+/// The best `limit` of `records` for a query already passed through
+/// [`normalize_query`], by score and then name. An empty query matches
+/// everything equally.
+pub fn search<'a, T: Searchable>(records: &'a [T], q: &str, limit: usize) -> Vec<Hit<'a, T>> {
+    let mut hits: Vec<Hit<'a, T>> = records
+        .iter()
+        .filter_map(|rec| {
+            let score = if q.is_empty() {
+                0.1
+            } else if rec.codes().iter().any(|s| normalize_string(s) == q) {
+                1.0
+            } else if starts_with_normalized(rec.name(), q)
+                || rec.keywords().iter().any(|k| starts_with_normalized(k, q))
+            {
+                0.8
+            } else if contains_normalized(rec.name(), q)
+                || rec.keywords().iter().any(|k| contains_normalized(k, q))
+            {
+                0.4
+            } else {
+                return None;
+            };
+            Some(Hit {
+                record: rec,
+                score,
+                matches: None,
+            })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.record.name().cmp(b.record.name()))
+    });
+    hits.truncate(limit);
+
+    // Only for what is shown.
+    if !q.is_empty() {
+        for hit in &mut hits {
+            hit.matches = Some(Matches {
+                title: name_matches(hit.record.name(), q),
+                subtitle: None,
+            });
+        }
+    }
+    hits
+}
+
+pub fn normalize_query(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+fn normalize_string(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+/// Range of `needle` in `name` after the same lowercasing the scorer
+/// applies, mapped back onto `name`'s own chars.
+fn name_matches(name: &str, needle: &str) -> Vec<MatchRange> {
+    let mut lower = String::with_capacity(name.len());
+    // Source char index for every byte of `lower`.
+    let mut src: Vec<usize> = Vec::with_capacity(name.len());
+    for (i, ch) in name.chars().enumerate() {
+        for lc in ch.to_lowercase() {
+            lower.push(lc);
+            src.extend(std::iter::repeat_n(i, lc.len_utf8()));
+        }
+    }
+
+    match lower.find(needle) {
+        Some(at) => MatchRange::from_char_indices(name, src[at..at + needle.len()].iter().copied()),
+        None => Vec::new(),
+    }
+}
+
+fn starts_with_normalized(haystack: &str, needle: &str) -> bool {
+    normalize_string(haystack).starts_with(needle)
+}
+
+fn contains_normalized(haystack: &str, needle: &str) -> bool {
+    normalize_string(haystack).contains(needle)
+}
@@ -1,5 +1,5 @@
 use lancea_provider_emoji::EmojiProvider;
-use lancea_model::Provider;
+use lancea_model::{MatchRange, Provider};
 
 #[test]
 fn can_create_provider() {
@@ -140,4 +140,29 @@ fn empty_search_returns_low_score_results() {
     for result in &results {
         assert_eq!(result.score, 0.1);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn search_reports_title_match_ranges() {
+    let provider = EmojiProvider::new().expect("Failed to create emoji provider");
+    let results = provider.search("beam");
+
+    let grin = results.iter().find(|r| r.key == "emoji:grin").unwrap();
+    let matches = grin.matches.as_ref().unwrap();
+    assert_eq!(matches.title, vec![MatchRange { start: 0, end: 4 }]);
+    assert!(matches.subtitle.is_none());
+
+    let results = provider.search("tears");
+    let joy = results.iter().find(|r| r.key == "emoji:joy").unwrap();
+    assert_eq!(
+        joy.matches.as_ref().unwrap().title,
+        vec![MatchRange { start: 10, end: 15 }]
+    );
+}
+
+#[test]
+fn empty_search_reports_no_match_ranges() {
+    let provider = EmojiProvider::new().expect("Failed to create emoji provider");
+    let results = provider.search("");
+
+    assert!(results.iter().all(|r| r.matches.is_none()));
+}
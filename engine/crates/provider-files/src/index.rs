@@ -0,0 +1,428 @@
+//! The file name index: a full walk of every root at startup, then
+//! per-directory rescans driven by inotify.
+//!
+//! Walks honour `.gitignore` (inside or outside a repository), `.hidden`
+//! files and the configured exclusions, and skip dotfiles unless asked not
+//! to. Only directories that made it into the index are watched, so an
+//! excluded `node_modules` costs neither memory nor inotify watches.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, RwLock, Weak};
+use std::thread;
+use std::time::Duration;
+
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::FilesConfig;
+
+/// Lets a burst of events (an unpacked archive, a build) settle into one
+/// rescan per directory.
+const SETTLE: Duration = Duration::from_millis(200);
+/// How often an idle watcher thread checks whether its index is gone.
+const POLL: Duration = Duration::from_secs(1);
+/// Entries inserted per write lock while building, so searches can run
+/// against a partial index instead of waiting for the whole walk.
+const BATCH: usize = 4096;
+
+/// Files whose edits change what a walk of their directory yields.
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore", ".hidden"];
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    is_dir: bool,
+}
+
+/// A search hit, best first.
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub score: i64,
+    /// Char indices into the file name that matched.
+    pub indices: Vec<usize>,
+}
+
+pub struct FileIndex {
+    entries: RwLock<BTreeMap<PathBuf, Entry>>,
+    ready: AtomicBool,
+    roots: Vec<PathBuf>,
+    filter: Filter,
+}
+
+impl FileIndex {
+    /// Walks every root before returning, without watching for changes.
+    pub fn build(config: &FilesConfig) -> Arc<Self> {
+        let index = Arc::new(Self::empty(config));
+        for root in &index.roots {
+            index.insert_walk(root, None);
+        }
+        index.ready.store(true, Ordering::SeqCst);
+        index
+    }
+
+    /// Returns at once and builds the index on a background thread, which
+    /// then keeps it current until the index is dropped.
+    pub fn spawn(config: &FilesConfig) -> Arc<Self> {
+        let index = Arc::new(Self::empty(config));
+        let weak = Arc::downgrade(&index);
+        thread::Builder::new()
+            .name("files-index".into())
+            .spawn(move || watch_loop(weak))
+            .expect("spawn files index thread");
+        index
+    }
+
+    fn empty(config: &FilesConfig) -> Self {
+        Self {
+            entries: RwLock::new(BTreeMap::new()),
+            ready: AtomicBool::new(false),
+            roots: config.roots(),
+            filter: Filter::new(config),
+        }
+    }
+
+    /// Whether the initial walk has finished.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.entries.read().is_ok_and(|e| e.contains_key(path))
+    }
+
+    /// Fuzzy-matches `q` against file names, or against whole paths when
+    /// `q` contains a `/`. Exact and prefix name matches rank first, then
+    /// shorter paths.
+    pub fn search(&self, q: &str, limit: usize) -> Vec<Hit> {
+        let q = q.trim();
+        if q.is_empty() {
+            return Vec::new();
+        }
+
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let by_path = q.contains('/');
+        let q_lower = q.to_lowercase();
+        let Ok(entries) = self.entries.read() else {
+            return Vec::new();
+        };
+
+        let mut hits: Vec<Hit> = entries
+            .iter()
+            .filter_map(|(path, entry)| {
+                let (score, indices) = if by_path {
+                    let (score, _) = matcher.fuzzy_indices(&path.to_string_lossy(), q)?;
+                    (score, Vec::new())
+                } else {
+                    matcher.fuzzy_indices(&entry.name, q)?
+                };
+
+                let name = entry.name.to_lowercase();
+                let bonus = if name == q_lower {
+                    1000
+                } else if name.starts_with(&q_lower) {
+                    500
+                } else {
+                    0
+                };
+
+                Some(Hit {
+                    path: path.clone(),
+                    is_dir: entry.is_dir,
+                    score: score + bonus,
+                    indices,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.path.as_os_str().len().cmp(&b.path.as_os_str().len()))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Re-reads the direct children of `dir`, dropping vanished or newly
+    /// ignored ones (with their subtrees) and walking new directories in
+    /// full. Returns the directories that were added.
+    pub fn refresh_dir(&self, dir: &Path) -> Vec<PathBuf> {
+        if !dir.is_dir() || !self.is_under_root(dir) {
+            self.remove_tree(dir);
+            return Vec::new();
+        }
+
+        let fresh: BTreeMap<PathBuf, Entry> = self.filter.walk(dir, Some(1)).collect();
+        let stale: Vec<PathBuf> = {
+            let Ok(entries) = self.entries.read() else {
+                return Vec::new();
+            };
+            children(&entries, dir)
+                .filter(|p| !fresh.contains_key(*p))
+                .cloned()
+                .collect()
+        };
+        for path in &stale {
+            self.remove_tree(path);
+        }
+
+        let known: HashSet<PathBuf> = match self.entries.read() {
+            Ok(entries) => children(&entries, dir).cloned().collect(),
+            Err(_) => return Vec::new(),
+        };
+
+        let mut added_dirs = Vec::new();
+        for (path, entry) in fresh {
+            if known.contains(&path) {
+                continue;
+            }
+            let is_dir = entry.is_dir;
+            if is_dir {
+                added_dirs.extend(self.insert_walk(&path, None));
+            }
+            if let Ok(mut entries) = self.entries.write() {
+                entries.insert(path.clone(), entry);
+            }
+            if is_dir {
+                added_dirs.push(path);
+            }
+        }
+        added_dirs
+    }
+
+    /// Drops and re-walks everything below `dir`, for when its ignore rules
+    /// changed. Returns the directories found.
+    pub fn reindex_dir(&self, dir: &Path) -> Vec<PathBuf> {
+        if !dir.is_dir() || !self.is_under_root(dir) {
+            self.remove_tree(dir);
+            return Vec::new();
+        }
+
+        let keep = self
+            .entries
+            .write()
+            .ok()
+            .and_then(|mut entries| entries.remove(dir));
+        self.remove_tree(dir);
+        if let (Some(entry), Ok(mut entries)) = (keep, self.entries.write()) {
+            entries.insert(dir.to_path_buf(), entry);
+        }
+        self.insert_walk(dir, None)
+    }
+
+    /// Walks `dir` and inserts everything below it, returning the
+    /// directories found.
+    fn insert_walk(&self, dir: &Path, max_depth: Option<usize>) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        let mut batch = Vec::with_capacity(BATCH);
+
+        for (path, entry) in self.filter.walk(dir, max_depth) {
+            if entry.is_dir {
+                dirs.push(path.clone());
+            }
+            batch.push((path, entry));
+            if batch.len() == BATCH {
+                self.insert_batch(&mut batch);
+            }
+        }
+        self.insert_batch(&mut batch);
+        dirs
+    }
+
+    fn insert_batch(&self, batch: &mut Vec<(PathBuf, Entry)>) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.extend(batch.drain(..));
+        }
+    }
+
+    /// Removes `path` and everything below it.
+    fn remove_tree(&self, path: &Path) {
+        let Ok(mut entries) = self.entries.write() else {
+            return;
+        };
+        let doomed: Vec<PathBuf> = entries
+            .range(path.to_path_buf()..)
+            .map(|(p, _)| p)
+            .take_while(|p| p.starts_with(path))
+            .cloned()
+            .collect();
+        for p in doomed {
+            entries.remove(&p);
+        }
+    }
+
+    fn is_under_root(&self, path: &Path) -> bool {
+        self.roots.iter().any(|r| path.starts_with(r))
+    }
+}
+
+/// Paths in `entries` whose parent is exactly `dir`.
+fn children<'a>(
+    entries: &'a BTreeMap<PathBuf, Entry>,
+    dir: &'a Path,
+) -> impl Iterator<Item = &'a PathBuf> {
+    entries
+        .range(dir.to_path_buf()..)
+        .map(|(p, _)| p)
+        .take_while(move |p| p.starts_with(dir))
+        .filter(move |p| p.parent() == Some(dir))
+}
+
+/// What a walk skips besides ignore files: configured exclusions (file
+/// names or globs like `*.o`, or absolute paths) and, by default, dotfiles.
+#[derive(Clone)]
+struct Filter {
+    names: GlobSet,
+    paths: Vec<PathBuf>,
+    hidden: bool,
+}
+
+impl Filter {
+    fn new(config: &FilesConfig) -> Self {
+        let mut names = GlobSetBuilder::new();
+        let mut paths = Vec::new();
+
+        for pattern in &config.exclude {
+            let expanded = lancea_model::expand_home(PathBuf::from(pattern));
+            if expanded.is_absolute() {
+                paths.push(expanded);
+            } else if let Ok(glob) = Glob::new(pattern) {
+                names.add(glob);
+            }
+        }
+
+        Self {
+            names: names.build().unwrap_or_else(|_| GlobSet::empty()),
+            paths,
+            hidden: config.hidden,
+        }
+    }
+
+    fn excludes(&self, path: &Path) -> bool {
+        self.paths.iter().any(|p| path.starts_with(p))
+            || path.file_name().is_some_and(|n| self.names.is_match(n))
+    }
+
+    /// Everything below `dir` (not `dir` itself) that survives filtering.
+    fn walk(&self, dir: &Path, max_depth: Option<usize>) -> impl Iterator<Item = (PathBuf, Entry)> {
+        let filter = self.clone();
+        WalkBuilder::new(dir)
+            .hidden(!self.hidden)
+            .require_git(false)
+            .add_custom_ignore_filename(".hidden")
+            .follow_links(false)
+            .max_depth(max_depth)
+            .filter_entry(move |e| e.depth() == 0 || !filter.excludes(e.path()))
+            .build()
+            .filter_map(Result::ok)
+            .filter(|e| e.depth() > 0)
+            .map(|e| {
+                let entry = Entry {
+                    name: e.file_name().to_string_lossy().into_owned(),
+                    is_dir: e.file_type().is_some_and(|t| t.is_dir()),
+                };
+                (e.into_path(), entry)
+            })
+    }
+}
+
+/// Builds the index, then applies inotify events until it is dropped.
+fn watch_loop(weak: Weak<FileIndex>) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher: Option<RecommendedWatcher> = notify::recommended_watcher(tx).ok();
+
+    let mut watch = |dirs: &[PathBuf]| {
+        if let Some(w) = watcher.as_mut() {
+            for dir in dirs {
+                let _ = w.watch(dir, RecursiveMode::NonRecursive);
+            }
+        }
+    };
+
+    {
+        let Some(index) = weak.upgrade() else {
+            return;
+        };
+        for root in &index.roots {
+            watch(std::slice::from_ref(root));
+            let dirs = index.insert_walk(root, None);
+            watch(&dirs);
+        }
+        index.ready.store(true, Ordering::SeqCst);
+    }
+
+    loop {
+        let first = match rx.recv_timeout(POLL) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) if weak.strong_count() > 0 => continue,
+            Err(_) => return,
+        };
+
+        thread::sleep(SETTLE);
+        let mut dirty: Vec<PathBuf> = Vec::new();
+        let mut rules_changed: Vec<PathBuf> = Vec::new();
+        for event in std::iter::once(first).chain(rx.try_iter()) {
+            let Ok(event) = event else {
+                continue;
+            };
+            let content_only = matches!(event.kind, EventKind::Modify(ModifyKind::Data(_)));
+            if !matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)
+            ) {
+                continue;
+            }
+
+            for path in event.paths {
+                let Some(parent) = path.parent() else {
+                    continue;
+                };
+                let is_rules = path
+                    .file_name()
+                    .is_some_and(|n| IGNORE_FILES.iter().any(|f| n == *f));
+                // Writes to ordinary files don't change any names.
+                if content_only && !is_rules {
+                    continue;
+                }
+                let list = if is_rules {
+                    &mut rules_changed
+                } else {
+                    &mut dirty
+                };
+                if !list.iter().any(|d| d == parent) {
+                    list.push(parent.to_path_buf());
+                }
+            }
+        }
+
+        let Some(index) = weak.upgrade() else {
+            return;
+        };
+        for dir in rules_changed {
+            let added = index.reindex_dir(&dir);
+            watch(&added);
+        }
+        for dir in dirty {
+            let added = index.refresh_dir(&dir);
+            watch(&added);
+        }
+    }
+}
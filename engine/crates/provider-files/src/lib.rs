@@ -0,0 +1,283 @@
+//! File search by name over an incrementally maintained index (see
+//! [`FileIndex`]). Files open with their default application through the
+//! apps provider.
+
+mod index;
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use lancea_model::{
+    MatchRange, Matches, Preview, Provider, ResultItem, command_query, expand_home, tildify,
+};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use index::{FileIndex, Hit};
+
+const PROVIDER_ID: &str = "files";
+const KEY_PREFIX: &str = "files:";
+
+/// How much of a file the preview reads when looking for text.
+const SNIPPET_BYTES: usize = 4096;
+const SNIPPET_LINES: usize = 20;
+
+/// The `[files]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FilesConfig {
+    /// Directories to index; the home directory when empty.
+    pub roots: Vec<PathBuf>,
+    /// File names or globs (`node_modules`, `*.o`) to skip anywhere, or
+    /// absolute paths (`~/Videos`) to skip entirely.
+    pub exclude: Vec<String>,
+    /// Index dotfiles too.
+    pub hidden: bool,
+    pub max_results: usize,
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            exclude: ["node_modules", "target", "__pycache__", "*.pyc", "*.o"]
+                .map(String::from)
+                .to_vec(),
+            hidden: false,
+            max_results: 50,
+        }
+    }
+}
+
+impl FilesConfig {
+    fn roots(&self) -> Vec<PathBuf> {
+        if self.roots.is_empty() {
+            return dirs::home_dir().into_iter().collect();
+        }
+        self.roots.iter().cloned().map(expand_home).collect()
+    }
+}
+
+pub struct FilesProvider {
+    index: Arc<FileIndex>,
+    apps: Arc<AppsProvider>,
+    max_results: usize,
+}
+
+impl FilesProvider {
+    /// Starts indexing `config.roots` in the background.
+    pub fn new(config: FilesConfig, apps: Arc<AppsProvider>) -> Self {
+        Self {
+            index: FileIndex::spawn(&config),
+            apps,
+            max_results: config.max_results,
+        }
+    }
+
+    /// Uses an existing index, e.g. one built synchronously in tests.
+    pub fn with_index(index: Arc<FileIndex>, apps: Arc<AppsProvider>) -> Self {
+        Self {
+            index,
+            apps,
+            max_results: FilesConfig::default().max_results,
+        }
+    }
+
+    pub fn index(&self) -> &Arc<FileIndex> {
+        &self.index
+    }
+
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &["/files", "/f"]);
+
+        self.index
+            .search(q, self.max_results)
+            .into_iter()
+            .map(|hit| to_result_item(&hit))
+            .collect()
+    }
+
+    /// Metadata for everything, plus the first lines of text files.
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let path = Path::new(key.strip_prefix(KEY_PREFIX)?);
+        let meta = fs::metadata(path).ok()?;
+
+        let comment = if meta.is_dir() {
+            let n = fs::read_dir(path)
+                .map(|d| {
+                    d.filter_map(Result::ok)
+                        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+                        .count()
+                })
+                .unwrap_or(0);
+            format!("Folder · {n} items")
+        } else {
+            human_size(meta.len())
+        };
+        let mut data = serde_json::json!({
+            "title": file_name(path),
+            "path": path.to_string_lossy(),
+            "comment": comment,
+            "size": (!meta.is_dir()).then_some(meta.len()),
+            "mtime": mtime_secs(&meta),
+            "isDir": meta.is_dir(),
+        });
+        if meta.is_file()
+            && let Some(snippet) = text_snippet(path)
+        {
+            data["snippet"] = snippet.into();
+        }
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data,
+        })
+    }
+
+    pub fn execute_open(&self, key: &str) -> Result<()> {
+        let path = key_path(key)?;
+        self.apps
+            .open_default(&[path.to_string_lossy().into_owned()])
+    }
+
+    /// Opens the containing folder in the default file manager.
+    pub fn execute_reveal(&self, key: &str) -> Result<()> {
+        let path = key_path(key)?;
+        let parent = path
+            .parent()
+            .with_context(|| format!("{} has no parent", path.display()))?;
+        self.apps
+            .open_default(&[parent.to_string_lossy().into_owned()])
+    }
+
+    /// Only checks the path still exists; the UI turns to the "Open with…"
+    /// list itself.
+    pub fn execute_copy_path(&self, key: &str) -> Result<bool> {
+        key_path(key).map(|_| true)
+    }
+}
+
+fn key_path(key: &str) -> Result<PathBuf> {
+    let path = key
+        .strip_prefix(KEY_PREFIX)
+        .map(PathBuf::from)
+        .with_context(|| format!("Not a files key: {key}"))?;
+    anyhow::ensure!(path.exists(), "{} no longer exists", path.display());
+    Ok(path)
+}
+
+fn to_result_item(hit: &Hit) -> ResultItem {
+    let path = hit.path.to_string_lossy();
+    let name = file_name(&hit.path);
+    let meta = fs::metadata(&hit.path).ok();
+
+    let extras = serde_json::json!({
+        "subtitle": hit.path.parent().map(tildify).unwrap_or_default(),
+        "path": path,
+        "isDir": hit.is_dir,
+        "size": meta.as_ref().filter(|m| m.is_file()).map(|m| m.len()),
+        "mtime": meta.as_ref().and_then(mtime_secs),
+        "copyText": path,
+        // "Open with…" continues as the apps provider's list for this file.
+        "queryTexts": { "open_with": AppsProvider::open_with_query(&hit.path) },
+        "defaultAction": "open",
+        "actions": ["open", "open_with", "reveal", "copy_path"],
+    });
+
+    ResultItem {
+        key: format!("{KEY_PREFIX}{path}"),
+        title: name.clone(),
+        provider_id: PROVIDER_ID.into(),
+        score: hit.score as f32,
+        extras: Some(extras),
+        matches: (!hit.indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(&name, hit.indices.iter().copied()),
+            subtitle: None,
+        }),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+fn mtime_secs(meta: &fs::Metadata) -> Option<u64> {
+    meta.modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+/// The first lines of `path` if its head looks like UTF-8 text.
+fn text_snippet(path: &Path) -> Option<String> {
+    let mut buf = Vec::with_capacity(SNIPPET_BYTES);
+    fs::File::open(path)
+        .ok()?
+        .take(SNIPPET_BYTES as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+    if buf.contains(&0) {
+        return None;
+    }
+
+    // A multi-byte char may straddle the cut; anything else is binary.
+    let text = match std::str::from_utf8(&buf) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&buf[..e.valid_up_to()]).ok()?,
+        Err(_) => return None,
+    };
+    Some(
+        text.lines()
+            .take(SNIPPET_LINES)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["kB", "MB", "GB", "TB", "PB"];
+    if bytes < 1000 {
+        return format!("{bytes} bytes");
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = "";
+    for u in UNITS {
+        value /= 1000.0;
+        unit = u;
+        if value < 1000.0 {
+            break;
+        }
+    }
+    format!("{value:.1} {unit}")
+}
+
+impl Provider for FilesProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "open" => self.execute_open(key).is_ok(),
+            "reveal" => self.execute_reveal(key).is_ok(),
+            "copy_path" | "open_with" => self.execute_copy_path(key).unwrap_or(false),
+            _ => false,
+        }
+    }
+}
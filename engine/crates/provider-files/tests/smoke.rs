@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lancea_model::Provider;
+use lancea_provider_files::{FileIndex, FilesConfig, FilesProvider};
+use lancea_testkit::{no_apps, titles};
+use tempfile::TempDir;
+
+fn write(root: &Path, rel: &str, contents: &[u8]) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+/// notes/, src/ and a few things that must stay out of the index.
+fn tree() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    write(root, "notes/todo.md", b"# Todo\n- buy milk\n");
+    write(root, "src/main.rs", b"fn main() {}\n");
+    write(root, "Photos/holiday.png", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+    write(root, ".secret", b"dotfile");
+    write(root, ".gitignore", b"build/\n");
+    write(root, "build/out.bin", b"ignored by .gitignore");
+    write(root, "node_modules/pkg/index.js", b"excluded by name");
+    write(root, "notes/.hidden", b"private.txt\n");
+    write(root, "notes/private.txt", b"hidden by .hidden");
+    dir
+}
+
+fn config(root: &Path) -> FilesConfig {
+    FilesConfig {
+        roots: vec![root.to_path_buf()],
+        ..FilesConfig::default()
+    }
+}
+
+#[test]
+fn index_respects_ignore_rules_and_exclusions() {
+    let dir = tree();
+    let p = FilesProvider::with_index(FileIndex::build(&config(dir.path())), no_apps());
+
+    assert_eq!(
+        titles(&p, "todo").first().map(String::as_str),
+        Some("todo.md")
+    );
+    assert!(titles(&p, "/files main").contains(&"main.rs".to_string()));
+    for hidden in ["out.bin", "index.js", "secret", "private"] {
+        assert!(
+            titles(&p, hidden).is_empty(),
+            "{hidden} should not be indexed"
+        );
+    }
+
+    let photos = dir.path().join("Photos").to_string_lossy().into_owned();
+    let mut excluded = config(dir.path());
+    excluded.exclude.push(photos);
+    excluded.hidden = true;
+    let p = FilesProvider::with_index(FileIndex::build(&excluded), no_apps());
+    assert!(titles(&p, "holiday").is_empty());
+    assert_eq!(titles(&p, "secret"), vec![".secret"]);
+}
+
+#[test]
+fn results_carry_path_size_and_mtime() {
+    let dir = tree();
+    let p = FilesProvider::with_index(FileIndex::build(&config(dir.path())), no_apps());
+
+    let results = p.search("notes/todo");
+    let todo = &results[0];
+    let path = dir.path().join("notes/todo.md");
+    assert_eq!(todo.key, format!("files:{}", path.display()));
+
+    let extras = todo.extras.as_ref().unwrap();
+    assert_eq!(extras["path"], path.to_string_lossy().as_ref());
+    assert_eq!(extras["size"], 18);
+    assert!(extras["mtime"].as_u64().unwrap() > 0);
+    assert_eq!(extras["copyText"], extras["path"]);
+    assert_eq!(extras["defaultAction"], "open");
+    assert_eq!(
+        extras["actions"],
+        serde_json::json!(["open", "open_with", "reveal", "copy_path"])
+    );
+    assert_eq!(
+        extras["queryTexts"]["open_with"],
+        format!("/apps file://{}", path.display())
+    );
+
+    let results = p.search("todo");
+    let ranges = &results[0].matches.as_ref().unwrap().title;
+    assert_eq!((ranges[0].start, ranges[0].end), (0, 4));
+}
+
+#[test]
+fn preview_shows_text_or_metadata() {
+    let dir = tree();
+    let p = FilesProvider::with_index(FileIndex::build(&config(dir.path())), no_apps());
+
+    let key = |rel: &str| format!("files:{}", dir.path().join(rel).display());
+
+    let text = p.preview(&key("notes/todo.md")).unwrap();
+    assert_eq!(text.data["snippet"], "# Todo\n- buy milk");
+
+    let binary = p.preview(&key("Photos/holiday.png")).unwrap();
+    assert!(binary.data.get("snippet").is_none());
+    assert_eq!(binary.data["comment"], "16 bytes");
+
+    let folder = p.preview(&key("notes")).unwrap();
+    assert_eq!(folder.data["comment"], "Folder · 2 items");
+}
+
+#[test]
+fn actions_check_the_file_still_exists() {
+    let dir = tree();
+    let p = FilesProvider::with_index(FileIndex::build(&config(dir.path())), no_apps());
+    let key = format!("files:{}", dir.path().join("src/main.rs").display());
+
+    assert!(p.execute("copy_path", &key));
+    assert!(p.execute("open_with", &key));
+    assert!(!p.execute("copy_path", "files:/nonexistent/file"));
+    // No apps are installed, so there is nothing to open it with.
+    assert!(!p.execute("open", &key));
+    assert!(!p.execute("reveal", &key));
+}
+
+fn wait_for(what: &str, mut cond: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !cond() {
+        assert!(Instant::now() < deadline, "timed out waiting for {what}");
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn watcher_keeps_the_index_current() {
+    let dir = tree();
+    let root = dir.path();
+    let index = FileIndex::spawn(&config(root));
+    wait_for("initial walk", || index.is_ready());
+    assert!(index.contains(&root.join("src/main.rs")));
+
+    write(root, "src/lib.rs", b"");
+    wait_for("new file", || index.contains(&root.join("src/lib.rs")));
+
+    write(root, "drafts/deep/idea.txt", b"");
+    wait_for("new tree", || {
+        index.contains(&root.join("drafts/deep/idea.txt"))
+    });
+
+    fs::rename(root.join("notes/todo.md"), root.join("notes/done.md")).unwrap();
+    wait_for("rename", || {
+        index.contains(&root.join("notes/done.md")) && !index.contains(&root.join("notes/todo.md"))
+    });
+
+    fs::remove_dir_all(root.join("drafts")).unwrap();
+    wait_for("removal", || {
+        !index.contains(&root.join("drafts/deep/idea.txt"))
+    });
+
+    write(root, "src/.gitignore", b"*.rs\n");
+    wait_for("ignore rules", || {
+        !index.contains(&root.join("src/main.rs"))
+    });
+}
@@ -0,0 +1,30 @@
+//! The system clipboard, written by the engine itself so a decrypted
+//! password never travels through the UI.
+
+use anyhow::Result;
+use lancea_model::clipboard;
+
+pub trait Clipboard: Send + Sync {
+    /// Current clipboard text, if any.
+    fn get(&self) -> Option<String>;
+    fn set(&self, text: &str) -> Result<()>;
+    fn clear(&self) -> Result<()>;
+}
+
+/// `wl-clipboard` on Wayland, `xclip` on X11.
+#[derive(Debug, Default)]
+pub struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+    fn get(&self) -> Option<String> {
+        clipboard::get()
+    }
+
+    fn set(&self, text: &str) -> Result<()> {
+        Ok(clipboard::set(text)?)
+    }
+
+    fn clear(&self) -> Result<()> {
+        Ok(clipboard::clear()?)
+    }
+}
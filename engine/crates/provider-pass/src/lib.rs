@@ -0,0 +1,336 @@
+//! Passwords from a `pass` store: `/pass term` searches entry names under
+//! `~/.password-store`, and choosing one decrypts it and copies its first
+//! line. The engine copies it itself and clears the clipboard again after
+//! `clip_seconds`, so the password never reaches the UI, the logs or a
+//! result's extras.
+
+mod clipboard;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{
+    MatchRange, Matches, Outcome, Preview, Provider, ResultItem, command_query, tildify,
+};
+use serde::Deserialize;
+
+pub use clipboard::{Clipboard, SystemClipboard};
+
+const PROVIDER_ID: &str = "pass";
+const KEY_PREFIX: &str = "pass:";
+const PREFIX: &str = "/pass";
+
+/// Stands for the entry's `.gpg` file in the decrypt command.
+const FILE_PLACEHOLDER: &str = "{file}";
+
+/// The `[pass]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PassConfig {
+    /// Defaults to `$PASSWORD_STORE_DIR`, else `~/.password-store`.
+    pub store_dir: Option<PathBuf>,
+    /// Prints the decrypted entry on stdout. `{file}` is replaced by the
+    /// entry's file, which is appended when there is no `{file}`.
+    pub decrypt: Vec<String>,
+    /// How long the password stays on the clipboard; 0 leaves it there.
+    pub clip_seconds: u64,
+    pub max_results: usize,
+}
+
+impl Default for PassConfig {
+    fn default() -> Self {
+        Self {
+            store_dir: None,
+            decrypt: ["gpg", "--quiet", "--batch", "--decrypt", FILE_PLACEHOLDER]
+                .map(String::from)
+                .to_vec(),
+            clip_seconds: 45,
+            max_results: 50,
+        }
+    }
+}
+
+pub struct PassProvider {
+    config: PassConfig,
+    store: Option<PathBuf>,
+    clipboard: Arc<dyn Clipboard>,
+    /// The password last copied, until it is cleared again.
+    copied: Arc<Mutex<Option<String>>>,
+}
+
+impl PassProvider {
+    pub fn new(config: PassConfig) -> Self {
+        Self::with_clipboard(config, Arc::new(SystemClipboard))
+    }
+
+    /// Uses `clipboard` instead of the system one, e.g. a fake in tests.
+    pub fn with_clipboard(config: PassConfig, clipboard: Arc<dyn Clipboard>) -> Self {
+        let store = config
+            .store_dir
+            .clone()
+            .or_else(|| std::env::var_os("PASSWORD_STORE_DIR").map(PathBuf::from))
+            .or_else(|| dirs::home_dir().map(|h| h.join(".password-store")));
+        Self {
+            config,
+            store,
+            clipboard,
+            copied: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn store(&self) -> Result<&Path> {
+        self.store.as_deref().context("No password store")
+    }
+
+    /// Entry names, like `email/work`, in name order. Hidden files and
+    /// directories such as `.git` are skipped.
+    pub fn entries(&self) -> Result<Vec<String>> {
+        let store = self.store()?;
+        let mut out = Vec::new();
+        collect(store, store, &mut out)?;
+        out.sort();
+        Ok(out)
+    }
+
+    /// Entries whose name matches the query, all of them without one.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &[PREFIX]);
+        let Ok(entries) = self.entries() else {
+            return Vec::new();
+        };
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let mut hits: Vec<(i64, Vec<usize>, String)> = entries
+            .into_iter()
+            .filter_map(|name| {
+                if q.is_empty() {
+                    return Some((0, Vec::new(), name));
+                }
+                let (score, idx) = matcher.fuzzy_indices(&name, q)?;
+                Some((score, idx, name))
+            })
+            .collect();
+        // Stable, so ties stay in name order.
+        hits.sort_by_key(|(score, ..)| std::cmp::Reverse(*score));
+        hits.truncate(self.config.max_results);
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, idx, name))| to_result_item(&name, 1.0 - rank as f32 / count, idx))
+            .collect()
+    }
+
+    /// The entry's file, if `key` names an entry of the store.
+    fn entry_file(&self, key: &str) -> Result<(String, PathBuf)> {
+        let name = key
+            .strip_prefix(KEY_PREFIX)
+            .filter(|n| !n.is_empty())
+            .with_context(|| format!("Not a pass key: {key}"))?;
+        // Only listed entries, so a key can't point outside the store.
+        if !self.entries()?.iter().any(|e| e == name) {
+            bail!("No entry {name} in the password store");
+        }
+        let file = self.store()?.join(format!("{name}.gpg"));
+        Ok((name.to_string(), file))
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let (name, file) = self.entry_file(key).ok()?;
+        let mut lines = vec![format!("File: {}", tildify(&file))];
+        if self.config.clip_seconds > 0 {
+            lines.push(format!(
+                "Copying clears the clipboard after {} s",
+                self.config.clip_seconds
+            ));
+        }
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": name,
+                "comment": "Password store entry",
+                "snippet": lines.join("\n"),
+            }),
+        })
+    }
+
+    /// Runs the decrypt command on `file` and returns the first line of
+    /// its output. Errors never include the output.
+    fn decrypt(&self, name: &str, file: &Path) -> Result<String> {
+        let file = file.to_string_lossy();
+        let mut argv: Vec<String> = self
+            .config
+            .decrypt
+            .iter()
+            .map(|a| a.replace(FILE_PLACEHOLDER, &file))
+            .collect();
+        if !self
+            .config
+            .decrypt
+            .iter()
+            .any(|a| a.contains(FILE_PLACEHOLDER))
+        {
+            argv.push(file.into_owned());
+        }
+        let (program, args) = argv.split_first().context("Empty decrypt command")?;
+
+        let out = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .with_context(|| format!("failed to spawn {program}"))?;
+        if !out.status.success() {
+            bail!(
+                "Decrypting {name} failed: {program} exited with {}",
+                out.status
+            );
+        }
+        let text = String::from_utf8(out.stdout)
+            .ok()
+            .with_context(|| format!("{name} isn't text"))?;
+        let secret = text
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('\r');
+        if secret.is_empty() {
+            bail!("{name} has no password");
+        }
+        Ok(secret.to_string())
+    }
+
+    /// Decrypts the entry behind `key` and copies its password, to be
+    /// cleared after `clip_seconds` unless something else was copied since.
+    pub fn execute_clip(&self, key: &str) -> Result<String> {
+        let (name, file) = self.entry_file(key)?;
+        let secret = self.decrypt(&name, &file)?;
+        // Marked first, so clipboard history already leaves it out when
+        // the UI reports the change.
+        if let Ok(mut copied) = self.copied.lock() {
+            *copied = Some(secret.clone());
+        }
+        self.clipboard.set(&secret)?;
+
+        let seconds = self.config.clip_seconds;
+        if seconds == 0 {
+            return Ok(format!("Copied the password for {name}"));
+        }
+        let clipboard = self.clipboard.clone();
+        let copied = self.copied.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(seconds));
+            if clipboard.get().as_deref() == Some(secret.as_str()) {
+                let _ = clipboard.clear();
+            }
+            if let Ok(mut copied) = copied.lock()
+                && copied.as_deref() == Some(secret.as_str())
+            {
+                *copied = None;
+            }
+        });
+        Ok(format!(
+            "Copied the password for {name}, clearing in {seconds} s"
+        ))
+    }
+
+    /// Whether `text` is the password this provider has on the clipboard,
+    /// so clipboard history can leave it out.
+    pub fn is_copied_password(&self, text: &str) -> bool {
+        self.copied
+            .lock()
+            .is_ok_and(|copied| copied.as_deref() == Some(text))
+    }
+}
+
+impl Provider for PassProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_with_args(action, key, &[])
+    }
+
+    fn execute_with_args(&self, action: &str, key: &str, args: &[String]) -> bool {
+        self.execute_outcome(action, key, args).status == "ok"
+    }
+
+    fn execute_outcome(&self, action: &str, key: &str, args: &[String]) -> Outcome {
+        let result = match action {
+            "clip_password" if args.is_empty() => self.execute_clip(key),
+            _ => Err(anyhow::anyhow!("Unknown action {action} for {key}")),
+        };
+        match result {
+            Ok(message) => Outcome {
+                status: "ok".into(),
+                message: Some(message),
+                exit_code: None,
+            },
+            Err(e) => Outcome {
+                status: "error".into(),
+                message: Some(format!("{e:#}")),
+                exit_code: None,
+            },
+        }
+    }
+}
+
+/// Adds the names of the `.gpg` files under `dir` to `out`, relative to
+/// `store` and without the extension.
+fn collect(store: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    let entries = fs::read_dir(dir).with_context(|| format!("read {}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect(store, &path, out)?;
+        } else if path.extension().is_some_and(|e| e == "gpg")
+            && let Ok(relative) = path.with_extension("").strip_prefix(store)
+        {
+            out.push(relative.to_string_lossy().into_owned());
+        }
+    }
+    Ok(())
+}
+
+fn to_result_item(name: &str, score: f32, indices: Vec<usize>) -> ResultItem {
+    let subtitle = match name.rsplit_once('/') {
+        Some((folder, _)) => format!("Password · {folder}"),
+        None => "Password".to_string(),
+    };
+
+    ResultItem {
+        key: format!("{KEY_PREFIX}{name}"),
+        title: name.to_string(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": subtitle,
+            "icon": "dialog-password",
+            "defaultAction": "clip_password",
+            "actions": ["clip_password"],
+        })),
+        matches: (!indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(name, indices),
+            subtitle: None,
+        }),
+    }
+}
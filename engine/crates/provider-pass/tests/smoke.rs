@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use lancea_model::Provider;
+use lancea_provider_pass::{Clipboard, PassConfig, PassProvider};
+use lancea_testkit::titles;
+
+const SECRET: &str = "hunter2-correct-horse";
+
+#[derive(Default)]
+struct FakeClipboard(Mutex<Option<String>>);
+
+impl FakeClipboard {
+    fn text(&self) -> Option<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Clipboard for FakeClipboard {
+    fn get(&self) -> Option<String> {
+        self.text()
+    }
+
+    fn set(&self, text: &str) -> Result<()> {
+        *self.0.lock().unwrap() = Some(text.to_string());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        *self.0.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+/// A store whose "encrypted" files are plain text, read back by a stub
+/// decrypt command instead of gpg.
+fn store(dir: &Path) {
+    fs::create_dir_all(dir.join("email")).unwrap();
+    fs::create_dir_all(dir.join(".git")).unwrap();
+    fs::write(
+        dir.join("email/work.gpg"),
+        format!("{SECRET}\nuser: alice@example.com\n"),
+    )
+    .unwrap();
+    fs::write(dir.join("email/home.gpg"), "other-secret\n").unwrap();
+    fs::write(dir.join("github.gpg"), "\nno password on the first line\n").unwrap();
+    fs::write(dir.join(".gpg-id"), "alice@example.com\n").unwrap();
+    fs::write(dir.join(".git/HEAD.gpg"), "not an entry\n").unwrap();
+}
+
+fn setup(clip_seconds: u64) -> (tempfile::TempDir, PassProvider, Arc<FakeClipboard>) {
+    let dir = tempfile::tempdir().unwrap();
+    store(dir.path());
+    let clipboard = Arc::new(FakeClipboard::default());
+    let config = PassConfig {
+        store_dir: Some(dir.path().to_path_buf()),
+        decrypt: vec!["sh".into(), "-c".into(), "cat \"$1\"".into(), "sh".into()],
+        clip_seconds,
+        ..PassConfig::default()
+    };
+    let p = PassProvider::with_clipboard(config, clipboard.clone());
+    (dir, p, clipboard)
+}
+
+#[test]
+fn lists_and_searches_entry_names() {
+    let (_dir, p, _) = setup(45);
+
+    assert_eq!(
+        titles(&p, "/pass"),
+        vec!["email/home", "email/work", "github"]
+    );
+    assert_eq!(titles(&p, "/PASS work")[0], "email/work");
+    assert_eq!(titles(&p, "/pass ghub"), vec!["github"]);
+
+    let items = p.search("/pass work");
+    let extras = items[0].extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], "Password · email");
+    assert_eq!(extras["defaultAction"], "clip_password");
+}
+
+#[test]
+fn copies_the_first_line_without_leaking_it() {
+    let (_dir, p, clipboard) = setup(45);
+
+    let outcome = p.execute_outcome("clip_password", "pass:email/work", &[]);
+    assert_eq!(outcome.status, "ok");
+    assert_eq!(clipboard.text().as_deref(), Some(SECRET));
+    assert!(p.is_copied_password(SECRET));
+    assert!(!p.is_copied_password("other-secret"));
+
+    let items = serde_json::to_string(&p.search("/pass")).unwrap();
+    let preview = serde_json::to_string(&p.preview("pass:email/work")).unwrap();
+    for shown in [items, preview, format!("{outcome:?}")] {
+        assert!(!shown.contains(SECRET), "{shown}");
+    }
+
+    let empty = p.execute_outcome("clip_password", "pass:github", &[]);
+    assert_eq!(empty.status, "error");
+    assert_eq!(empty.message.as_deref(), Some("github has no password"));
+    assert!(!p.execute("clip_password", "pass:../../etc/passwd"));
+    assert!(!p.execute("clip_password", "pass:.git/HEAD"));
+    assert_eq!(clipboard.text().as_deref(), Some(SECRET));
+}
+
+#[test]
+fn clears_the_clipboard_only_if_it_still_holds_the_password() {
+    let (_dir, p, clipboard) = setup(1);
+    let (_other_dir, other, other_clipboard) = setup(1);
+
+    assert!(p.execute("clip_password", "pass:email/work"));
+    assert!(other.execute("clip_password", "pass:email/work"));
+    other_clipboard.set("copied since").unwrap();
+    thread::sleep(Duration::from_millis(1500));
+
+    assert_eq!(clipboard.text(), None);
+    assert!(!p.is_copied_password(SECRET));
+    assert_eq!(other_clipboard.text().as_deref(), Some("copied since"));
+}
+
+#[test]
+fn reports_a_failing_decrypt_command() {
+    let dir = tempfile::tempdir().unwrap();
+    store(dir.path());
+    let clipboard = Arc::new(FakeClipboard::default());
+    let config = PassConfig {
+        store_dir: Some(dir.path().to_path_buf()),
+        decrypt: vec!["sh".into(), "-c".into(), format!("echo {SECRET}; exit 2")],
+        ..PassConfig::default()
+    };
+    let p = PassProvider::with_clipboard(config, clipboard.clone());
+
+    let outcome = p.execute_outcome("clip_password", "pass:email/work", &[]);
+    assert_eq!(outcome.status, "error");
+    assert!(!outcome.message.unwrap().contains(SECRET));
+    assert_eq!(clipboard.text(), None);
+}
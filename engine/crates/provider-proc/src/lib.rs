@@ -0,0 +1,333 @@
+//! Running processes from `/proc`: `/kill fire` finds them by name, command
+//! line or PID, shows their CPU and memory use, and ends them. Signalling
+//! needs the UI's confirmation, passed as the `confirm` argument.
+
+mod procfs;
+mod signal;
+
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, bail};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Outcome, Preview, Provider, ResultItem, command_query};
+use serde::Deserialize;
+
+pub use procfs::Process;
+pub use signal::{Signal, Signaller, SystemKill};
+
+const PROVIDER_ID: &str = "proc";
+const KEY_PREFIX: &str = "proc:";
+const PREFIX: &str = "/kill";
+
+/// The argument that confirms a signal.
+pub const CONFIRM: &str = "confirm";
+
+/// Clock ticks per second in `/proc/<pid>/stat`; `USER_HZ` is 100 on every
+/// architecture Linux exposes it on.
+const TICKS_PER_SEC: f64 = 100.0;
+
+/// The `[proc]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProcConfig {
+    /// List other users' processes too, not just the engine's own user's.
+    pub all_users: bool,
+    pub max_results: usize,
+}
+
+impl Default for ProcConfig {
+    fn default() -> Self {
+        Self {
+            all_users: false,
+            max_results: 50,
+        }
+    }
+}
+
+/// CPU time of every process at the previous search, to turn the next
+/// one's into current usage rather than a lifetime average.
+#[derive(Debug, Default)]
+struct Sample {
+    uptime: f64,
+    ticks: HashMap<(u32, u64), u64>,
+}
+
+/// A process with its usage worked out.
+#[derive(Debug, Clone)]
+struct Usage {
+    process: Process,
+    cpu_percent: f64,
+    mem_percent: f64,
+}
+
+pub struct ProcProvider {
+    config: ProcConfig,
+    root: PathBuf,
+    uid: Option<u32>,
+    signaller: Box<dyn Signaller>,
+    last: Mutex<Sample>,
+}
+
+impl ProcProvider {
+    /// Reads the system's `/proc` and lists the engine's own user's
+    /// processes unless `all_users` is set.
+    pub fn new(config: ProcConfig) -> Self {
+        let uid = std::fs::metadata("/proc/self").ok().map(|m| m.uid());
+        Self::with_root(config, "/proc", uid, Box::new(SystemKill))
+    }
+
+    /// Reads processes from `root` as owned by `uid`, all of them when
+    /// `None`, and signals them through `signaller`, e.g. fakes in tests.
+    pub fn with_root(
+        config: ProcConfig,
+        root: impl Into<PathBuf>,
+        uid: Option<u32>,
+        signaller: Box<dyn Signaller>,
+    ) -> Self {
+        Self {
+            config,
+            root: root.into(),
+            uid,
+            signaller,
+            last: Mutex::new(Sample::default()),
+        }
+    }
+
+    /// The processes that may be listed, kernel threads left out, with CPU
+    /// use since the previous sample. `remember` makes this the new sample.
+    fn usage(&self, remember: bool) -> Vec<Usage> {
+        let uptime = procfs::uptime(&self.root).unwrap_or(0.0);
+        let mem_total = procfs::mem_total_kb(&self.root).unwrap_or(0);
+        let processes: Vec<Process> = procfs::processes(&self.root)
+            .into_iter()
+            .filter(|p| !p.cmdline.is_empty())
+            .filter(|p| self.config.all_users || self.uid.is_none() || p.uid == self.uid)
+            .collect();
+
+        let Ok(mut last) = self.last.lock() else {
+            return Vec::new();
+        };
+        let elapsed = uptime - last.uptime;
+        let usage = processes
+            .into_iter()
+            .map(|p| {
+                let since_start = uptime - p.start_ticks as f64 / TICKS_PER_SEC;
+                let (ticks, secs) = match last.ticks.get(&(p.pid, p.start_ticks)) {
+                    Some(&before) if elapsed > 0.0 => (p.cpu_ticks.saturating_sub(before), elapsed),
+                    _ => (p.cpu_ticks, since_start),
+                };
+                let cpu_percent = if secs > 0.0 {
+                    ticks as f64 * 100.0 / TICKS_PER_SEC / secs
+                } else {
+                    0.0
+                };
+                let mem_percent = if mem_total > 0 {
+                    p.rss_kb as f64 * 100.0 / mem_total as f64
+                } else {
+                    0.0
+                };
+                Usage {
+                    process: p,
+                    cpu_percent,
+                    mem_percent,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if !remember {
+            return usage;
+        }
+        *last = Sample {
+            uptime,
+            ticks: usage
+                .iter()
+                .map(|u| ((u.process.pid, u.process.start_ticks), u.process.cpu_ticks))
+                .collect(),
+        };
+        usage
+    }
+
+    /// Processes whose PID starts with the query, or whose name or else
+    /// command line matches it; the busiest first when there is no query.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &[PREFIX]);
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        // (tier, score): PID prefixes above name matches above command lines.
+        let mut hits: Vec<((u8, i64), Vec<usize>, Usage)> = self
+            .usage(true)
+            .into_iter()
+            .filter_map(|u| {
+                if q.is_empty() {
+                    return Some(((0, 0), Vec::new(), u));
+                }
+                if q.bytes().all(|b| b.is_ascii_digit()) && u.process.pid.to_string().starts_with(q)
+                {
+                    return Some(((2, 0), Vec::new(), u));
+                }
+                if let Some((score, idx)) = matcher.fuzzy_indices(&u.process.name, q) {
+                    return Some(((1, score), idx, u));
+                }
+                let score = matcher.fuzzy_match(&u.process.cmdline, q)?;
+                Some(((0, score), Vec::new(), u))
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then(b.2.cpu_percent.total_cmp(&a.2.cpu_percent))
+                .then(a.2.process.pid.cmp(&b.2.process.pid))
+        });
+        hits.truncate(self.config.max_results);
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, idx, u))| to_result_item(&u, 1.0 - rank as f32 / count, idx))
+            .collect()
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let (pid, start) = parse_key(key).ok()?;
+        let u = self
+            .usage(false)
+            .into_iter()
+            .find(|u| u.process.pid == pid && u.process.start_ticks == start)?;
+        let p = &u.process;
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": format!("{} ({})", p.name, p.pid),
+                "comment": format!(
+                    "{:.1}% CPU · {:.1}% memory · {}",
+                    u.cpu_percent,
+                    u.mem_percent,
+                    human_kb(p.rss_kb)
+                ),
+                "snippet": p.cmdline,
+            }),
+        })
+    }
+
+    /// The process behind `key`, if it is still the one that was listed
+    /// rather than a newer one that got its PID.
+    pub fn process(&self, key: &str) -> Result<Process> {
+        let (pid, start) = parse_key(key)?;
+        procfs::process(&self.root, pid)
+            .filter(|p| p.start_ticks == start)
+            .with_context(|| format!("Process {pid} is no longer running"))
+    }
+
+    /// Sends `signal` once the UI has confirmed it with [`CONFIRM`].
+    pub fn execute_signal(&self, key: &str, signal: Signal, args: &[String]) -> Result<()> {
+        if args.first().map(String::as_str) != Some(CONFIRM) {
+            bail!("Sending SIG{} needs confirmation", signal.name());
+        }
+        let p = self.process(key)?;
+        self.signaller.send(p.pid, signal)
+    }
+}
+
+impl Provider for ProcProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_with_args(action, key, &[])
+    }
+
+    fn execute_with_args(&self, action: &str, key: &str, args: &[String]) -> bool {
+        self.execute_outcome(action, key, args).status == "ok"
+    }
+
+    fn execute_outcome(&self, action: &str, key: &str, args: &[String]) -> Outcome {
+        let result = match action {
+            "terminate" => self
+                .execute_signal(key, Signal::Term, args)
+                .map(|()| "Asked it to quit".to_string()),
+            "kill" => self
+                .execute_signal(key, Signal::Kill, args)
+                .map(|()| "Killed".to_string()),
+            "copy_pid" => self.process(key).map(|_| "Copied".to_string()),
+            _ => Err(anyhow::anyhow!("Unknown action {action}")),
+        };
+
+        match result {
+            Ok(message) => Outcome {
+                status: "ok".into(),
+                message: Some(message),
+                exit_code: None,
+            },
+            Err(e) => Outcome {
+                status: "error".into(),
+                message: Some(format!("{e:#}")),
+                exit_code: None,
+            },
+        }
+    }
+}
+
+fn to_result_item(u: &Usage, score: f32, indices: Vec<usize>) -> ResultItem {
+    let p = &u.process;
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}:{}", p.pid, p.start_ticks),
+        title: p.name.clone(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": format!(
+                "{} · {:.1}% CPU · {:.1}% memory · {}",
+                p.pid, u.cpu_percent, u.mem_percent, p.cmdline
+            ),
+            "pid": p.pid,
+            "cpuPercent": u.cpu_percent,
+            "memPercent": u.mem_percent,
+            "rssKb": p.rss_kb,
+            "copyText": p.pid.to_string(),
+            "defaultAction": "terminate",
+            "actions": ["terminate", "kill", "copy_pid"],
+            // The UI asks before running these.
+            "confirm": ["terminate", "kill"],
+            "confirmText": format!("End {} ({})?", p.name, p.pid),
+        })),
+        matches: (!indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(&p.name, indices),
+            subtitle: None,
+        }),
+    }
+}
+
+/// `proc:<pid>:<start ticks>`.
+fn parse_key(key: &str) -> Result<(u32, u64)> {
+    let (pid, start) = key
+        .strip_prefix(KEY_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .with_context(|| format!("Not a process key: {key}"))?;
+    Ok((
+        pid.parse().with_context(|| format!("Bad PID in {key}"))?,
+        start
+            .parse()
+            .with_context(|| format!("Bad start time in {key}"))?,
+    ))
+}
+
+fn human_kb(kb: u64) -> String {
+    match kb {
+        0..1024 => format!("{kb} kB"),
+        1024..1_048_576 => format!("{:.1} MB", kb as f64 / 1024.0),
+        _ => format!("{:.1} GB", kb as f64 / 1_048_576.0),
+    }
+}
@@ -0,0 +1,100 @@
+//! Reading processes out of a `/proc`-style directory. Everything goes
+//! through `root`, so tests can point it at a fake tree.
+
+use std::fs;
+use std::path::Path;
+
+/// What `/proc/<pid>` says about one process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Process {
+    pub pid: u32,
+    /// `comm`, the kernel's (possibly truncated) name for it.
+    pub name: String,
+    /// Arguments joined by spaces; empty for kernel threads.
+    pub cmdline: String,
+    pub uid: Option<u32>,
+    /// Clock ticks since boot at which it started; with `pid`, identifies
+    /// it even after the pid is reused.
+    pub start_ticks: u64,
+    /// User plus system time, in clock ticks.
+    pub cpu_ticks: u64,
+    pub rss_kb: u64,
+}
+
+/// Every process under `root` that could be read; processes exit while
+/// this runs, so unreadable ones are skipped.
+pub fn processes(root: &Path) -> Vec<Process> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut out: Vec<Process> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| process(root, pid))
+        .collect();
+    out.sort_by_key(|p| p.pid);
+    out
+}
+
+pub fn process(root: &Path, pid: u32) -> Option<Process> {
+    let dir = root.join(pid.to_string());
+    let stat = fs::read_to_string(dir.join("stat")).ok()?;
+    let (name, fields) = parse_stat(&stat)?;
+    let field = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok());
+
+    let cmdline = fs::read(dir.join("cmdline"))
+        .map(|raw| {
+            raw.split(|b| *b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+    let status = fs::read_to_string(dir.join("status")).unwrap_or_default();
+
+    Some(Process {
+        pid,
+        name: name.to_string(),
+        cmdline,
+        uid: status_field(&status, "Uid"),
+        // Fields 14, 15 and 22 of stat(5), counted after the name.
+        cpu_ticks: field(11)? + field(12)?,
+        start_ticks: field(19)?,
+        rss_kb: status_field(&status, "VmRSS").unwrap_or(0),
+    })
+}
+
+/// The name between the parentheses, which may itself contain spaces and
+/// parentheses, and the fields after it, starting with the state.
+fn parse_stat(stat: &str) -> Option<(&str, Vec<&str>)> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let name = stat.get(open + 1..close)?;
+    Some((name, stat[close + 1..].split_whitespace().collect()))
+}
+
+/// The first number of a `Key:` line in `status` or `meminfo`.
+fn status_field<T: std::str::FromStr>(text: &str, key: &str) -> Option<T> {
+    text.lines()
+        .find_map(|l| l.strip_prefix(key)?.strip_prefix(':'))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Seconds since boot, from `uptime`.
+pub fn uptime(root: &Path) -> Option<f64> {
+    fs::read_to_string(root.join("uptime"))
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// `MemTotal` from `meminfo`, in kB.
+pub fn mem_total_kb(root: &Path) -> Option<u64> {
+    status_field(&fs::read_to_string(root.join("meminfo")).ok()?, "MemTotal")
+}
@@ -0,0 +1,43 @@
+//! Sending signals to processes.
+
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Term,
+    Kill,
+}
+
+impl Signal {
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::Term => "TERM",
+            Signal::Kill => "KILL",
+        }
+    }
+}
+
+pub trait Signaller: Send + Sync {
+    fn send(&self, pid: u32, signal: Signal) -> Result<()>;
+}
+
+/// `kill(1)`, so the engine needs no libc bindings.
+#[derive(Debug, Default)]
+pub struct SystemKill;
+
+impl Signaller for SystemKill {
+    fn send(&self, pid: u32, signal: Signal) -> Result<()> {
+        let output = Command::new("kill")
+            .args(["-s", signal.name(), &pid.to_string()])
+            .stdin(Stdio::null())
+            .output()
+            .context("failed to run kill")?;
+        if !output.status.success() {
+            let err = String::from_utf8_lossy(&output.stderr);
+            bail!("kill -s {} {pid}: {}", signal.name(), err.trim());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use lancea_model::Provider;
+use lancea_provider_proc::{ProcConfig, ProcProvider, Signal, Signaller};
+use lancea_testkit::titles;
+use tempfile::TempDir;
+
+type Sent = Arc<Mutex<Vec<(u32, Signal)>>>;
+
+struct FakeKill(Sent);
+
+impl Signaller for FakeKill {
+    fn send(&self, pid: u32, signal: Signal) -> Result<()> {
+        self.0.lock().unwrap().push((pid, signal));
+        Ok(())
+    }
+}
+
+/// Writes `/proc/<pid>` as the provider reads it: `stat` with the name in
+/// parentheses, NUL-separated `cmdline` and `status` with the owner and RSS.
+fn process(root: &Path, pid: u32, name: &str, argv: &[&str], uid: u32, cpu: u64, start: u64) {
+    let dir = root.join(pid.to_string());
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("stat"),
+        format!("{pid} ({name}) S 1 {pid} {pid} 0 -1 4194304 100 0 0 0 {cpu} 0 0 0 20 0 1 0 {start} 1000 100\n"),
+    )
+    .unwrap();
+    let mut cmdline = argv.join("\0");
+    if !cmdline.is_empty() {
+        cmdline.push('\0');
+    }
+    fs::write(dir.join("cmdline"), cmdline).unwrap();
+    fs::write(
+        dir.join("status"),
+        format!("Name:\t{name}\nUid:\t{uid}\t{uid}\t{uid}\t{uid}\nVmRSS:\t  204800 kB\n"),
+    )
+    .unwrap();
+}
+
+fn uptime(root: &Path, secs: f64) {
+    fs::write(root.join("uptime"), format!("{secs:.2} 1234.00\n")).unwrap();
+}
+
+fn setup(config: ProcConfig) -> (TempDir, ProcProvider, Sent) {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::write(
+        root.join("meminfo"),
+        "MemTotal:       2048000 kB\nMemFree: 1 kB\n",
+    )
+    .unwrap();
+    uptime(root, 100.0);
+    process(root, 1, "systemd", &["/sbin/init"], 0, 500, 1);
+    process(root, 2, "kthreadd", &[], 0, 0, 1);
+    process(
+        root,
+        4242,
+        "firefox",
+        &["/usr/lib/firefox/firefox", "-P", "work"],
+        1000,
+        5000,
+        0,
+    );
+    process(
+        root,
+        777,
+        "Web Content",
+        &["/usr/lib/firefox/firefox", "-contentproc"],
+        1000,
+        100,
+        5000,
+    );
+    process(
+        root,
+        31337,
+        "python3",
+        &["python3", "-m", "http.server"],
+        1000,
+        10,
+        9000,
+    );
+
+    let sent = Sent::default();
+    let p = ProcProvider::with_root(config, root, Some(1000), Box::new(FakeKill(sent.clone())));
+    (dir, p, sent)
+}
+
+#[test]
+fn lists_own_processes_busiest_first() {
+    let (dir, p, _) = setup(ProcConfig::default());
+
+    assert_eq!(
+        titles(&p, "/kill"),
+        vec!["firefox", "Web Content", "python3"]
+    );
+
+    let all = ProcProvider::with_root(
+        ProcConfig {
+            all_users: true,
+            ..ProcConfig::default()
+        },
+        dir.path(),
+        Some(1000),
+        Box::new(FakeKill(Sent::default())),
+    );
+    // Kernel threads have no command line and are never listed.
+    assert_eq!(
+        titles(&all, "/kill"),
+        vec!["firefox", "systemd", "Web Content", "python3"]
+    );
+
+    let firefox = &p.search("/kill firefox")[0];
+    let extras = firefox.extras.as_ref().unwrap();
+    assert_eq!(extras["pid"], 4242);
+    // 50 s of CPU over 100 s of uptime; 200 MB of 2 GB.
+    assert_eq!(extras["cpuPercent"], 50.0);
+    assert_eq!(extras["memPercent"], 10.0);
+    assert_eq!(extras["copyText"], "4242");
+}
+
+#[test]
+fn searches_name_command_line_and_pid() {
+    let (_dir, p, _) = setup(ProcConfig::default());
+
+    assert_eq!(titles(&p, "/kill PYTH")[0], "python3");
+    assert_eq!(titles(&p, "/kill http.server"), vec!["python3"]);
+    assert_eq!(titles(&p, "/kill 313"), vec!["python3"]);
+    assert_eq!(titles(&p, "/kill contentproc"), vec!["Web Content"]);
+    // A name match outranks another process's command line.
+    assert_eq!(titles(&p, "/kill firef"), vec!["firefox", "Web Content"]);
+}
+
+#[test]
+fn cpu_usage_is_measured_between_searches() {
+    let (dir, p, _) = setup(ProcConfig::default());
+    p.search("/kill");
+
+    // Firefox uses 4 s of CPU in the next 10 s; python3 starts spinning.
+    uptime(dir.path(), 110.0);
+    process(dir.path(), 4242, "firefox", &["firefox"], 1000, 5400, 0);
+    process(dir.path(), 31337, "python3", &["python3"], 1000, 1010, 9000);
+
+    let items = p.search("/kill");
+    let cpu: Vec<(String, f64)> = items
+        .iter()
+        .map(|r| {
+            (
+                r.title.clone(),
+                r.extras.as_ref().unwrap()["cpuPercent"].as_f64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        cpu,
+        vec![
+            ("python3".to_string(), 100.0),
+            ("firefox".to_string(), 40.0),
+            ("Web Content".to_string(), 0.0),
+        ]
+    );
+}
+
+#[test]
+fn signals_need_confirmation_and_the_same_process() {
+    let (dir, p, sent) = setup(ProcConfig::default());
+    let key = p.search("/kill python3")[0].key.clone();
+    let confirm = vec!["confirm".to_string()];
+
+    let refused = p.execute_outcome("terminate", &key, &[]);
+    assert_eq!(refused.status, "error");
+    assert!(!p.execute("kill", &key));
+    assert!(sent.lock().unwrap().is_empty());
+
+    assert!(p.execute_with_args("terminate", &key, &confirm));
+    assert!(p.execute_with_args("kill", &key, &confirm));
+    assert!(p.execute("copy_pid", &key));
+    assert_eq!(
+        *sent.lock().unwrap(),
+        vec![(31337, Signal::Term), (31337, Signal::Kill)]
+    );
+
+    // The PID now belongs to a process started later.
+    process(dir.path(), 31337, "python3", &["python3"], 1000, 0, 9500);
+    let outcome = p.execute_outcome("kill", &key, &confirm);
+    assert_eq!(outcome.status, "error");
+    assert!(outcome.message.unwrap().contains("no longer running"));
+    assert_eq!(sent.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,220 @@
+//! Recently used documents from `recently-used.xbel`, newest first, opened
+//! with the desktop's default application.
+
+mod xbel;
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Preview, Provider, ResultItem, command_query, tildify};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use xbel::{RecentEntry, parse, parse_timestamp};
+
+const PROVIDER_ID: &str = "recent";
+const KEY_PREFIX: &str = "recent:";
+
+/// The `[recent]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RecentConfig {
+    /// Defaults to `$XDG_DATA_HOME/recently-used.xbel`.
+    pub file: Option<PathBuf>,
+    pub max_results: usize,
+}
+
+impl Default for RecentConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            max_results: 50,
+        }
+    }
+}
+
+pub struct RecentProvider {
+    path: Option<PathBuf>,
+    /// Entries as of the file's last seen mtime; re-read when it changes.
+    cache: Mutex<Option<(SystemTime, Arc<Vec<RecentEntry>>)>>,
+    apps: Arc<AppsProvider>,
+    max_results: usize,
+}
+
+impl RecentProvider {
+    pub fn new(config: RecentConfig, apps: Arc<AppsProvider>) -> Self {
+        let path = config
+            .file
+            .or_else(|| dirs::data_dir().map(|d| d.join("recently-used.xbel")));
+
+        Self {
+            path,
+            cache: Mutex::new(None),
+            apps,
+            max_results: config.max_results,
+        }
+    }
+
+    /// Current entries, newest first, without ones whose file is gone.
+    pub fn entries(&self) -> Vec<RecentEntry> {
+        let Some(all) = self.load() else {
+            return Vec::new();
+        };
+        all.iter()
+            .filter(|e| e.path.as_ref().is_none_or(|p| p.exists()))
+            .cloned()
+            .collect()
+    }
+
+    fn load(&self) -> Option<Arc<Vec<RecentEntry>>> {
+        let path = self.path.as_deref()?;
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+
+        let mut cache = self.cache.lock().ok()?;
+        if let Some((seen, entries)) = cache.as_ref()
+            && *seen == modified
+        {
+            return Some(entries.clone());
+        }
+
+        let mut entries = parse(&fs::read_to_string(path).ok()?).ok()?;
+        entries.sort_by_key(|e| Reverse(e.timestamp));
+        let entries = Arc::new(entries);
+        *cache = Some((modified, entries.clone()));
+        Some(entries)
+    }
+
+    /// Every recent entry for an empty query; otherwise fuzzy matches on
+    /// the name, or on the whole path or URI, with ties going to the more
+    /// recent entry.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &["/recent", "/rec"]);
+        let entries = self.entries();
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let mut hits: Vec<(i64, usize, Vec<usize>)> = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| {
+                if q.is_empty() {
+                    return Some((0, i, Vec::new()));
+                }
+                let by_name = matcher.fuzzy_indices(&e.name, q);
+                let by_path = matcher.fuzzy_match(&location(e), q);
+                match by_name {
+                    Some((s, idx)) if by_path.is_none_or(|p| s >= p) => Some((s, i, idx)),
+                    _ => by_path.map(|p| (p, i, Vec::new())),
+                }
+            })
+            .collect();
+
+        // Entries are already newest first, so index breaks score ties.
+        hits.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        hits.truncate(self.max_results);
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, i, idx))| to_result_item(&entries[i], 1.0 - rank as f32 / count, idx))
+            .collect()
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let uri = key.strip_prefix(KEY_PREFIX)?;
+        let entry = self.load()?.iter().find(|e| e.uri == uri)?.clone();
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": entry.name,
+                "path": location(&entry),
+                "comment": entry.app.as_ref().map(|a| format!("Last used with {a}")),
+                "mimeType": entry.mime_type,
+                "timestamp": entry.timestamp,
+            }),
+        })
+    }
+
+    pub fn execute_open(&self, key: &str) -> Result<()> {
+        let uri = key
+            .strip_prefix(KEY_PREFIX)
+            .with_context(|| format!("Not a recent key: {key}"))?;
+        let entry = self
+            .entries()
+            .into_iter()
+            .find(|e| e.uri == uri)
+            .with_context(|| format!("{uri} is no longer available"))?;
+        self.apps.open_default(&[location(&entry)])
+    }
+}
+
+/// Local path when there is one, the URI otherwise.
+fn location(entry: &RecentEntry) -> String {
+    match &entry.path {
+        Some(p) => p.to_string_lossy().into_owned(),
+        None => entry.uri.clone(),
+    }
+}
+
+fn to_result_item(entry: &RecentEntry, score: f32, indices: Vec<usize>) -> ResultItem {
+    let location = location(entry);
+    let folder = entry.path.as_ref().and_then(|p| p.parent()).map(tildify);
+    let subtitle = match (&folder, &entry.app) {
+        (Some(f), Some(a)) => format!("{f} · {a}"),
+        (Some(f), None) => f.clone(),
+        (None, Some(a)) => format!("{} · {a}", entry.uri),
+        (None, None) => entry.uri.clone(),
+    };
+
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}", entry.uri),
+        title: entry.name.clone(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": subtitle,
+            "path": location,
+            "uri": entry.uri,
+            "app": entry.app,
+            "mimeType": entry.mime_type,
+            "timestamp": entry.timestamp,
+            "copyText": location,
+            "defaultAction": "open",
+            "actions": ["open", "copy_path"],
+        })),
+        matches: (!indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(&entry.name, indices),
+            subtitle: None,
+        }),
+    }
+}
+
+impl Provider for RecentProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "open" => self.execute_open(key).is_ok(),
+            "copy_path" => key
+                .strip_prefix(KEY_PREFIX)
+                .is_some_and(|uri| self.entries().iter().any(|e| e.uri == uri)),
+            _ => false,
+        }
+    }
+}
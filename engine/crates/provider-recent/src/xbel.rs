@@ -0,0 +1,153 @@
+//! Reader for the freedesktop recent-files store, an XBEL document with
+//! `mime` and `bookmark` metadata per entry.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use percent_encoding::percent_decode_str;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use url::Url;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentEntry {
+    pub uri: String,
+    /// Local path for `file://` URIs.
+    pub path: Option<PathBuf>,
+    /// File name, or the last URI segment for remote entries.
+    pub name: String,
+    pub mime_type: Option<String>,
+    /// The application that used the file most recently.
+    pub app: Option<String>,
+    /// Latest of the entry's and its applications' timestamps, as Unix
+    /// seconds.
+    pub timestamp: Option<i64>,
+}
+
+pub fn parse(xml: &str) -> Result<Vec<RecentEntry>> {
+    let mut reader = Reader::from_str(xml);
+    let mut out = Vec::new();
+    let mut current: Option<RecentEntry> = None;
+    // Timestamp of the app currently in `current.app`.
+    let mut app_time: Option<i64> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"bookmark" => {
+                    if let Some(entry) = current.take() {
+                        out.push(entry);
+                    }
+                    current = start_entry(&e);
+                    app_time = None;
+                }
+                b"mime-type" => {
+                    if let Some(entry) = current.as_mut() {
+                        entry.mime_type = attr(&e, b"type");
+                    }
+                }
+                b"application" => {
+                    let Some(entry) = current.as_mut() else {
+                        continue;
+                    };
+                    let time = attr(&e, b"modified").and_then(|t| parse_timestamp(&t));
+                    if entry.app.is_none() || time > app_time {
+                        entry.app = attr(&e, b"name");
+                        app_time = time;
+                    }
+                    entry.timestamp = entry.timestamp.max(time);
+                }
+                _ => {}
+            },
+            Event::End(e) if e.local_name().as_ref() == b"bookmark" => {
+                out.extend(current.take());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    // A self-closing `<bookmark/>` never sees an end tag.
+    out.extend(current);
+    Ok(out)
+}
+
+fn start_entry(e: &BytesStart) -> Option<RecentEntry> {
+    let uri = attr(e, b"href")?;
+    let url = Url::parse(&uri).ok()?;
+    let path = (url.scheme() == "file")
+        .then(|| url.to_file_path().ok())
+        .flatten();
+
+    let name = match &path {
+        Some(p) => p.file_name()?.to_string_lossy().into_owned(),
+        None => url
+            .path_segments()
+            .and_then(|mut s| s.rfind(|s| !s.is_empty()))
+            .map(|s| percent_decode_str(s).decode_utf8_lossy().into_owned())
+            .unwrap_or_else(|| uri.clone()),
+    };
+
+    let timestamp = [b"added".as_slice(), b"modified", b"visited"]
+        .into_iter()
+        .filter_map(|k| attr(e, k).and_then(|t| parse_timestamp(&t)))
+        .max();
+
+    Some(RecentEntry {
+        uri,
+        path,
+        name,
+        mime_type: None,
+        app: None,
+        timestamp,
+    })
+}
+
+fn attr(e: &BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == key)
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+}
+
+/// Parses the RFC 3339 timestamps GLib writes, e.g.
+/// `2026-10-01T12:34:56.123456Z` or with a `+02:00` offset.
+pub fn parse_timestamp(s: &str) -> Option<i64> {
+    let (date, time) = s.split_once('T')?;
+    let mut d = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (d.next()??, d.next()??, d.next()??);
+
+    let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(i) => (&time[..i], &time[i..]),
+        None => (time, "Z"),
+    };
+    let clock = clock.split('.').next()?;
+    let mut t = clock.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (t.next()??, t.next()??, t.next()??);
+
+    let offset_secs = match offset {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let (h, m) = offset[1..].split_once(':')?;
+            sign * (h.parse::<i64>().ok()? * 3600 + m.parse::<i64>().ok()? * 60)
+        }
+    };
+
+    Some(
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+            - offset_secs,
+    )
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
@@ -0,0 +1,181 @@
+use std::fs;
+use std::path::Path;
+
+use lancea_model::Provider;
+use lancea_provider_recent::{RecentConfig, RecentProvider, parse, parse_timestamp};
+use lancea_testkit::{no_apps, titles};
+use tempfile::TempDir;
+
+fn bookmark(path: &Path, added: &str, apps: &[(&str, &str)]) -> String {
+    let apps: String = apps
+        .iter()
+        .map(|(name, modified)| {
+            format!(
+                r#"<bookmark:application name="{name}" exec="&apos;{name} %u&apos;" modified="{modified}" count="1"/>"#
+            )
+        })
+        .collect();
+    format!(
+        r#"<bookmark href="file://{}" added="{added}" modified="{added}" visited="{added}">
+  <info><metadata owner="http://freedesktop.org">
+    <mime:mime-type type="text/plain"/>
+    <bookmark:applications>{apps}</bookmark:applications>
+  </metadata></info>
+</bookmark>"#,
+        path.display()
+    )
+}
+
+fn xbel(bookmarks: &[String]) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0"
+      xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks"
+      xmlns:mime="http://www.freedesktop.org/standards/shared-mime-info">
+{}
+</xbel>"#,
+        bookmarks.join("\n")
+    )
+}
+
+/// report.odt (older), notes.txt (newer) and a file that has since been
+/// deleted.
+fn setup() -> (TempDir, RecentProvider) {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::write(root.join("report.odt"), b"").unwrap();
+    fs::write(root.join("notes.txt"), b"").unwrap();
+
+    let store = root.join("recently-used.xbel");
+    fs::write(
+        &store,
+        xbel(&[
+            bookmark(
+                &root.join("report.odt"),
+                "2026-09-01T08:00:00Z",
+                &[("LibreOffice", "2026-09-01T08:00:00Z")],
+            ),
+            bookmark(
+                &root.join("notes.txt"),
+                "2026-09-10T08:00:00Z",
+                &[
+                    ("gedit", "2026-09-10T08:00:00Z"),
+                    ("Text Editor", "2026-10-02T09:30:00.123456Z"),
+                ],
+            ),
+            bookmark(
+                &root.join("gone.txt"),
+                "2026-10-05T08:00:00Z",
+                &[("gedit", "2026-10-05T08:00:00Z")],
+            ),
+        ]),
+    )
+    .unwrap();
+
+    let config = RecentConfig {
+        file: Some(store),
+        ..RecentConfig::default()
+    };
+    (dir, RecentProvider::new(config, no_apps()))
+}
+
+#[test]
+fn parses_timestamps() {
+    assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+    assert_eq!(
+        parse_timestamp("2026-10-02T09:30:00.123456Z"),
+        Some(1_790_933_400)
+    );
+    assert_eq!(
+        parse_timestamp("2026-10-02T11:30:00+02:00"),
+        parse_timestamp("2026-10-02T09:30:00Z")
+    );
+    assert_eq!(parse_timestamp("yesterday"), None);
+}
+
+#[test]
+fn parses_entries_with_their_latest_app() {
+    let (dir, p) = setup();
+    let xml = fs::read_to_string(dir.path().join("recently-used.xbel")).unwrap();
+    let entries = parse(&xml).unwrap();
+    assert_eq!(entries.len(), 3);
+
+    let notes = &entries[1];
+    assert_eq!(notes.name, "notes.txt");
+    assert_eq!(
+        notes.path.as_deref(),
+        Some(dir.path().join("notes.txt").as_path())
+    );
+    assert_eq!(notes.mime_type.as_deref(), Some("text/plain"));
+    assert_eq!(notes.app.as_deref(), Some("Text Editor"));
+    assert_eq!(notes.timestamp, parse_timestamp("2026-10-02T09:30:00Z"));
+
+    // Newest first, and the deleted file is left out.
+    let listed: Vec<_> = p.entries().into_iter().map(|e| e.name).collect();
+    assert_eq!(listed, vec!["notes.txt", "report.odt"]);
+}
+
+#[test]
+fn searches_names_and_paths() {
+    let (dir, p) = setup();
+
+    assert_eq!(titles(&p, ""), vec!["notes.txt", "report.odt"]);
+    assert_eq!(titles(&p, "/recent rep"), vec!["report.odt"]);
+    assert!(titles(&p, "gone").is_empty());
+
+    let dir_name = dir.path().file_name().unwrap().to_string_lossy();
+    assert_eq!(titles(&p, &format!("/rec {dir_name}")).len(), 2);
+
+    let results = p.search("notes");
+    let extras = results[0].extras.as_ref().unwrap();
+    let path = dir.path().join("notes.txt");
+    assert_eq!(results[0].key, format!("recent:file://{}", path.display()));
+    assert_eq!(extras["path"], path.to_string_lossy().as_ref());
+    assert_eq!(extras["copyText"], extras["path"]);
+    assert_eq!(extras["app"], "Text Editor");
+    assert_eq!(extras["defaultAction"], "open");
+    let ranges = &results[0].matches.as_ref().unwrap().title;
+    assert_eq!((ranges[0].start, ranges[0].end), (0, 5));
+}
+
+#[test]
+fn reloads_when_the_store_changes() {
+    let (dir, p) = setup();
+    assert_eq!(p.entries().len(), 2);
+
+    let store = dir.path().join("recently-used.xbel");
+    fs::write(
+        &store,
+        xbel(&[bookmark(
+            &dir.path().join("report.odt"),
+            "2026-10-10T08:00:00Z",
+            &[("LibreOffice", "2026-10-10T08:00:00Z")],
+        )]),
+    )
+    .unwrap();
+    // Make sure the mtime moves even on coarse-grained filesystems.
+    let later =
+        fs::metadata(&store).unwrap().modified().unwrap() + std::time::Duration::from_secs(5);
+    fs::File::options()
+        .write(true)
+        .open(&store)
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+
+    assert_eq!(titles(&p, ""), vec!["report.odt"]);
+}
+
+#[test]
+fn actions_check_the_entry_still_exists() {
+    let (dir, p) = setup();
+    let key = |name: &str| format!("recent:file://{}", dir.path().join(name).display());
+
+    assert!(p.execute("copy_path", &key("notes.txt")));
+    assert!(!p.execute("copy_path", &key("gone.txt")));
+    // No apps are installed, so there is nothing to open it with.
+    assert!(!p.execute("open", &key("notes.txt")));
+
+    let preview = p.preview(&key("report.odt")).unwrap();
+    assert_eq!(preview.data["comment"], "Last used with LibreOffice");
+}
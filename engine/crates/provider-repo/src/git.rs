@@ -0,0 +1,279 @@
+//! What the preview shows about a repository, read from `.git` without
+//! running git: the branch from `HEAD`, the last commit from the reflog
+//! (objects are compressed, the reflog isn't), remotes from `config`, and
+//! changes by comparing the index's stat data with the work tree.
+
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Head {
+    Branch(String),
+    Detached(String),
+}
+
+/// The newest reflog entry, which is what HEAD points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub id: String,
+    pub author: String,
+    /// Seconds since the epoch.
+    pub time: i64,
+    /// The commit's subject for commits, else what moved HEAD, like
+    /// `pull: Fast-forward`.
+    pub summary: String,
+}
+
+pub struct Repo {
+    git_dir: PathBuf,
+    /// Shared by all worktrees: refs, config, packed refs.
+    common_dir: PathBuf,
+    work_tree: PathBuf,
+}
+
+impl Repo {
+    /// The repository whose work tree is `work_tree`. `.git` is a directory,
+    /// or for worktrees and submodules a file pointing at one.
+    pub fn open(work_tree: &Path) -> Result<Self> {
+        let dot_git = work_tree.join(".git");
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else {
+            let text = fs::read_to_string(&dot_git)
+                .with_context(|| format!("read {}", dot_git.display()))?;
+            let target = text
+                .trim()
+                .strip_prefix("gitdir: ")
+                .with_context(|| format!("{} doesn't point at a git dir", dot_git.display()))?;
+            work_tree.join(target)
+        };
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(dir) => git_dir.join(dir.trim()),
+            Err(_) => git_dir.clone(),
+        };
+        Ok(Self {
+            git_dir,
+            common_dir,
+            work_tree: work_tree.to_path_buf(),
+        })
+    }
+
+    pub fn head(&self) -> Result<Head> {
+        let head = fs::read_to_string(self.git_dir.join("HEAD")).context("read HEAD")?;
+        let head = head.trim();
+        Ok(match head.strip_prefix("ref: ") {
+            Some(name) => Head::Branch(name.strip_prefix("refs/heads/").unwrap_or(name).into()),
+            None => Head::Detached(head.to_string()),
+        })
+    }
+
+    /// The commit id `name` (like `refs/heads/main`) points at, from its
+    /// loose ref file or else `packed-refs`.
+    pub fn resolve(&self, name: &str) -> Option<String> {
+        for dir in [&self.git_dir, &self.common_dir] {
+            if let Ok(id) = fs::read_to_string(dir.join(name)) {
+                return Some(id.trim().to_string());
+            }
+        }
+        let packed = fs::read_to_string(self.common_dir.join("packed-refs")).ok()?;
+        packed
+            .lines()
+            .filter(|l| !l.starts_with(['#', '^']))
+            .find_map(|l| {
+                let (id, refname) = l.split_once(' ')?;
+                (refname == name).then(|| id.to_string())
+            })
+    }
+
+    /// What HEAD points at; `None` on a branch without commits.
+    pub fn head_id(&self) -> Option<String> {
+        match self.head().ok()? {
+            Head::Branch(branch) => self.resolve(&format!("refs/heads/{branch}")),
+            Head::Detached(id) => Some(id),
+        }
+    }
+
+    /// The newest entry of HEAD's reflog, if it is about the commit HEAD
+    /// points at.
+    pub fn last_commit(&self) -> Option<Commit> {
+        let log = fs::read_to_string(self.git_dir.join("logs/HEAD")).ok()?;
+        let commit = parse_reflog_line(log.lines().rev().find(|l| !l.is_empty())?)?;
+        (Some(&commit.id) == self.head_id().as_ref()).then_some(commit)
+    }
+
+    /// `origin`'s URL, else the first remote's.
+    pub fn remote_url(&self) -> Option<String> {
+        let config = fs::read_to_string(self.common_dir.join("config")).ok()?;
+        let mut remotes: Vec<(String, String)> = Vec::new();
+        let mut section: Option<String> = None;
+        for line in config.lines().map(str::trim) {
+            if line.starts_with('[') {
+                section = line
+                    .strip_prefix("[remote \"")
+                    .and_then(|s| s.strip_suffix("\"]"))
+                    .map(str::to_string);
+            } else if let Some(remote) = &section
+                && let Some((key, value)) = line.split_once('=')
+                && key.trim() == "url"
+            {
+                remotes.push((remote.clone(), value.trim().to_string()));
+            }
+        }
+        remotes
+            .iter()
+            .find(|(name, _)| name == "origin")
+            .or(remotes.first())
+            .map(|(_, url)| url.clone())
+    }
+
+    /// Whether a tracked file was changed or deleted, judged like git's
+    /// fast path: by size and modification time against the index. Staged
+    /// changes that match the work tree and untracked files don't count.
+    pub fn is_dirty(&self) -> Result<bool> {
+        let index = fs::read(self.git_dir.join("index")).context("read the index")?;
+        for entry in parse_index(&index)? {
+            let Ok(meta) = fs::symlink_metadata(self.work_tree.join(&entry.path)) else {
+                return Ok(true);
+            };
+            let mtime_matches = meta.mtime() as u32 == entry.mtime
+                && (entry.mtime_nsec == 0 || meta.mtime_nsec() as u32 == entry.mtime_nsec);
+            if meta.size() as u32 != entry.size || !mtime_matches {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// `<old> <new> <name> <<email>> <time> <zone>\t<message>`
+fn parse_reflog_line(line: &str) -> Option<Commit> {
+    let (meta, message) = line.split_once('\t')?;
+    let mut words = meta.splitn(3, ' ');
+    let (_old, id, rest) = (words.next()?, words.next()?, words.next()?);
+    let (who, when) = rest.rsplit_once("> ")?;
+    let author = who.split_once(" <").map_or(who, |(name, _)| name);
+    let time = when.split(' ').next()?.parse().ok()?;
+
+    // `commit: `, `commit (amend): `, `commit (initial): ` and the like.
+    let summary = match message.split_once(": ") {
+        Some((action, subject)) if action.starts_with("commit") => subject,
+        _ => message,
+    };
+    Some(Commit {
+        id: id.to_string(),
+        author: author.to_string(),
+        time,
+        summary: summary.to_string(),
+    })
+}
+
+/// The parts of an index entry the dirty check needs.
+#[derive(Debug)]
+struct IndexEntry {
+    path: String,
+    mtime: u32,
+    mtime_nsec: u32,
+    size: u32,
+}
+
+const ENTRY_FIXED: usize = 62;
+const GITLINK: u32 = 0o160000;
+const ASSUME_VALID: u16 = 0x8000;
+const EXTENDED: u16 = 0x4000;
+const SKIP_WORKTREE: u16 = 0x4000;
+
+/// Entries of an index file, versions 2 to 4, skipping submodules and
+/// those git is told not to check.
+fn parse_index(data: &[u8]) -> Result<Vec<IndexEntry>> {
+    if data.len() < 12 || &data[..4] != b"DIRC" {
+        bail!("Not a git index");
+    }
+    let version = be32(data, 4)?;
+    if !(2..=4).contains(&version) {
+        bail!("Unsupported index version {version}");
+    }
+    let count = be32(data, 8)?;
+
+    let mut entries = Vec::new();
+    let mut at = 12;
+    let mut previous: Vec<u8> = Vec::new();
+    for _ in 0..count {
+        let mtime = be32(data, at + 8)?;
+        let mtime_nsec = be32(data, at + 12)?;
+        let mode = be32(data, at + 24)?;
+        let size = be32(data, at + 36)?;
+        let flags = be16(data, at + 60)?;
+        let mut path_at = at + ENTRY_FIXED;
+        let mut extended = 0;
+        if version >= 3 && flags & EXTENDED != 0 {
+            extended = be16(data, path_at)?;
+            path_at += 2;
+        }
+
+        let path = if version == 4 {
+            // The length of the previous path to drop, then what follows.
+            let (strip, len) = varint(data, path_at)?;
+            path_at += len;
+            let (suffix, end) = nul_terminated(data, path_at)?;
+            let mut path = previous[..previous.len().saturating_sub(strip)].to_vec();
+            path.extend_from_slice(suffix);
+            at = end + 1;
+            path
+        } else {
+            let (path, end) = nul_terminated(data, path_at)?;
+            // Padded with NULs to a multiple of 8, at least one.
+            at += (end - at + 8) & !7;
+            path.to_vec()
+        };
+        let path = String::from_utf8_lossy(&path).into_owned();
+        previous = path.clone().into_bytes();
+
+        if mode != GITLINK && flags & ASSUME_VALID == 0 && extended & SKIP_WORKTREE == 0 {
+            entries.push(IndexEntry {
+                path,
+                mtime,
+                mtime_nsec,
+                size,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn be32(data: &[u8], at: usize) -> Result<u32> {
+    let bytes = data.get(at..at + 4).context("Truncated index")?;
+    Ok(u32::from_be_bytes(bytes.try_into()?))
+}
+
+fn be16(data: &[u8], at: usize) -> Result<u16> {
+    let bytes = data.get(at..at + 2).context("Truncated index")?;
+    Ok(u16::from_be_bytes(bytes.try_into()?))
+}
+
+/// Git's offset encoding: seven bits a byte, most significant first, each
+/// continuation adding one.
+fn varint(data: &[u8], at: usize) -> Result<(usize, usize)> {
+    let mut len = 0;
+    let mut value = 0usize;
+    loop {
+        let byte = *data.get(at + len).context("Truncated index")?;
+        len += 1;
+        value = (value << 7) | usize::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            return Ok((value, len));
+        }
+        value += 1;
+    }
+}
+
+fn nul_terminated(data: &[u8], at: usize) -> Result<(&[u8], usize)> {
+    let rest = data.get(at..).context("Truncated index")?;
+    let len = rest
+        .iter()
+        .position(|b| *b == 0)
+        .context("Truncated index")?;
+    Ok((&rest[..len], at + len))
+}
@@ -0,0 +1,151 @@
+//! The list of repositories under the configured roots. It starts from
+//! the copy cached on disk, so `/repo` answers right after a restart, and
+//! a background thread walks the roots again every `refresh_secs`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use lancea_model::{load_state, save_state};
+use serde::{Deserialize, Serialize};
+
+use crate::RepoConfig;
+
+/// How often an idle refresh thread checks whether its index is gone.
+const POLL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cached {
+    repos: Vec<PathBuf>,
+}
+
+pub struct RepoIndex {
+    repos: RwLock<Vec<PathBuf>>,
+    roots: Vec<PathBuf>,
+    max_depth: usize,
+    exclude: Vec<String>,
+    cache: Option<PathBuf>,
+}
+
+impl RepoIndex {
+    /// Walks every root before returning, without refreshing later.
+    pub fn build(config: &RepoConfig) -> Arc<Self> {
+        let index = Arc::new(Self::empty(config));
+        index.refresh();
+        index
+    }
+
+    /// Returns at once with the cached list, and walks the roots on a
+    /// background thread now and every `refresh_secs` until the index is
+    /// dropped.
+    pub fn spawn(config: &RepoConfig) -> Arc<Self> {
+        let index = Arc::new(Self::empty(config));
+        if let Some(cached) = index.cache.as_deref().and_then(|p| load(p).ok())
+            && let Ok(mut repos) = index.repos.write()
+        {
+            *repos = cached;
+        }
+
+        let weak = Arc::downgrade(&index);
+        let every = Duration::from_secs(config.refresh_secs.max(1));
+        thread::Builder::new()
+            .name("repo-index".into())
+            .spawn(move || refresh_loop(weak, every))
+            .expect("spawn repo index thread");
+        index
+    }
+
+    fn empty(config: &RepoConfig) -> Self {
+        Self {
+            repos: RwLock::new(Vec::new()),
+            roots: config.roots(),
+            max_depth: config.max_depth,
+            exclude: config.exclude.clone(),
+            cache: config.cache_file(),
+        }
+    }
+
+    /// Work trees, in path order.
+    pub fn repos(&self) -> Vec<PathBuf> {
+        self.repos.read().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.repos.read().is_ok_and(|r| r.iter().any(|p| p == path))
+    }
+
+    /// Walks the roots again, replacing the list and its cached copy.
+    pub fn refresh(&self) {
+        let mut found = Vec::new();
+        for root in &self.roots {
+            self.walk(root, 0, &mut found);
+        }
+        found.sort();
+        found.dedup();
+
+        if let Some(path) = &self.cache {
+            let _ = save(path, &found);
+        }
+        if let Ok(mut repos) = self.repos.write() {
+            *repos = found;
+        }
+    }
+
+    /// Adds `dir` if it is a work tree, else looks through its
+    /// subdirectories. Repositories inside others, hidden directories and
+    /// symlinks are skipped.
+    fn walk(&self, dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+        if dir.join(".git").exists() {
+            out.push(dir.to_path_buf());
+            return;
+        }
+        if depth >= self.max_depth {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.')
+                || self.exclude.iter().any(|e| *e == name)
+                || !entry.file_type().is_ok_and(|t| t.is_dir())
+            {
+                continue;
+            }
+            self.walk(&entry.path(), depth + 1, out);
+        }
+    }
+}
+
+fn refresh_loop(weak: Weak<RepoIndex>, every: Duration) {
+    loop {
+        match weak.upgrade() {
+            Some(index) => index.refresh(),
+            None => return,
+        }
+        let since = Instant::now();
+        while since.elapsed() < every {
+            thread::sleep(POLL.min(every));
+            if weak.strong_count() == 0 {
+                return;
+            }
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<Vec<PathBuf>> {
+    let cached: Cached = load_state(path).with_context(|| format!("load {}", path.display()))?;
+    Ok(cached.repos)
+}
+
+fn save(path: &Path, repos: &[PathBuf]) -> Result<()> {
+    let cached = Cached {
+        repos: repos.to_vec(),
+    };
+    save_state(path, &cached).with_context(|| format!("save {}", path.display()))
+}
@@ -0,0 +1,333 @@
+//! Git repositories under the configured roots: `/repo name` finds them
+//! by directory name, with the branch, changes and last commit in the
+//! preview, and opens one in the editor or a terminal. The list is cached
+//! and refreshed in the background (see [`RepoIndex`]); everything about a
+//! repository is read from `.git` directly (see [`Repo`]).
+
+mod git;
+mod index;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use chrono::{Local, TimeZone};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{
+    MatchRange, Matches, Preview, Provider, ResultItem, command_query, expand_home, tildify,
+};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use git::{Commit, Head, Repo};
+pub use index::RepoIndex;
+
+const PROVIDER_ID: &str = "repo";
+const KEY_PREFIX: &str = "repo:";
+const PREFIX: &str = "/repo";
+
+/// Stands for the repository in the editor command.
+const PATH_PLACEHOLDER: &str = "{path}";
+
+/// The `[repo]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RepoConfig {
+    /// Directories to look for repositories in; the home directory when
+    /// empty.
+    pub roots: Vec<PathBuf>,
+    /// How many directories deep below a root to look.
+    pub max_depth: usize,
+    /// Directory names never to look in.
+    pub exclude: Vec<String>,
+    pub refresh_secs: u64,
+    /// Defaults to `repos.json` in the cache directory.
+    pub cache_file: Option<PathBuf>,
+    /// Opens a repository; `{path}` is replaced by its directory, which is
+    /// appended when there is no `{path}`. Without one, `$VISUAL` or
+    /// `$EDITOR` in the terminal.
+    pub editor: Vec<String>,
+    /// Runs `editor` in the terminal, for terminal editors.
+    pub editor_terminal: bool,
+    pub max_results: usize,
+}
+
+impl Default for RepoConfig {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            max_depth: 4,
+            exclude: ["node_modules", "target", "vendor"]
+                .map(String::from)
+                .to_vec(),
+            refresh_secs: 300,
+            cache_file: None,
+            editor: Vec::new(),
+            editor_terminal: false,
+            max_results: 50,
+        }
+    }
+}
+
+impl RepoConfig {
+    fn roots(&self) -> Vec<PathBuf> {
+        if self.roots.is_empty() {
+            return dirs::home_dir().into_iter().collect();
+        }
+        self.roots.iter().cloned().map(expand_home).collect()
+    }
+
+    fn cache_file(&self) -> Option<PathBuf> {
+        self.cache_file
+            .clone()
+            .or_else(|| dirs::cache_dir().map(|d| d.join("lancea").join("repos.json")))
+    }
+}
+
+pub struct RepoProvider {
+    config: RepoConfig,
+    index: Arc<RepoIndex>,
+    apps: Arc<AppsProvider>,
+}
+
+impl RepoProvider {
+    /// Starts from the cached list and refreshes it in the background.
+    pub fn new(config: RepoConfig, apps: Arc<AppsProvider>) -> Self {
+        let index = RepoIndex::spawn(&config);
+        Self::with_index(config, apps, index)
+    }
+
+    /// Uses an existing index, e.g. one built synchronously in tests.
+    pub fn with_index(config: RepoConfig, apps: Arc<AppsProvider>, index: Arc<RepoIndex>) -> Self {
+        Self {
+            config,
+            index,
+            apps,
+        }
+    }
+
+    pub fn index(&self) -> &Arc<RepoIndex> {
+        &self.index
+    }
+
+    /// Repositories whose directory name matches the query, or whose path
+    /// does when the query has a `/`; all of them, by path, without one.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &[PREFIX]);
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let by_path = q.contains('/');
+
+        let mut hits: Vec<(i64, Vec<usize>, PathBuf)> = self
+            .index
+            .repos()
+            .into_iter()
+            .filter_map(|path| {
+                if q.is_empty() {
+                    return Some((0, Vec::new(), path));
+                }
+                if by_path {
+                    let score = matcher.fuzzy_match(&path.to_string_lossy(), q)?;
+                    return Some((score, Vec::new(), path));
+                }
+                let (score, idx) = matcher.fuzzy_indices(&repo_name(&path), q)?;
+                Some((score, idx, path))
+            })
+            .collect();
+        // Stable, so ties stay in path order.
+        hits.sort_by_key(|(score, ..)| std::cmp::Reverse(*score));
+        hits.truncate(self.config.max_results);
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, idx, path))| to_result_item(&path, 1.0 - rank as f32 / count, idx))
+            .collect()
+    }
+
+    /// The work tree behind `key`, if it is one the index knows.
+    fn work_tree(&self, key: &str) -> Result<PathBuf> {
+        let path = key
+            .strip_prefix(KEY_PREFIX)
+            .map(PathBuf::from)
+            .with_context(|| format!("Not a repo key: {key}"))?;
+        if !self.index.contains(&path) {
+            bail!("{} isn't a known repository", path.display());
+        }
+        Ok(path)
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let path = self.work_tree(key).ok()?;
+        let repo = Repo::open(&path).ok()?;
+
+        let mut lines = vec![match repo.head().ok()? {
+            Head::Branch(branch) => format!("Branch: {branch}"),
+            Head::Detached(id) => format!("Detached at {}", short(&id)),
+        }];
+        lines.push(match repo.is_dirty() {
+            Ok(true) => "Changes: uncommitted changes".into(),
+            Ok(false) => "Changes: none".into(),
+            Err(_) => "Changes: unknown".into(),
+        });
+        match (repo.last_commit(), repo.head_id()) {
+            (Some(commit), _) => lines.push(format!(
+                "Last commit: {} {}\n  {}, {}",
+                short(&commit.id),
+                commit.summary,
+                commit.author,
+                commit_time(commit.time)
+            )),
+            (None, Some(id)) => lines.push(format!("Last commit: {}", short(&id))),
+            (None, None) => lines.push("No commits yet".into()),
+        }
+        if let Some(url) = repo.remote_url() {
+            lines.push(format!("Remote: {url}"));
+        }
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": repo_name(&path),
+                "comment": tildify(&path),
+                "snippet": lines.join("\n"),
+            }),
+        })
+    }
+
+    /// The editor command for `path`, and whether it needs a terminal.
+    pub fn editor_argv(&self, path: &Path) -> Result<(Vec<String>, bool)> {
+        let path = path.to_string_lossy();
+        if self.config.editor.is_empty() {
+            let editor = ["VISUAL", "EDITOR"]
+                .iter()
+                .find_map(|v| std::env::var(v).ok().filter(|e| !e.trim().is_empty()))
+                .context("No editor configured and neither $VISUAL nor $EDITOR is set")?;
+            let mut argv: Vec<String> = editor.split_whitespace().map(String::from).collect();
+            argv.push(path.into_owned());
+            return Ok((argv, true));
+        }
+
+        let mut argv: Vec<String> = self
+            .config
+            .editor
+            .iter()
+            .map(|a| a.replace(PATH_PLACEHOLDER, &path))
+            .collect();
+        if !self
+            .config
+            .editor
+            .iter()
+            .any(|a| a.contains(PATH_PLACEHOLDER))
+        {
+            argv.push(path.into_owned());
+        }
+        Ok((argv, self.config.editor_terminal))
+    }
+
+    pub fn execute_open(&self, key: &str) -> Result<()> {
+        let path = self.work_tree(key)?;
+        let name = repo_name(&path);
+        match self.editor_argv(&path)? {
+            (argv, true) => self.apps.run_in_terminal(PROVIDER_ID, &name, &argv),
+            (argv, false) => self.apps.run_command(PROVIDER_ID, &name, &argv),
+        }
+    }
+
+    /// A shell in the repository, in the terminal.
+    pub fn execute_terminal(&self, key: &str) -> Result<()> {
+        let path = self.work_tree(key)?;
+        let argv = [
+            "sh",
+            "-c",
+            "cd \"$1\" && exec \"${SHELL:-sh}\"",
+            "sh",
+            &path.to_string_lossy(),
+        ]
+        .map(String::from);
+        self.apps
+            .run_in_terminal(PROVIDER_ID, &repo_name(&path), &argv)
+    }
+
+    pub fn execute_copy_url(&self, key: &str) -> Result<()> {
+        Repo::open(&self.work_tree(key)?)?
+            .remote_url()
+            .context("No remote")?;
+        Ok(())
+    }
+}
+
+impl Provider for RepoProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "open" => self.execute_open(key).is_ok(),
+            "terminal" => self.execute_terminal(key).is_ok(),
+            "copy_url" => self.execute_copy_url(key).is_ok(),
+            _ => false,
+        }
+    }
+}
+
+fn to_result_item(path: &Path, score: f32, indices: Vec<usize>) -> ResultItem {
+    let name = repo_name(path);
+    let repo = Repo::open(path).ok();
+    let subtitle = match repo.as_ref().and_then(|r| r.head().ok()) {
+        Some(Head::Branch(branch)) => format!("{} · {branch}", tildify(path)),
+        Some(Head::Detached(id)) => format!("{} · {}", tildify(path), short(&id)),
+        None => tildify(path),
+    };
+    let url = repo.as_ref().and_then(Repo::remote_url);
+    let mut actions = vec!["open", "terminal"];
+    if url.is_some() {
+        actions.push("copy_url");
+    }
+
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}", path.display()),
+        title: name.clone(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": subtitle,
+            "icon": "folder-development",
+            "copyText": url,
+            "copyTexts": url.as_ref().map(|u| serde_json::json!({ "copy_url": u })),
+            "defaultAction": "open",
+            "actions": actions,
+        })),
+        matches: (!indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(&name, indices),
+            subtitle: None,
+        }),
+    }
+}
+
+fn repo_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn short(id: &str) -> &str {
+    id.get(..7).unwrap_or(id)
+}
+
+fn commit_time(secs: i64) -> String {
+    Local
+        .timestamp_opt(secs, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
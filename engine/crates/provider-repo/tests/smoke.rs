@@ -0,0 +1,218 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use lancea_model::Provider;
+use lancea_provider_apps::AppsProvider;
+use lancea_provider_repo::{Head, Repo, RepoConfig, RepoIndex, RepoProvider};
+use lancea_testkit::{no_apps, recorder, recording_terminal};
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args([
+            "-c",
+            "user.name=Alice",
+            "-c",
+            "user.email=alice@example.com",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+/// A repository at `dir` with one commit.
+fn repo(dir: &Path) {
+    fs::create_dir_all(dir).unwrap();
+    git(dir, &["init", "--quiet", "--initial-branch=main"]);
+    fs::write(dir.join("README.md"), "hello\n").unwrap();
+    git(dir, &["add", "README.md"]);
+    git(dir, &["commit", "--quiet", "-m", "Initial import"]);
+}
+
+fn config(root: &Path) -> RepoConfig {
+    RepoConfig {
+        roots: vec![root.to_path_buf()],
+        cache_file: Some(root.join("cache/repos.json")),
+        ..RepoConfig::default()
+    }
+}
+
+fn provider(config: RepoConfig, apps: Arc<AppsProvider>) -> RepoProvider {
+    let index = RepoIndex::build(&config);
+    RepoProvider::with_index(config, apps, index)
+}
+
+fn key(path: &Path) -> String {
+    format!("repo:{}", path.display())
+}
+
+fn snippet(p: &RepoProvider, path: &Path) -> String {
+    let preview = p.preview(&key(path)).unwrap();
+    preview.data["snippet"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn finds_repositories_under_the_roots() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    repo(&root.join("src/lancea"));
+    repo(&root.join("src/dotfiles"));
+    repo(&root.join("src/lancea/vendor-copy"));
+    repo(&root.join("src/web/node_modules/left-pad"));
+    repo(&root.join(".local/share/plugin"));
+    repo(&root.join("a/b/c/d/too-deep"));
+
+    let p = provider(config(root), no_apps());
+    let titles: Vec<String> = p.search("/repo").into_iter().map(|r| r.title).collect();
+    assert_eq!(titles, vec!["dotfiles", "lancea"]);
+    assert_eq!(p.search("/REPO lnc")[0].title, "lancea");
+    assert_eq!(p.search("/repo src/dot")[0].title, "dotfiles");
+
+    let item = &p.search("/repo lancea")[0];
+    assert_eq!(item.key, key(&root.join("src/lancea")));
+    let subtitle = item.extras.as_ref().unwrap()["subtitle"].as_str().unwrap();
+    assert!(subtitle.ends_with("src/lancea · main"), "{subtitle}");
+
+    let cached = fs::read_to_string(root.join("cache/repos.json")).unwrap();
+    assert!(cached.contains("src/dotfiles") && cached.contains("src/lancea"));
+}
+
+#[test]
+fn preview_reads_branch_changes_and_last_commit_from_git_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lancea");
+    repo(&path);
+    let p = provider(config(dir.path()), no_apps());
+    let repo = Repo::open(&path).unwrap();
+    let id = repo.head_id().unwrap();
+
+    let text = snippet(&p, &path);
+    assert!(text.starts_with("Branch: main\nChanges: none\n"), "{text}");
+    assert!(
+        text.contains(&format!(
+            "Last commit: {} Initial import\n  Alice, ",
+            &id[..7]
+        )),
+        "{text}"
+    );
+    assert!(!text.contains("Remote"));
+
+    // Sizes differ, so this holds within the index's mtime granularity.
+    fs::write(path.join("README.md"), "hello, world\n").unwrap();
+    assert!(snippet(&p, &path).contains("Changes: uncommitted changes"));
+    git(&path, &["update-index", "--index-version", "4"]);
+    assert!(repo.is_dirty().unwrap());
+    git(&path, &["commit", "--quiet", "-am", "Greet the world"]);
+    assert!(!repo.is_dirty().unwrap());
+    fs::remove_file(path.join("README.md")).unwrap();
+    assert!(repo.is_dirty().unwrap());
+    git(&path, &["checkout", "--quiet", "README.md"]);
+
+    git(&path, &["pack-refs", "--all"]);
+    assert!(!path.join(".git/refs/heads/main").exists());
+    assert_eq!(repo.last_commit().unwrap().summary, "Greet the world");
+
+    git(
+        &path,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "git@example.com:alice/lancea.git",
+        ],
+    );
+    git(&path, &["checkout", "--quiet", "--detach"]);
+    let text = snippet(&p, &path);
+    let id = repo.head_id().unwrap();
+    assert!(
+        text.starts_with(&format!("Detached at {}", &id[..7])),
+        "{text}"
+    );
+    assert!(
+        text.ends_with("Remote: git@example.com:alice/lancea.git"),
+        "{text}"
+    );
+}
+
+#[test]
+fn follows_worktrees_to_the_shared_git_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("lancea");
+    repo(&main);
+    git(
+        &main,
+        &["remote", "add", "origin", "https://example.com/lancea.git"],
+    );
+    git(
+        &main,
+        &["worktree", "add", "--quiet", "-b", "feature", "../feature"],
+    );
+
+    let worktree = Repo::open(&dir.path().join("feature")).unwrap();
+    assert_eq!(worktree.head().unwrap(), Head::Branch("feature".into()));
+    assert_eq!(worktree.head_id(), Repo::open(&main).unwrap().head_id());
+    assert_eq!(
+        worktree.remote_url().as_deref(),
+        Some("https://example.com/lancea.git")
+    );
+    assert!(!worktree.is_dirty().unwrap());
+}
+
+/// Waits for a detached command to write `file`.
+fn wait_for(file: &Path) -> String {
+    for _ in 0..100 {
+        if let Ok(text) = fs::read_to_string(file)
+            && !text.is_empty()
+        {
+            return text;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("{} never appeared", file.display());
+}
+
+#[test]
+fn opens_in_the_editor_or_terminal_and_copies_the_remote() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out");
+    let with_remote = dir.path().join("lancea");
+    let without_remote: PathBuf = dir.path().join("notes");
+    repo(&with_remote);
+    repo(&without_remote);
+    git(
+        &with_remote,
+        &["remote", "add", "origin", "https://example.com/lancea.git"],
+    );
+
+    let record = |name: &str| recorder(&out.join(name));
+    fs::create_dir(&out).unwrap();
+    let apps = recording_terminal(&out.join("terminal"));
+    let p = provider(
+        RepoConfig {
+            editor: record("editor"),
+            ..config(dir.path())
+        },
+        apps,
+    );
+
+    assert!(p.execute("open", &key(&with_remote)));
+    assert_eq!(
+        wait_for(&out.join("editor")),
+        format!("{}\n", with_remote.display())
+    );
+    assert!(p.execute("terminal", &key(&with_remote)));
+    assert!(wait_for(&out.join("terminal")).ends_with(&format!("{}\n", with_remote.display())));
+
+    let items = p.search("/repo lancea");
+    let extras = items[0].extras.as_ref().unwrap();
+    assert_eq!(extras["copyText"], "https://example.com/lancea.git");
+    assert_eq!(extras["actions"][2], "copy_url");
+    assert!(p.execute("copy_url", &key(&with_remote)));
+    assert!(!p.execute("copy_url", &key(&without_remote)));
+    assert!(!p.execute("open", &key(&dir.path().join("elsewhere"))));
+}
@@ -0,0 +1,125 @@
+//! Running commands detached from the engine while capturing what they
+//! print, for the preview.
+
+use std::io::Read;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+/// Output kept per stream; the rest is read and dropped so the command
+/// never blocks on a full pipe.
+const OUTPUT_LIMIT: usize = 64 * 1024;
+
+/// How long to wait for the pipes to drain once the command has exited.
+/// Background children it left behind may hold them open for good.
+const DRAIN_GRACE: Duration = Duration::from_millis(100);
+
+const POLL: Duration = Duration::from_millis(10);
+
+/// A finished command and what it printed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Captured {
+    /// The exit status, or 128 + the signal that killed it, as shells
+    /// report it.
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Starts `argv` in its own process group. If it finishes within `wait`,
+/// returns what it printed; otherwise leaves it running and hands the
+/// result to `on_done` once it exits.
+pub fn run_detached(
+    argv: &[String],
+    wait: Duration,
+    on_done: impl FnOnce(Captured) + Send + 'static,
+) -> Result<Option<Captured>> {
+    let (program, rest) = argv.split_first().context("empty command line")?;
+    let mut child = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .with_context(|| format!("failed to spawn {program}"))?;
+
+    let stdout = Stream::read(child.stdout.take());
+    let stderr = Stream::read(child.stderr.take());
+
+    let deadline = Instant::now() + wait;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(collect(status, &stdout, &stderr)));
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+        thread::sleep(POLL);
+    }
+
+    thread::spawn(move || {
+        if let Ok(status) = child.wait() {
+            on_done(collect(status, &stdout, &stderr));
+        }
+    });
+    Ok(None)
+}
+
+fn collect(status: ExitStatus, stdout: &Stream, stderr: &Stream) -> Captured {
+    let deadline = Instant::now() + DRAIN_GRACE;
+    while !(stdout.done() && stderr.done()) && Instant::now() < deadline {
+        thread::sleep(POLL);
+    }
+    Captured {
+        exit_code: status
+            .code()
+            .or_else(|| status.signal().map(|s| 128 + s))
+            .unwrap_or(-1),
+        stdout: stdout.text(),
+        stderr: stderr.text(),
+    }
+}
+
+/// One output pipe, read on its own thread.
+struct Stream {
+    buf: Arc<Mutex<Vec<u8>>>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl Stream {
+    fn read(pipe: Option<impl Read + Send + 'static>) -> Self {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let reader = pipe.map(|mut pipe| {
+            let buf = buf.clone();
+            thread::spawn(move || {
+                let mut chunk = [0u8; 8192];
+                while let Ok(n) = pipe.read(&mut chunk) {
+                    if n == 0 {
+                        break;
+                    }
+                    if let Ok(mut buf) = buf.lock() {
+                        let room = OUTPUT_LIMIT.saturating_sub(buf.len());
+                        buf.extend_from_slice(&chunk[..n.min(room)]);
+                    }
+                }
+            })
+        });
+        Self { buf, reader }
+    }
+
+    fn done(&self) -> bool {
+        self.reader.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    fn text(&self) -> String {
+        self.buf
+            .lock()
+            .map(|b| String::from_utf8_lossy(&b).into_owned())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,80 @@
+//! Commands run before, most recent first, optionally mirrored to a JSON
+//! file so they survive restarts.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lancea_model::{Stored, load_state, save_state, unix_now};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub command: String,
+    /// How often it was run.
+    pub count: u32,
+    /// When it was last run, as Unix seconds.
+    pub last_run: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct RunHistory {
+    /// Most recent first.
+    entries: Vec<HistoryEntry>,
+    path: Option<PathBuf>,
+}
+
+impl RunHistory {
+    /// An in-memory history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the history kept at `path`, which need not exist yet, and saves
+    /// every change back to it.
+    pub fn open(path: &Path) -> Result<Self> {
+        let stored: Stored<HistoryEntry> =
+            load_state(path).with_context(|| format!("load {}", path.display()))?;
+
+        Ok(Self {
+            entries: stored.entries,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, command: &str) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|e| e.command == command)
+    }
+
+    /// Moves `command` to the front, counting the run, and drops the
+    /// oldest entries beyond `max_entries`.
+    pub fn record(&mut self, command: &str, max_entries: usize) -> Result<()> {
+        let mut entry = match self.entries.iter().position(|e| e.command == command) {
+            Some(i) => self.entries.remove(i),
+            None => HistoryEntry {
+                command: command.to_string(),
+                count: 0,
+                last_run: 0,
+            },
+        };
+        entry.count += 1;
+        entry.last_run = unix_now();
+        self.entries.insert(0, entry);
+        self.entries.truncate(max_entries);
+        self.save()
+    }
+
+    /// Mirrors the history to its file, when it has one.
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let stored = Stored {
+            entries: self.entries.clone(),
+        };
+        save_state(path, &stored).with_context(|| format!("save {}", path.display()))
+    }
+}
@@ -0,0 +1,444 @@
+//! Shell commands typed after `/run` or `>`, completed from executables on
+//! `PATH` and from the command history, and run either in a terminal or
+//! detached with their output captured for the preview.
+
+mod exec;
+mod history;
+
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Outcome, Preview, Provider, ResultItem};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use exec::{Captured, run_detached};
+pub use history::{HistoryEntry, RunHistory};
+
+const PROVIDER_ID: &str = "run";
+const KEY_PREFIX: &str = "run:";
+const PREFIXES: &[&str] = &["/run", ">"];
+
+/// Captured output shown in the preview, cut to this many chars.
+const PREVIEW_CHARS: usize = 4000;
+
+/// Where a command runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunMode {
+    /// In the configured terminal, which closes when the command ends.
+    #[default]
+    Terminal,
+    /// In the background, with its output kept for the preview.
+    Detached,
+}
+
+impl RunMode {
+    fn id(self) -> &'static str {
+        match self {
+            RunMode::Terminal => "terminal",
+            RunMode::Detached => "detached",
+        }
+    }
+}
+
+/// The `[run]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RunConfig {
+    /// What selecting a command does; the other mode is the second action.
+    pub mode: RunMode,
+    /// Runs commands as `<shell> -c <command>`. Defaults to `$SHELL`, else
+    /// `/bin/sh`.
+    pub shell: Option<String>,
+    /// How long a detached command may take to still report its exit code
+    /// and output right away.
+    pub wait_ms: u64,
+    pub max_history: usize,
+    /// Defaults to `$XDG_STATE_HOME/lancea/run_history.json`.
+    pub history_file: Option<PathBuf>,
+    /// Directories to complete executables from. Defaults to `$PATH`.
+    pub path: Option<Vec<PathBuf>>,
+    pub max_results: usize,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            mode: RunMode::default(),
+            shell: None,
+            wait_ms: 2000,
+            max_history: 200,
+            history_file: None,
+            path: None,
+            max_results: 20,
+        }
+    }
+}
+
+/// A detached run as far as the preview knows.
+#[derive(Debug, Clone)]
+enum RunState {
+    Running,
+    Done(Captured),
+}
+
+/// Executables on the path, with the directory mtimes they were listed at.
+type Listed = (Vec<(PathBuf, Option<SystemTime>)>, Arc<Vec<String>>);
+
+pub struct RunProvider {
+    config: RunConfig,
+    shell: String,
+    path: Vec<PathBuf>,
+    history: Mutex<RunHistory>,
+    /// Last detached run of each command, shared with the threads waiting
+    /// on ones that outlived `wait_ms`.
+    runs: Arc<Mutex<HashMap<String, RunState>>>,
+    executables: Mutex<Option<Listed>>,
+    apps: Arc<AppsProvider>,
+}
+
+impl RunProvider {
+    /// Loads the persisted history, starting empty if it can't be read.
+    pub fn new(config: RunConfig, apps: Arc<AppsProvider>) -> Self {
+        let file = config
+            .history_file
+            .clone()
+            .or_else(|| dirs::state_dir().map(|d| d.join("lancea").join("run_history.json")));
+        let history = file
+            .and_then(|p| RunHistory::open(&p).ok())
+            .unwrap_or_default();
+        let shell = config
+            .shell
+            .clone()
+            .or_else(|| std::env::var("SHELL").ok().filter(|s| !s.is_empty()))
+            .unwrap_or_else(|| "/bin/sh".into());
+        let path = config.path.clone().unwrap_or_else(|| {
+            std::env::var_os("PATH")
+                .map(|p| std::env::split_paths(&p).collect())
+                .unwrap_or_default()
+        });
+
+        Self {
+            config,
+            shell,
+            path,
+            history: Mutex::new(history),
+            runs: Arc::default(),
+            executables: Mutex::new(None),
+            apps,
+        }
+    }
+
+    /// Executable names on the path, sorted; re-listed when a directory
+    /// changes.
+    pub fn executables(&self) -> Arc<Vec<String>> {
+        let seen: Vec<(PathBuf, Option<SystemTime>)> = self
+            .path
+            .iter()
+            .map(|d| (d.clone(), fs::metadata(d).and_then(|m| m.modified()).ok()))
+            .collect();
+
+        let Ok(mut cache) = self.executables.lock() else {
+            return Arc::default();
+        };
+        if let Some((listed_at, names)) = cache.as_ref()
+            && *listed_at == seen
+        {
+            return names.clone();
+        }
+
+        let mut names: Vec<String> = self
+            .path
+            .iter()
+            .filter_map(|d| fs::read_dir(d).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter(|e| {
+                // Follows symlinks, as most of /usr/bin is.
+                fs::metadata(e.path())
+                    .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+            })
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let names = Arc::new(names);
+        *cache = Some((seen, names.clone()));
+        names
+    }
+
+    /// The command as typed first, then history entries matching it and,
+    /// while only the program name is typed, executables it starts. An
+    /// empty command lists the history.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let (prefix, q) = split_command(raw_query);
+        let Ok(history) = self.history.lock() else {
+            return Vec::new();
+        };
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let mut items: Vec<(String, &'static str, Vec<usize>)> = Vec::new();
+        if !q.is_empty() {
+            items.push((q.to_string(), "command", Vec::new()));
+        }
+
+        let mut from_history: Vec<(i64, usize, Vec<usize>)> = history
+            .entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.command != q)
+            .filter_map(|(i, e)| {
+                if q.is_empty() {
+                    return Some((0, i, Vec::new()));
+                }
+                matcher
+                    .fuzzy_indices(&e.command, q)
+                    .map(|(s, idx)| (s, i, idx))
+            })
+            .collect();
+        // Ties keep the most recent first.
+        from_history.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        items.extend(
+            from_history
+                .into_iter()
+                .map(|(_, i, idx)| (history.entries()[i].command.clone(), "history", idx)),
+        );
+
+        if !q.is_empty() && !q.contains(char::is_whitespace) {
+            let executables = self.executables();
+            let completions: Vec<_> = executables
+                .iter()
+                .filter(|name| name.starts_with(q) && name.as_str() != q)
+                .filter(|name| !items.iter().any(|(c, _, _)| c == *name))
+                .map(|name| (name.clone(), "executable", (0..q.chars().count()).collect()))
+                .collect();
+            items.extend(completions);
+        }
+
+        items.truncate(self.config.max_results);
+        let count = items.len().max(1) as f32;
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (command, source, idx))| {
+                let subtitle = match (source, history.get(&command)) {
+                    (_, Some(e)) => times_run(e.count),
+                    ("executable", None) => "Executable on PATH".to_string(),
+                    _ => format!("Run with {}", self.shell),
+                };
+                self.to_result_item(prefix, &command, subtitle, 1.0 - rank as f32 / count, idx)
+            })
+            .collect()
+    }
+
+    fn to_result_item(
+        &self,
+        prefix: &str,
+        command: &str,
+        subtitle: String,
+        score: f32,
+        indices: Vec<usize>,
+    ) -> ResultItem {
+        let mode = self.config.mode;
+        let other = match mode {
+            RunMode::Terminal => RunMode::Detached,
+            RunMode::Detached => RunMode::Terminal,
+        };
+
+        ResultItem {
+            key: format!("{KEY_PREFIX}{command}"),
+            title: command.to_string(),
+            provider_id: PROVIDER_ID.into(),
+            score,
+            extras: Some(serde_json::json!({
+                "subtitle": subtitle,
+                "copyText": command,
+                // Tab puts this in the search field to keep editing.
+                "completion": format!("{prefix} {command} "),
+                "defaultAction": mode.id(),
+                "actions": [mode.id(), other.id()],
+            })),
+            matches: (!indices.is_empty()).then(|| Matches {
+                title: MatchRange::from_char_indices(command, indices),
+                subtitle: None,
+            }),
+        }
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let command = key.strip_prefix(KEY_PREFIX)?;
+        let run = self.runs.lock().ok()?.get(command).cloned();
+        let count = self
+            .history
+            .lock()
+            .ok()?
+            .get(command)
+            .map_or(0, |e| e.count);
+
+        let (comment, snippet, exit_code) = match run {
+            Some(RunState::Done(c)) => {
+                let mut out = c.stdout.trim_end().to_string();
+                if !c.stderr.trim().is_empty() {
+                    if !out.is_empty() {
+                        out.push_str("\n\n");
+                    }
+                    out.push_str(c.stderr.trim_end());
+                }
+                let out: String = out.chars().take(PREVIEW_CHARS).collect();
+                (
+                    format!("Exited with {}", c.exit_code),
+                    out,
+                    Some(c.exit_code),
+                )
+            }
+            Some(RunState::Running) => ("Still running".to_string(), String::new(), None),
+            None if count > 0 => (times_run(count), String::new(), None),
+            None => ("Not run yet".to_string(), String::new(), None),
+        };
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": command,
+                "comment": comment,
+                "snippet": snippet,
+                "exitCode": exit_code,
+            }),
+        })
+    }
+
+    fn argv(&self, command: &str) -> Vec<String> {
+        vec![self.shell.clone(), "-c".into(), command.to_string()]
+    }
+
+    fn command<'a>(&self, key: &'a str) -> Result<&'a str> {
+        key.strip_prefix(KEY_PREFIX)
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .with_context(|| format!("Not a run key: {key}"))
+    }
+
+    fn record(&self, command: &str) {
+        if let Ok(mut history) = self.history.lock() {
+            // Running matters more than remembering it.
+            let _ = history.record(command, self.config.max_history);
+        }
+    }
+
+    pub fn execute_terminal(&self, key: &str) -> Result<()> {
+        let command = self.command(key)?;
+        self.apps
+            .run_in_terminal(PROVIDER_ID, command, &self.argv(command))?;
+        self.record(command);
+        Ok(())
+    }
+
+    /// Runs the command in the background. `Some` when it finished within
+    /// `wait_ms`; otherwise its output shows in the preview once it does.
+    pub fn execute_detached(&self, key: &str) -> Result<Option<Captured>> {
+        let command = self.command(key)?.to_string();
+        let runs = self.runs.clone();
+        let name = command.clone();
+        let finished = run_detached(
+            &self.argv(&command),
+            Duration::from_millis(self.config.wait_ms),
+            move |captured| {
+                if let Ok(mut runs) = runs.lock() {
+                    runs.insert(name, RunState::Done(captured));
+                }
+            },
+        )?;
+        self.record(&command);
+
+        let state = match &finished {
+            Some(c) => RunState::Done(c.clone()),
+            None => RunState::Running,
+        };
+        if let Ok(mut runs) = self.runs.lock() {
+            // The waiting thread may already have stored the result.
+            let done = matches!(runs.get(&command), Some(RunState::Done(_)));
+            if !(done && finished.is_none()) {
+                runs.insert(command, state);
+            }
+        }
+        Ok(finished)
+    }
+}
+
+fn times_run(count: u32) -> String {
+    match count {
+        1 => "Ran once".into(),
+        n => format!("Ran {n} times"),
+    }
+}
+
+/// The prefix used (`/run` or `>`) and the command after it.
+fn split_command(query: &str) -> (&'static str, &str) {
+    let q = query.trim_start();
+    PREFIXES
+        .iter()
+        .find_map(|p| {
+            q.get(..p.len())
+                .filter(|head| head.eq_ignore_ascii_case(p))
+                .map(|_| (*p, q[p.len()..].trim()))
+        })
+        .unwrap_or((PREFIXES[0], q.trim()))
+}
+
+impl Provider for RunProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_outcome(action, key, &[]).status == "ok"
+    }
+
+    fn execute_outcome(&self, action: &str, key: &str, args: &[String]) -> Outcome {
+        let result = match action {
+            _ if !args.is_empty() => Err(anyhow::anyhow!("{action} takes no arguments")),
+            "terminal" => self
+                .execute_terminal(key)
+                .map(|()| ("Started in the terminal".to_string(), None)),
+            "detached" => self.execute_detached(key).map(|finished| match finished {
+                Some(c) => (format!("Exited with {}", c.exit_code), Some(c.exit_code)),
+                None => ("Still running in the background".to_string(), None),
+            }),
+            "copy" => self.command(key).map(|_| ("Copied".to_string(), None)),
+            _ => Err(anyhow::anyhow!("Unknown action {action}")),
+        };
+
+        match result {
+            Ok((message, exit_code)) => Outcome {
+                status: if exit_code.unwrap_or(0) == 0 {
+                    "ok".into()
+                } else {
+                    "error".into()
+                },
+                message: Some(message),
+                exit_code,
+            },
+            Err(e) => Outcome {
+                status: "error".into(),
+                message: Some(format!("{e:#}")),
+                exit_code: None,
+            },
+        }
+    }
+}
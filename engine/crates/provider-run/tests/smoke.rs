@@ -0,0 +1,160 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lancea_model::Provider;
+use lancea_provider_run::{RunConfig, RunHistory, RunMode, RunProvider};
+use lancea_testkit::{quiet_terminal, titles};
+use tempfile::TempDir;
+
+fn executable(dir: &Path, name: &str) {
+    let path = dir.join(name);
+    fs::write(&path, "#!/bin/sh\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn config(dir: &Path) -> RunConfig {
+    RunConfig {
+        shell: Some("/bin/sh".into()),
+        history_file: Some(dir.join("state/run_history.json")),
+        path: Some(vec![dir.join("bin"), dir.join("missing")]),
+        wait_ms: 2000,
+        ..RunConfig::default()
+    }
+}
+
+fn provider(config: RunConfig) -> RunProvider {
+    RunProvider::new(config, quiet_terminal())
+}
+
+fn setup() -> (TempDir, RunProvider) {
+    let dir = tempfile::tempdir().unwrap();
+    let bin = dir.path().join("bin");
+    fs::create_dir(&bin).unwrap();
+    executable(&bin, "htop");
+    executable(&bin, "hexdump");
+    executable(&bin, "git");
+    fs::write(bin.join("hello.txt"), "not executable").unwrap();
+
+    let p = provider(config(dir.path()));
+    (dir, p)
+}
+
+#[test]
+fn completes_from_path_and_history() {
+    let (dir, p) = setup();
+
+    assert!(titles(&p, "/run").is_empty());
+    assert_eq!(titles(&p, "> h"), vec!["h", "hexdump", "htop"]);
+    assert_eq!(titles(&p, "/run git status"), vec!["git status"]);
+
+    assert!(p.execute("terminal", "run:htop -d 5"));
+    assert!(p.execute("terminal", "run:git status"));
+    assert_eq!(titles(&p, ">"), vec!["git status", "htop -d 5"]);
+    assert_eq!(titles(&p, "> ht"), vec!["ht", "htop -d 5", "htop"]);
+
+    let results = p.search("> ht");
+    let extras = results[1].extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], "Ran once");
+    assert_eq!(extras["completion"], "> htop -d 5 ");
+    assert_eq!(extras["defaultAction"], "terminal");
+    assert_eq!(
+        extras["actions"],
+        serde_json::json!(["terminal", "detached"])
+    );
+    let extras = results[2].extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], "Executable on PATH");
+
+    // New executables show up without restarting.
+    executable(&dir.path().join("bin"), "hx");
+    assert!(p.executables().contains(&"hx".to_string()));
+    assert!(!p.executables().contains(&"hello.txt".to_string()));
+}
+
+#[test]
+fn detached_runs_report_exit_codes_and_output() {
+    let (_dir, p) = setup();
+
+    let outcome = p.execute_outcome("detached", "run:echo hi; echo oops >&2; exit 3", &[]);
+    assert_eq!(outcome.status, "error");
+    assert_eq!(outcome.exit_code, Some(3));
+    assert_eq!(outcome.message.as_deref(), Some("Exited with 3"));
+
+    let preview = p.preview("run:echo hi; echo oops >&2; exit 3").unwrap();
+    assert_eq!(preview.data["comment"], "Exited with 3");
+    assert_eq!(preview.data["snippet"], "hi\n\noops");
+    assert_eq!(preview.data["exitCode"], 3);
+
+    let ok = p.execute_outcome("detached", "run:true", &[]);
+    assert_eq!((ok.status.as_str(), ok.exit_code), ("ok", Some(0)));
+
+    assert_eq!(
+        p.preview("run:uptime").unwrap().data["comment"],
+        "Not run yet"
+    );
+    assert_eq!(p.execute_outcome("detached", "run:", &[]).status, "error");
+    assert_eq!(p.execute_outcome("fly", "run:true", &[]).status, "error");
+    assert_eq!(
+        p.execute_outcome("detached", "run:true", &["x".into()])
+            .status,
+        "error"
+    );
+}
+
+#[test]
+fn long_commands_keep_running_in_the_background() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = provider(RunConfig {
+        wait_ms: 50,
+        mode: RunMode::Detached,
+        ..config(dir.path())
+    });
+
+    let outcome = p.execute_outcome("detached", "run:sleep 0.3; echo late", &[]);
+    assert_eq!(outcome.status, "ok");
+    assert_eq!(outcome.exit_code, None);
+    assert_eq!(
+        p.preview("run:sleep 0.3; echo late").unwrap().data["comment"],
+        "Still running"
+    );
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let data = p.preview("run:sleep 0.3; echo late").unwrap().data;
+        if data["comment"] == "Exited with 0" {
+            assert_eq!(data["snippet"], "late");
+            break;
+        }
+        assert!(Instant::now() < deadline, "never finished: {data}");
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let extras = p.search("> sleep").remove(0).extras.unwrap();
+    assert_eq!(extras["defaultAction"], "detached");
+}
+
+#[test]
+fn history_persists() {
+    let (dir, p) = setup();
+    for cmd in ["true", "echo done", "true"] {
+        assert!(p.execute("detached", &format!("run:{cmd}")));
+    }
+    drop(p);
+
+    let history = RunHistory::open(&dir.path().join("state/run_history.json")).unwrap();
+    let saved: Vec<(&str, u32)> = history
+        .entries()
+        .iter()
+        .map(|e| (e.command.as_str(), e.count))
+        .collect();
+    assert_eq!(saved, vec![("true", 2), ("echo done", 1)]);
+
+    let p = provider(config(dir.path()));
+    assert_eq!(titles(&p, "/run"), vec!["true", "echo done"]);
+    assert_eq!(
+        p.preview("run:true").unwrap().data["comment"],
+        "Ran 2 times"
+    );
+}
@@ -0,0 +1,400 @@
+//! Session and power actions through `org.freedesktop.login1`: `/sys` lists
+//! lock, suspend, hibernate, reboot, power off and log out, as far as logind
+//! says they are possible. Reboot, power off and log out need the UI's
+//! confirmation, passed as the `confirm` argument.
+
+mod login1;
+
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, bail};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{
+    MatchRange, Matches, Outcome, Preview, Provider, ResultItem, command_query, off_runtime,
+};
+use serde::Deserialize;
+use zbus::blocking::Connection;
+
+pub use login1::{ManagerProxy, SessionProxy};
+
+const PROVIDER_ID: &str = "session";
+const KEY_PREFIX: &str = "session:";
+const PREFIX: &str = "/sys";
+
+/// The argument that confirms a destructive action.
+pub const CONFIRM: &str = "confirm";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionAction {
+    Lock,
+    Suspend,
+    Hibernate,
+    Reboot,
+    PowerOff,
+    LogOut,
+}
+
+impl SessionAction {
+    pub const ALL: [SessionAction; 6] = [
+        SessionAction::Lock,
+        SessionAction::Suspend,
+        SessionAction::Hibernate,
+        SessionAction::Reboot,
+        SessionAction::PowerOff,
+        SessionAction::LogOut,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            SessionAction::Lock => "lock",
+            SessionAction::Suspend => "suspend",
+            SessionAction::Hibernate => "hibernate",
+            SessionAction::Reboot => "reboot",
+            SessionAction::PowerOff => "power_off",
+            SessionAction::LogOut => "log_out",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SessionAction::Lock => "Lock screen",
+            SessionAction::Suspend => "Suspend",
+            SessionAction::Hibernate => "Hibernate",
+            SessionAction::Reboot => "Reboot",
+            SessionAction::PowerOff => "Power off",
+            SessionAction::LogOut => "Log out",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            SessionAction::Lock => "Lock this session",
+            SessionAction::Suspend => "Sleep, keeping everything in memory",
+            SessionAction::Hibernate => "Save everything to disk and power off",
+            SessionAction::Reboot => "Restart the computer",
+            SessionAction::PowerOff => "Shut the computer down",
+            SessionAction::LogOut => "End this session",
+        }
+    }
+
+    /// Other words people type for it.
+    fn keywords(self) -> &'static str {
+        match self {
+            SessionAction::Lock => "screensaver",
+            SessionAction::Suspend => "sleep",
+            SessionAction::Hibernate => "sleep disk",
+            SessionAction::Reboot => "restart",
+            SessionAction::PowerOff => "shutdown halt",
+            SessionAction::LogOut => "logout sign out exit",
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            SessionAction::Lock => "system-lock-screen",
+            SessionAction::Suspend => "system-suspend",
+            SessionAction::Hibernate => "system-suspend-hibernate",
+            SessionAction::Reboot => "system-reboot",
+            SessionAction::PowerOff => "system-shutdown",
+            SessionAction::LogOut => "system-log-out",
+        }
+    }
+
+    /// Loses unsaved work, so the UI asks first.
+    pub fn is_destructive(self) -> bool {
+        matches!(
+            self,
+            SessionAction::Reboot | SessionAction::PowerOff | SessionAction::LogOut
+        )
+    }
+}
+
+/// What logind's `Can*` check says about an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    Yes,
+    /// Allowed once the user authenticates.
+    Challenge,
+    No,
+}
+
+impl Capability {
+    fn parse(answer: &str) -> Self {
+        match answer {
+            "yes" => Capability::Yes,
+            "challenge" => Capability::Challenge,
+            // "no", "na" and anything newer.
+            _ => Capability::No,
+        }
+    }
+}
+
+/// The `[session]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Action ids never to list, e.g. `["hibernate"]`.
+    pub hide: Vec<String>,
+    /// Lets polkit ask for a password where logind answers "challenge".
+    pub interactive: bool,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            hide: Vec::new(),
+            interactive: true,
+        }
+    }
+}
+
+pub struct SessionProvider {
+    config: SessionConfig,
+    /// Connected on first use, and again after the connection breaks.
+    conn: Mutex<Option<Connection>>,
+}
+
+impl SessionProvider {
+    /// Talks to logind on the system bus.
+    pub fn new(config: SessionConfig) -> Self {
+        Self {
+            config,
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// Talks to whatever serves `org.freedesktop.login1` on `conn`, e.g. a
+    /// mock on a private bus in tests.
+    pub fn with_connection(config: SessionConfig, conn: Connection) -> Self {
+        Self {
+            config,
+            conn: Mutex::new(Some(conn)),
+        }
+    }
+
+    /// Runs `call` against logind, off the engine's runtime.
+    fn login1<T: Send>(
+        &self,
+        call: impl FnOnce(&Connection) -> zbus::Result<T> + Send,
+    ) -> Result<T> {
+        off_runtime(|| {
+            let mut conn = self
+                .conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("logind connection lock poisoned"))?;
+            let connection = match conn.as_ref() {
+                Some(c) => c.clone(),
+                None => Connection::system().context("connect to the system bus")?,
+            };
+            let result = call(&connection);
+            *conn = match &result {
+                Err(zbus::Error::InputOutput(_)) => None,
+                _ => Some(connection),
+            };
+            result.context("call logind")
+        })
+    }
+
+    /// Whether logind allows `action`; locking and logging out always are.
+    pub fn capability(&self, action: SessionAction) -> Result<Capability> {
+        let answer = self.login1(|conn| ask(&ManagerProxy::new(conn)?, action))?;
+        Ok(Capability::parse(&answer))
+    }
+
+    /// The actions to list, with what logind says about each.
+    pub fn available(&self) -> Result<Vec<(SessionAction, Capability)>> {
+        let listed: Vec<SessionAction> = SessionAction::ALL
+            .into_iter()
+            .filter(|a| !self.config.hide.iter().any(|h| h == a.id()))
+            .collect();
+        let answers = self.login1(|conn| {
+            let manager = ManagerProxy::new(conn)?;
+            listed
+                .iter()
+                .map(|a| ask(&manager, *a))
+                .collect::<zbus::Result<Vec<_>>>()
+        })?;
+
+        Ok(listed
+            .into_iter()
+            .zip(answers)
+            .map(|(a, answer)| (a, Capability::parse(&answer)))
+            .filter(|(_, c)| *c != Capability::No)
+            .collect())
+    }
+
+    /// Actions whose title or else other names match the query, in their
+    /// usual order when there is no query.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &[PREFIX]);
+        let Ok(available) = self.available() else {
+            return Vec::new();
+        };
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        // (tier, score): title matches above keyword matches.
+        let mut hits: Vec<_> = available
+            .into_iter()
+            .filter_map(|(a, c)| {
+                if q.is_empty() {
+                    return Some(((0, 0), Vec::new(), a, c));
+                }
+                if let Some((score, idx)) = matcher.fuzzy_indices(a.title(), q) {
+                    return Some(((1, score), idx, a, c));
+                }
+                let score = matcher.fuzzy_match(a.keywords(), q)?;
+                Some(((0, score), Vec::new(), a, c))
+            })
+            .collect();
+        // Stable, so ties keep the usual order.
+        hits.sort_by_key(|(tier, ..)| std::cmp::Reverse(*tier));
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, idx, a, c))| to_result_item(a, c, 1.0 - rank as f32 / count, idx))
+            .collect()
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let action = parse_key(key).ok()?;
+        let capability = self.capability(action).ok()?;
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": action.title(),
+                "comment": action.description(),
+                "snippet": availability(capability),
+            }),
+        })
+    }
+
+    /// Runs the action behind `key`, which must be `action`. Destructive
+    /// ones only run once the UI has confirmed them with [`CONFIRM`].
+    pub fn execute_action(&self, action: &str, key: &str, args: &[String]) -> Result<()> {
+        let entry = parse_key(key)?;
+        if entry.id() != action {
+            bail!("Unknown action {action} for {key}");
+        }
+        if entry.is_destructive() && args.first().map(String::as_str) != Some(CONFIRM) {
+            bail!("{} needs confirmation", entry.title());
+        }
+        if self.capability(entry)? == Capability::No {
+            bail!("{} isn't possible right now", entry.title());
+        }
+
+        let interactive = self.config.interactive;
+        self.login1(|conn| match entry {
+            SessionAction::Lock => SessionProxy::new(conn)?.lock(),
+            SessionAction::LogOut => SessionProxy::new(conn)?.terminate(),
+            SessionAction::Suspend => ManagerProxy::new(conn)?.suspend(interactive),
+            SessionAction::Hibernate => ManagerProxy::new(conn)?.hibernate(interactive),
+            SessionAction::Reboot => ManagerProxy::new(conn)?.reboot(interactive),
+            SessionAction::PowerOff => ManagerProxy::new(conn)?.power_off(interactive),
+        })
+    }
+}
+
+impl Provider for SessionProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_with_args(action, key, &[])
+    }
+
+    fn execute_with_args(&self, action: &str, key: &str, args: &[String]) -> bool {
+        self.execute_outcome(action, key, args).status == "ok"
+    }
+
+    fn execute_outcome(&self, action: &str, key: &str, args: &[String]) -> Outcome {
+        match self.execute_action(action, key, args) {
+            Ok(()) => Outcome {
+                status: "ok".into(),
+                message: None,
+                exit_code: None,
+            },
+            Err(e) => Outcome {
+                status: "error".into(),
+                message: Some(format!("{e:#}")),
+                exit_code: None,
+            },
+        }
+    }
+}
+
+fn to_result_item(
+    action: SessionAction,
+    capability: Capability,
+    score: f32,
+    indices: Vec<usize>,
+) -> ResultItem {
+    let subtitle = match capability {
+        Capability::Challenge => format!("{} · asks for your password", action.description()),
+        _ => action.description().to_string(),
+    };
+    // The UI asks before running these.
+    let confirm: &[&str] = if action.is_destructive() {
+        &[action.id()]
+    } else {
+        &[]
+    };
+
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}", action.id()),
+        title: action.title().into(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": subtitle,
+            "icon": action.icon(),
+            "defaultAction": action.id(),
+            "actions": [action.id()],
+            "confirm": confirm,
+            "confirmText": format!("{} now?", action.title()),
+        })),
+        matches: (!indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(action.title(), indices),
+            subtitle: None,
+        }),
+    }
+}
+
+/// logind's answer to the `Can*` check for `action`.
+fn ask(manager: &ManagerProxy, action: SessionAction) -> zbus::Result<String> {
+    match action {
+        SessionAction::Lock | SessionAction::LogOut => Ok("yes".into()),
+        SessionAction::Suspend => manager.can_suspend(),
+        SessionAction::Hibernate => manager.can_hibernate(),
+        SessionAction::Reboot => manager.can_reboot(),
+        SessionAction::PowerOff => manager.can_power_off(),
+    }
+}
+
+fn availability(capability: Capability) -> &'static str {
+    match capability {
+        Capability::Yes => "Available",
+        Capability::Challenge => "Available after authenticating",
+        Capability::No => "Not available",
+    }
+}
+
+fn parse_key(key: &str) -> Result<SessionAction> {
+    key.strip_prefix(KEY_PREFIX)
+        .and_then(SessionAction::from_id)
+        .with_context(|| format!("Not a session key: {key}"))
+}
@@ -0,0 +1,45 @@
+//! The part of `org.freedesktop.login1` the provider uses.
+
+use zbus::proxy;
+
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1",
+    gen_async = false,
+    blocking_name = "ManagerProxy"
+)]
+pub trait Manager {
+    /// Each `Can*` answers "yes", "no", "challenge" (allowed after
+    /// authenticating) or "na" (not supported here).
+    fn can_suspend(&self) -> zbus::Result<String>;
+
+    fn can_hibernate(&self) -> zbus::Result<String>;
+
+    fn can_reboot(&self) -> zbus::Result<String>;
+
+    fn can_power_off(&self) -> zbus::Result<String>;
+
+    /// `interactive` lets polkit ask for authentication.
+    fn suspend(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn hibernate(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn reboot(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn power_off(&self, interactive: bool) -> zbus::Result<()>;
+}
+
+/// The caller's own session; logind resolves `auto` by the caller's PID.
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1/session/auto",
+    gen_async = false,
+    blocking_name = "SessionProxy"
+)]
+pub trait Session {
+    fn lock(&self) -> zbus::Result<()>;
+
+    fn terminate(&self) -> zbus::Result<()>;
+}
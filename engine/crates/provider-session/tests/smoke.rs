@@ -0,0 +1,243 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use lancea_model::Provider;
+use lancea_provider_session::{SessionConfig, SessionProvider};
+use lancea_testkit::titles;
+use zbus::interface;
+
+/// A `dbus-daemon` of our own, so tests need neither a system bus nor
+/// logind.
+struct PrivateBus {
+    daemon: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    fn start() -> Self {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("dbus-daemon is needed for these tests");
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+        Self {
+            daemon,
+            address: address.trim().to_string(),
+        }
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+type Calls = Arc<Mutex<Vec<String>>>;
+
+/// Serves the `Can*` checks and power calls of
+/// `org.freedesktop.login1.Manager`, recording the calls.
+struct MockManager {
+    can: [&'static str; 4],
+    calls: Calls,
+}
+
+impl MockManager {
+    fn record(&self, call: &str, interactive: bool) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("{call} interactive={interactive}"));
+    }
+}
+
+#[interface(name = "org.freedesktop.login1.Manager")]
+impl MockManager {
+    fn can_suspend(&self) -> String {
+        self.can[0].into()
+    }
+
+    fn can_hibernate(&self) -> String {
+        self.can[1].into()
+    }
+
+    fn can_reboot(&self) -> String {
+        self.can[2].into()
+    }
+
+    fn can_power_off(&self) -> String {
+        self.can[3].into()
+    }
+
+    fn suspend(&self, interactive: bool) {
+        self.record("Suspend", interactive);
+    }
+
+    fn hibernate(&self, interactive: bool) {
+        self.record("Hibernate", interactive);
+    }
+
+    fn reboot(&self, interactive: bool) {
+        self.record("Reboot", interactive);
+    }
+
+    fn power_off(&self, interactive: bool) {
+        self.record("PowerOff", interactive);
+    }
+}
+
+struct MockSession {
+    calls: Calls,
+}
+
+#[interface(name = "org.freedesktop.login1.Session")]
+impl MockSession {
+    fn lock(&self) {
+        self.calls.lock().unwrap().push("Lock".into());
+    }
+
+    fn terminate(&self) {
+        self.calls.lock().unwrap().push("Terminate".into());
+    }
+}
+
+struct Fixture {
+    provider: SessionProvider,
+    calls: Calls,
+    _rt: tokio::runtime::Runtime,
+    _server: zbus::Connection,
+    _bus: PrivateBus,
+}
+
+/// `can` answers CanSuspend, CanHibernate, CanReboot and CanPowerOff.
+fn setup(can: [&'static str; 4], config: SessionConfig) -> Fixture {
+    let bus = PrivateBus::start();
+    let calls = Calls::default();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let manager = MockManager {
+        can,
+        calls: calls.clone(),
+    };
+    let session = MockSession {
+        calls: calls.clone(),
+    };
+    let server = rt
+        .block_on(async {
+            zbus::connection::Builder::address(bus.address.as_str())?
+                .name("org.freedesktop.login1")?
+                .serve_at("/org/freedesktop/login1", manager)?
+                .serve_at("/org/freedesktop/login1/session/auto", session)?
+                .build()
+                .await
+        })
+        .unwrap();
+
+    let client = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    Fixture {
+        provider: SessionProvider::with_connection(config, client),
+        calls,
+        _rt: rt,
+        _server: server,
+        _bus: bus,
+    }
+}
+
+#[test]
+fn lists_what_logind_allows() {
+    let f = setup(["yes", "na", "challenge", "no"], SessionConfig::default());
+
+    assert_eq!(
+        titles(&f.provider, "/sys"),
+        vec!["Lock screen", "Suspend", "Reboot", "Log out"]
+    );
+    assert_eq!(titles(&f.provider, "/sys shutdown"), Vec::<String>::new());
+    assert_eq!(titles(&f.provider, "/sys restart"), vec!["Reboot"]);
+    assert_eq!(titles(&f.provider, "/sys sleep")[0], "Suspend");
+
+    let reboot = &f.provider.search("/sys reboot")[0];
+    let extras = reboot.extras.as_ref().unwrap();
+    assert_eq!(
+        extras["subtitle"],
+        "Restart the computer · asks for your password"
+    );
+    assert_eq!(extras["confirm"], serde_json::json!(["reboot"]));
+    let suspend = &f.provider.search("/sys suspend")[0];
+    assert_eq!(
+        suspend.extras.as_ref().unwrap()["confirm"],
+        serde_json::json!([])
+    );
+
+    let hidden = setup(
+        ["yes", "yes", "yes", "yes"],
+        SessionConfig {
+            hide: vec!["hibernate".into(), "lock".into()],
+            ..SessionConfig::default()
+        },
+    );
+    assert_eq!(
+        titles(&hidden.provider, "/sys"),
+        vec!["Suspend", "Reboot", "Power off", "Log out"]
+    );
+}
+
+#[test]
+fn destructive_actions_need_confirmation() {
+    let f = setup(["yes", "yes", "yes", "yes"], SessionConfig::default());
+    let p = &f.provider;
+    let confirm = vec!["confirm".to_string()];
+
+    for (action, key) in [
+        ("reboot", "session:reboot"),
+        ("power_off", "session:power_off"),
+        ("log_out", "session:log_out"),
+    ] {
+        let outcome = p.execute_outcome(action, key, &[]);
+        assert_eq!(outcome.status, "error");
+        assert!(outcome.message.unwrap().contains("needs confirmation"));
+    }
+    assert!(f.calls.lock().unwrap().is_empty());
+
+    assert!(p.execute("lock", "session:lock"));
+    assert!(p.execute("suspend", "session:suspend"));
+    assert!(p.execute_with_args("power_off", "session:power_off", &confirm));
+    assert!(p.execute_with_args("log_out", "session:log_out", &confirm));
+    assert!(!p.execute("suspend", "session:lock"));
+    assert_eq!(
+        *f.calls.lock().unwrap(),
+        vec![
+            "Lock",
+            "Suspend interactive=true",
+            "PowerOff interactive=true",
+            "Terminate",
+        ]
+    );
+}
+
+#[test]
+fn refuses_what_logind_does_not_allow() {
+    let f = setup(["no", "na", "yes", "yes"], SessionConfig::default());
+
+    let outcome = f
+        .provider
+        .execute_outcome("suspend", "session:suspend", &[]);
+    assert_eq!(outcome.status, "error");
+    assert!(f.calls.lock().unwrap().is_empty());
+
+    let preview = f.provider.preview("session:hibernate").unwrap();
+    assert_eq!(preview.data["snippet"], "Not available");
+    let preview = f.provider.preview("session:reboot").unwrap();
+    assert_eq!(preview.data["comment"], "Restart the computer");
+    assert_eq!(preview.data["snippet"], "Available");
+}
@@ -0,0 +1,62 @@
+//! Getting expanded text to the user: the clipboard, or typed into the
+//! focused window.
+
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Result;
+use lancea_model::clipboard;
+
+/// How long to wait before typing, so the launcher can hide and focus can
+/// return to the window the text is meant for.
+const TYPE_DELAY: Duration = Duration::from_millis(200);
+
+pub trait Desktop: Send + Sync {
+    /// Current clipboard text, if any.
+    fn clipboard(&self) -> Option<String>;
+    fn copy(&self, text: &str) -> Result<()>;
+    /// Types `text` into the focused window, then moves the caret
+    /// `cursor_back` chars to the left.
+    fn type_text(&self, text: &str, cursor_back: usize) -> Result<()>;
+}
+
+/// `wl-clipboard` and `wtype` on Wayland, `xclip` and `xdotool` on X11.
+#[derive(Debug, Default)]
+pub struct SystemDesktop;
+
+impl Desktop for SystemDesktop {
+    fn clipboard(&self) -> Option<String> {
+        clipboard::get()
+    }
+
+    fn copy(&self, text: &str) -> Result<()> {
+        Ok(clipboard::set(text)?)
+    }
+
+    fn type_text(&self, text: &str, cursor_back: usize) -> Result<()> {
+        let (typing, back): (Vec<&str>, Vec<String>) = if clipboard::wayland() {
+            let mut back = vec!["wtype".to_string()];
+            for _ in 0..cursor_back {
+                back.extend(["-k".to_string(), "Left".to_string()]);
+            }
+            (vec!["wtype", "-"], back)
+        } else {
+            let back = ["xdotool", "key", "--repeat"]
+                .map(String::from)
+                .into_iter()
+                .chain([cursor_back.to_string(), "Left".into()])
+                .collect();
+            (vec!["xdotool", "type", "--file", "-"], back)
+        };
+
+        let text = text.to_string();
+        thread::spawn(move || {
+            thread::sleep(TYPE_DELAY);
+            if clipboard::pipe(&typing, &text).is_ok() && cursor_back > 0 {
+                let _ = Command::new(&back[0]).args(&back[1..]).status();
+            }
+        });
+        Ok(())
+    }
+}
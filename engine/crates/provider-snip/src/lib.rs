@@ -0,0 +1,262 @@
+//! Text snippets from a directory of files (see [`Snippet`] for the format),
+//! matched like app names and expanded into the clipboard or typed out.
+
+mod desktop;
+mod snippet;
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{Matches, Preview, Provider, ResultItem, command_query};
+use lancea_provider_apps::{MatchTerms, MatchWeights, match_ranges, normalize_query};
+use serde::Deserialize;
+
+pub use desktop::{Desktop, SystemDesktop};
+pub use snippet::{Expansion, Inputs, Snippet, load_dir};
+
+const PROVIDER_ID: &str = "snip";
+const KEY_PREFIX: &str = "snip:";
+
+/// What selecting a snippet does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnipAction {
+    #[default]
+    Copy,
+    Type,
+}
+
+impl SnipAction {
+    fn id(self) -> &'static str {
+        match self {
+            SnipAction::Copy => "copy",
+            SnipAction::Type => "type",
+        }
+    }
+}
+
+/// The `[snip]` table of the engine config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SnipConfig {
+    /// Defaults to `$XDG_DATA_HOME/lancea/snippets`.
+    pub dir: Option<PathBuf>,
+    pub action: SnipAction,
+}
+
+/// A loaded snippet with its match terms.
+struct Entry {
+    snippet: Snippet,
+    terms: MatchTerms,
+    blob: String,
+}
+
+type Loaded = (Vec<(PathBuf, SystemTime)>, Arc<Vec<Entry>>);
+
+pub struct SnipProvider {
+    dir: Option<PathBuf>,
+    /// Snippets as of the last scan of `dir`; reloaded when a file changes.
+    cache: Mutex<Option<Loaded>>,
+    desktop: Box<dyn Desktop>,
+    action: SnipAction,
+    weights: MatchWeights,
+}
+
+impl SnipProvider {
+    pub fn new(config: SnipConfig) -> Self {
+        Self::with_desktop(config, Box::new(SystemDesktop))
+    }
+
+    /// Uses `desktop` for the clipboard and typing, e.g. a fake in tests.
+    pub fn with_desktop(config: SnipConfig, desktop: Box<dyn Desktop>) -> Self {
+        let dir = config
+            .dir
+            .or_else(|| dirs::data_dir().map(|d| d.join("lancea").join("snippets")));
+
+        Self {
+            dir,
+            cache: Mutex::new(None),
+            desktop,
+            action: config.action,
+            weights: MatchWeights::default(),
+        }
+    }
+
+    fn entries(&self) -> Arc<Vec<Entry>> {
+        let Some(dir) = self.dir.as_deref() else {
+            return Arc::default();
+        };
+        let files = snippet::scan(dir);
+
+        let Ok(mut cache) = self.cache.lock() else {
+            return Arc::default();
+        };
+        if let Some((seen, entries)) = cache.as_ref()
+            && *seen == files
+        {
+            return entries.clone();
+        }
+
+        let entries: Arc<Vec<Entry>> = Arc::new(
+            load_dir(dir)
+                .into_iter()
+                .map(|snippet| {
+                    let terms = MatchTerms::new(&snippet.name, None, &snippet.keywords);
+                    let blob = normalize_query(
+                        std::iter::once(snippet.name.as_str())
+                            .chain(snippet.keywords.iter().map(String::as_str))
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    );
+                    Entry {
+                        snippet,
+                        terms,
+                        blob,
+                    }
+                })
+                .collect(),
+        );
+        *cache = Some((files, entries.clone()));
+        entries
+    }
+
+    /// All snippets by name for an empty query; otherwise the same tiered
+    /// matching as apps, over names and keywords.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = normalize_query(command_query(raw_query, &["/snip"]));
+        let entries = self.entries();
+        let matcher = SkimMatcherV2::default();
+
+        let mut scored: Vec<(f32, &Entry)> = entries
+            .iter()
+            .filter_map(|e| {
+                if q.is_empty() {
+                    return Some((0.0, e));
+                }
+                e.terms
+                    .score(&e.blob, &q, &self.weights, &matcher)
+                    .map(|s| (s, e))
+            })
+            .collect();
+        // Stable, so equal scores stay in name order.
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        scored
+            .into_iter()
+            .map(|(score, e)| {
+                let matches = (!q.is_empty()).then(|| Matches {
+                    title: match_ranges(&e.snippet.name, &q, &matcher),
+                    subtitle: None,
+                });
+                self.to_result_item(&e.snippet, score, matches)
+            })
+            .collect()
+    }
+
+    fn to_result_item(
+        &self,
+        snippet: &Snippet,
+        score: f32,
+        matches: Option<Matches>,
+    ) -> ResultItem {
+        let other = match self.action {
+            SnipAction::Copy => SnipAction::Type,
+            SnipAction::Type => SnipAction::Copy,
+        };
+        let subtitle = snippet
+            .body
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .unwrap_or_default();
+
+        ResultItem {
+            key: format!("{KEY_PREFIX}{}", snippet.id),
+            title: snippet.name.clone(),
+            provider_id: PROVIDER_ID.into(),
+            score,
+            extras: Some(serde_json::json!({
+                "subtitle": subtitle,
+                "keywords": snippet.keywords,
+                "prompts": snippet.prompts(),
+                "defaultAction": self.action.id(),
+                "actions": [self.action.id(), other.id()],
+            })),
+            matches,
+        }
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let id = key.strip_prefix(KEY_PREFIX)?;
+        let entries = self.entries();
+        let snippet = &entries.iter().find(|e| e.snippet.id == id)?.snippet;
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": snippet.name,
+                "comment": snippet.keywords.join(", "),
+                "snippet": snippet.body,
+            }),
+        })
+    }
+
+    /// Expands the snippet behind `key`, with `args` answering its prompts.
+    pub fn expand(&self, key: &str, args: &[String]) -> Result<Expansion> {
+        let id = key
+            .strip_prefix(KEY_PREFIX)
+            .with_context(|| format!("Not a snip key: {key}"))?;
+        let entries = self.entries();
+        let snippet = &entries
+            .iter()
+            .find(|e| e.snippet.id == id)
+            .with_context(|| format!("No snippet {id}"))?
+            .snippet;
+
+        snippet.expand(&Inputs {
+            now: chrono::Local::now(),
+            clipboard: self.desktop.clipboard(),
+            args,
+        })
+    }
+
+    pub fn execute_copy(&self, key: &str, args: &[String]) -> Result<()> {
+        let expansion = self.expand(key, args)?;
+        self.desktop.copy(&expansion.text)
+    }
+
+    pub fn execute_type(&self, key: &str, args: &[String]) -> Result<()> {
+        let expansion = self.expand(key, args)?;
+        self.desktop
+            .type_text(&expansion.text, expansion.cursor_back)
+    }
+}
+
+impl Provider for SnipProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_with_args(action, key, &[])
+    }
+
+    fn execute_with_args(&self, action: &str, key: &str, args: &[String]) -> bool {
+        match action {
+            "copy" => self.execute_copy(key, args).is_ok(),
+            "type" => self.execute_type(key, args).is_ok(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Snippet files and their placeholders.
+//!
+//! A snippet is a text file, optionally starting with a frontmatter block:
+//!
+//! ```text
+//! ---
+//! name: Thanks for the report
+//! keywords: [bug, reply]
+//! ---
+//! Hi {arg:name}, thanks for reporting this on {date}.{cursor}
+//! ```
+//!
+//! Recognised placeholders are `{date}`, `{time}` (both optionally with a
+//! strftime format, as in `{date:%d.%m.%Y}`), `{clipboard}`, `{cursor}` and
+//! `{arg:NAME}`, which is asked for before expanding. Anything else in
+//! braces is kept as written, so code templates need no escaping.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    /// Path relative to the snippet directory.
+    pub id: String,
+    pub name: String,
+    pub keywords: Vec<String>,
+    /// The text after the frontmatter, placeholders unexpanded.
+    pub body: String,
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Date(String),
+    Time(String),
+    Clipboard,
+    Cursor,
+    Arg(String),
+}
+
+/// What a snippet expands to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expansion {
+    pub text: String,
+    /// Chars after the `{cursor}` mark: how far to move the caret back once
+    /// the text has been typed.
+    pub cursor_back: usize,
+}
+
+/// Values for the placeholders that don't come from the snippet itself.
+pub struct Inputs<'a> {
+    pub now: DateTime<Local>,
+    pub clipboard: Option<String>,
+    /// One per [`Snippet::prompts`] entry, in the same order.
+    pub args: &'a [String],
+}
+
+impl Snippet {
+    /// `id` names the snippet when the frontmatter doesn't.
+    pub fn parse(id: &str, text: &str) -> Self {
+        let (front, body) = split_frontmatter(text);
+        let mut name = None;
+        let mut keywords = Vec::new();
+
+        for line in front.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            match key.trim() {
+                "name" => name = Some(unquote(value.trim()).to_string()),
+                "keywords" => {
+                    let list = value.trim().trim_start_matches('[').trim_end_matches(']');
+                    keywords = list
+                        .split(',')
+                        .map(|k| unquote(k.trim()).to_string())
+                        .filter(|k| !k.is_empty())
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        let body = body
+            .strip_suffix('\n')
+            .map(|b| b.strip_suffix('\r').unwrap_or(b))
+            .unwrap_or(body);
+        let stem = Path::new(id)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| id.to_string());
+
+        Self {
+            id: id.to_string(),
+            name: name.filter(|n| !n.is_empty()).unwrap_or(stem),
+            keywords,
+            body: body.to_string(),
+            parts: parse_parts(body),
+        }
+    }
+
+    /// Names of the `{arg:…}` placeholders, each once, in order.
+    pub fn prompts(&self) -> Vec<&str> {
+        let mut out: Vec<&str> = Vec::new();
+        for part in &self.parts {
+            if let Part::Arg(name) = part
+                && !out.contains(&name.as_str())
+            {
+                out.push(name);
+            }
+        }
+        out
+    }
+
+    pub fn expand(&self, inputs: &Inputs) -> Result<Expansion> {
+        let prompts = self.prompts();
+        if inputs.args.len() < prompts.len() {
+            bail!(
+                "{} needs {} argument(s), got {}",
+                self.name,
+                prompts.len(),
+                inputs.args.len()
+            );
+        }
+
+        let mut text = String::new();
+        let mut cursor = None;
+        for part in &self.parts {
+            match part {
+                Part::Text(s) => text.push_str(s),
+                Part::Date(fmt) | Part::Time(fmt) => write!(text, "{}", inputs.now.format(fmt))
+                    .ok()
+                    .with_context(|| format!("bad date format {fmt:?} in {}", self.name))?,
+                Part::Clipboard => text.push_str(inputs.clipboard.as_deref().unwrap_or_default()),
+                Part::Cursor => {
+                    cursor.get_or_insert(text.chars().count());
+                }
+                Part::Arg(name) => {
+                    let i = prompts.iter().position(|p| p == name).unwrap_or_default();
+                    text.push_str(&inputs.args[i]);
+                }
+            }
+        }
+
+        let cursor_back = cursor.map_or(0, |at| text.chars().count() - at);
+        Ok(Expansion { text, cursor_back })
+    }
+}
+
+/// Frontmatter between `---` lines at the very top, and the rest.
+fn split_frontmatter(text: &str) -> (&str, &str) {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return ("", text);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (&rest[..offset], &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    // Unterminated: not frontmatter after all.
+    ("", text)
+}
+
+fn unquote(s: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(inner) = s.strip_prefix(q).and_then(|s| s.strip_suffix(q)) {
+            return inner;
+        }
+    }
+    s
+}
+
+fn parse_parts(body: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut rest = body;
+
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let placeholder = after
+            .find(['}', '{', '\n'])
+            .filter(|&close| after[close..].starts_with('}'))
+            .and_then(|close| placeholder(&after[..close]).map(|p| (p, close)));
+
+        match placeholder {
+            Some((part, close)) => {
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(part);
+                rest = &after[close + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    parts
+}
+
+fn placeholder(inner: &str) -> Option<Part> {
+    let (name, arg) = match inner.split_once(':') {
+        Some((name, arg)) => (name, Some(arg)),
+        None => (inner, None),
+    };
+    match (name, arg) {
+        ("date", fmt) => Some(Part::Date(fmt.unwrap_or("%Y-%m-%d").to_string())),
+        ("time", fmt) => Some(Part::Time(fmt.unwrap_or("%H:%M").to_string())),
+        ("clipboard", None) => Some(Part::Clipboard),
+        ("cursor", None) => Some(Part::Cursor),
+        ("arg", Some(name)) if !name.trim().is_empty() => Some(Part::Arg(name.trim().to_string())),
+        _ => None,
+    }
+}
+
+/// Every snippet file under `dir`, recursively, skipping hidden ones.
+pub fn load_dir(dir: &Path) -> Vec<Snippet> {
+    let mut out: Vec<Snippet> = scan(dir)
+        .into_iter()
+        .filter_map(|(path, _)| {
+            let text = fs::read_to_string(&path).ok()?;
+            let id = path.strip_prefix(dir).ok()?.to_string_lossy().into_owned();
+            Some(Snippet::parse(&id, &text))
+        })
+        .collect();
+    out.sort_by_key(|s| s.name.to_lowercase());
+    out
+}
+
+/// Snippet files under `dir` with their mtimes, in a stable order; cheap
+/// enough to compare on every search to notice edits.
+pub(crate) fn scan(dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    let mut out = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(d) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&d) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(entry.path());
+            } else if let Ok(modified) = meta.modified() {
+                out.push((entry.path(), modified));
+            }
+        }
+    }
+    out.sort();
+    out
+}
@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use lancea_model::Provider;
+use lancea_provider_snip::{Desktop, Inputs, SnipAction, SnipConfig, SnipProvider, Snippet};
+use lancea_testkit::titles;
+use tempfile::TempDir;
+
+/// Records what would have been copied or typed.
+#[derive(Clone, Default)]
+struct FakeDesktop {
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeDesktop {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.log.lock().unwrap())
+    }
+}
+
+impl Desktop for FakeDesktop {
+    fn clipboard(&self) -> Option<String> {
+        Some("https://example.org/issue/7".into())
+    }
+
+    fn copy(&self, text: &str) -> Result<()> {
+        self.log.lock().unwrap().push(format!("copy {text}"));
+        Ok(())
+    }
+
+    fn type_text(&self, text: &str, cursor_back: usize) -> Result<()> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("type {text} <{cursor_back}"));
+        Ok(())
+    }
+}
+
+fn write(root: &Path, rel: &str, contents: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn setup(action: SnipAction) -> (TempDir, SnipProvider, FakeDesktop) {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "replies/thanks.md",
+        "---\nname: Thanks for the report\nkeywords: [bug, reply]\n---\n\
+         Hi {arg:name}, thanks for {clipboard}!\n{cursor}\n-- {arg:name}'s friend\n",
+    );
+    write(
+        root,
+        "rust-main.rs",
+        "---\nname: \"Rust main\"\nkeywords: code, template\n---\nfn main() {\n    {cursor}\n}\n",
+    );
+    write(root, "signature.txt", "Best,\nAlex\n");
+    write(root, ".draft.txt", "not a snippet");
+
+    let desktop = FakeDesktop::default();
+    let config = SnipConfig {
+        dir: Some(root.to_path_buf()),
+        action,
+    };
+    let p = SnipProvider::with_desktop(config, Box::new(desktop.clone()));
+    (dir, p, desktop)
+}
+
+#[test]
+fn parses_frontmatter_and_placeholders() {
+    let s = Snippet::parse(
+        "notes/stamp.txt",
+        "---\nname: Stamp\n---\nOn {date} at {time:%H.%M} ({date:%d/%m}) {arg:who} said {x} {arg:who}{cursor}.\n",
+    );
+    assert_eq!(s.name, "Stamp");
+    assert_eq!(s.prompts(), vec!["who"]);
+
+    let now = Local.with_ymd_and_hms(2026, 10, 18, 9, 5, 0).unwrap();
+    let args = vec!["Sam".to_string()];
+    let out = s
+        .expand(&Inputs {
+            now,
+            clipboard: None,
+            args: &args,
+        })
+        .unwrap();
+    assert_eq!(out.text, "On 2026-10-18 at 09.05 (18/10) Sam said {x} Sam.");
+    assert_eq!(out.cursor_back, 1);
+
+    let missing = s.expand(&Inputs {
+        now,
+        clipboard: None,
+        args: &[],
+    });
+    assert!(missing.is_err());
+
+    let plain = Snippet::parse("greeting.txt", "--- not frontmatter\n");
+    assert_eq!(plain.name, "greeting");
+    assert_eq!(plain.body, "--- not frontmatter");
+}
+
+#[test]
+fn searches_like_apps() {
+    let (_dir, p, _) = setup(SnipAction::Copy);
+
+    assert_eq!(
+        titles(&p, ""),
+        vec!["Rust main", "signature", "Thanks for the report"]
+    );
+    assert_eq!(titles(&p, "/snip tftr"), vec!["Thanks for the report"]);
+    assert_eq!(titles(&p, "template")[0], "Rust main");
+    assert_eq!(titles(&p, "THÄNKS")[0], "Thanks for the report");
+    assert!(titles(&p, "draft").is_empty());
+
+    let results = p.search("thanks");
+    let item = &results[0];
+    assert_eq!(item.key, "snip:replies/thanks.md");
+    let extras = item.extras.as_ref().unwrap();
+    assert_eq!(extras["prompts"], serde_json::json!(["name"]));
+    assert_eq!(extras["defaultAction"], "copy");
+    assert_eq!(extras["actions"], serde_json::json!(["copy", "type"]));
+    let ranges = &item.matches.as_ref().unwrap().title;
+    assert_eq!((ranges[0].start, ranges[0].end), (0, 6));
+}
+
+#[test]
+fn copies_and_types_expansions() {
+    let (_dir, p, desktop) = setup(SnipAction::Type);
+    let args = vec!["Kim".to_string()];
+
+    assert!(p.execute_with_args("copy", "snip:replies/thanks.md", &args));
+    assert!(p.execute("type", "snip:rust-main.rs"));
+    assert_eq!(
+        desktop.take(),
+        vec![
+            "copy Hi Kim, thanks for https://example.org/issue/7!\n\n-- Kim's friend",
+            "type fn main() {\n    \n} <2",
+        ]
+    );
+
+    // Prompts must be answered.
+    assert!(!p.execute("copy", "snip:replies/thanks.md"));
+    assert!(!p.execute("copy", "snip:nope.txt"));
+    assert!(desktop.take().is_empty());
+
+    let extras = p.search("signature")[0].extras.clone().unwrap();
+    assert_eq!(extras["defaultAction"], "type");
+}
+
+#[test]
+fn reloads_when_files_change() {
+    let (dir, p, _) = setup(SnipAction::Copy);
+    assert_eq!(titles(&p, "").len(), 3);
+
+    write(
+        dir.path(),
+        "addr.txt",
+        "---\nname: Address\n---\n1 Main St\n",
+    );
+    fs::remove_file(dir.path().join("signature.txt")).unwrap();
+    assert_eq!(
+        titles(&p, ""),
+        vec!["Address", "Rust main", "Thanks for the report"]
+    );
+
+    let preview = p.preview("snip:addr.txt").unwrap();
+    assert_eq!(preview.data["snippet"], "1 Main St");
+}
@@ -0,0 +1,217 @@
+//! Just enough of `ssh_config(5)` to list hosts and resolve their settings:
+//! `Host` blocks with wildcard and negated patterns, `Include` (globbed,
+//! relative to `~/.ssh`, also inside blocks) and first-value-wins lookup.
+//! `Match` blocks can't be evaluated here and are skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How deep `Include`s may nest, as in OpenSSH.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Default)]
+pub struct SshConfigFile {
+    blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    /// `Host` patterns; empty for `Match` blocks, which never apply.
+    patterns: Vec<String>,
+    /// Keyword (as written) and value, in file order.
+    options: Vec<(String, String)>,
+}
+
+impl SshConfigFile {
+    /// Reads `path`, resolving relative `Include`s against `ssh_dir`.
+    /// Unreadable files count as empty.
+    pub fn load(path: &Path, ssh_dir: &Path) -> Self {
+        let mut config = Self {
+            // Options before the first `Host` apply to every host.
+            blocks: vec![Block {
+                patterns: vec!["*".into()],
+                options: Vec::new(),
+            }],
+        };
+        config.read(path, ssh_dir, 0, 0);
+        config
+    }
+
+    pub fn parse(text: &str, ssh_dir: &Path) -> Self {
+        let mut config = Self {
+            blocks: vec![Block {
+                patterns: vec!["*".into()],
+                options: Vec::new(),
+            }],
+        };
+        config.read_text(text, ssh_dir, 0, 0);
+        config
+    }
+
+    fn read(&mut self, path: &Path, ssh_dir: &Path, current: usize, depth: usize) {
+        if let Ok(text) = fs::read_to_string(path) {
+            self.read_text(&text, ssh_dir, current, depth);
+        }
+    }
+
+    /// Reads `text` with options going to block `current` until a `Host` or
+    /// `Match` line starts a new one.
+    fn read_text(&mut self, text: &str, ssh_dir: &Path, mut current: usize, depth: usize) {
+        for line in text.lines() {
+            let Some((key, value)) = split_line(line) else {
+                continue;
+            };
+
+            match key.to_ascii_lowercase().as_str() {
+                "host" => {
+                    self.blocks.push(Block {
+                        patterns: words(value),
+                        options: Vec::new(),
+                    });
+                    current = self.blocks.len() - 1;
+                }
+                "match" => {
+                    self.blocks.push(Block {
+                        patterns: Vec::new(),
+                        options: Vec::new(),
+                    });
+                    current = self.blocks.len() - 1;
+                }
+                "include" if depth < MAX_INCLUDE_DEPTH => {
+                    for pattern in words(value) {
+                        for path in expand_include(&pattern, ssh_dir) {
+                            // Blocks opened by the included file end with it.
+                            self.read(&path, ssh_dir, current, depth + 1);
+                        }
+                    }
+                }
+                "include" => {}
+                _ => self.blocks[current]
+                    .options
+                    .push((key.to_string(), unquote(value).to_string())),
+            }
+        }
+    }
+
+    /// Concrete aliases from `Host` lines, in file order, without
+    /// duplicates. Wildcard and negated patterns are not hosts.
+    pub fn aliases(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        // The first block holds the top-level options.
+        for block in self.blocks.iter().skip(1) {
+            for p in &block.patterns {
+                if !p.contains(['*', '?', '!']) && !out.contains(p) {
+                    out.push(p.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Every option that applies to `host`, first value winning, in the
+    /// order they were found.
+    pub fn resolve(&self, host: &str) -> Vec<(String, String)> {
+        let mut out: Vec<(String, String)> = Vec::new();
+        for block in self.blocks.iter().filter(|b| block_matches(b, host)) {
+            for (key, value) in &block.options {
+                if !out.iter().any(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                    out.push((key.clone(), value.clone()));
+                }
+            }
+        }
+        out
+    }
+
+    /// The resolved value of one keyword.
+    pub fn get(&self, host: &str, key: &str) -> Option<String> {
+        self.resolve(host)
+            .into_iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+}
+
+fn block_matches(block: &Block, host: &str) -> bool {
+    let mut matched = false;
+    for p in &block.patterns {
+        match p.strip_prefix('!') {
+            Some(negated) if wildcard(negated, host) => return false,
+            Some(_) => {}
+            None => matched |= wildcard(p, host),
+        }
+    }
+    matched
+}
+
+/// `*` and `?` matching, as in `Host` patterns.
+fn wildcard(pattern: &str, text: &str) -> bool {
+    fn go(p: &[char], t: &[char]) -> bool {
+        match p.split_first() {
+            None => t.is_empty(),
+            Some(('*', rest)) => (0..=t.len()).any(|i| go(rest, &t[i..])),
+            Some(('?', rest)) => !t.is_empty() && go(rest, &t[1..]),
+            Some((c, rest)) => t
+                .first()
+                .is_some_and(|tc| tc.eq_ignore_ascii_case(c) && go(rest, &t[1..])),
+        }
+    }
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    go(&p, &t)
+}
+
+/// Keyword and the rest of the line, for `Key value` and `Key=value`.
+fn split_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let (key, rest) = line.split_at(end);
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim_start();
+    Some((key, rest))
+}
+
+/// Whitespace-separated words, honouring double quotes.
+fn words(value: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut cur = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !cur.is_empty() {
+                    out.push(std::mem::take(&mut cur));
+                }
+            }
+            c => cur.push(c),
+        }
+    }
+    if !cur.is_empty() {
+        out.push(cur);
+    }
+    out
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Files an `Include` pattern names, sorted as `glob(3)` would.
+fn expand_include(pattern: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let path = match pattern.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().map_or_else(|| ssh_dir.join(rest), |h| h.join(rest)),
+        None if Path::new(pattern).is_absolute() => PathBuf::from(pattern),
+        None => ssh_dir.join(pattern),
+    };
+    let Ok(paths) = glob::glob(&path.to_string_lossy()) else {
+        return Vec::new();
+    };
+    let mut out: Vec<PathBuf> = paths.flatten().filter(|p| p.is_file()).collect();
+    out.sort();
+    out
+}
@@ -0,0 +1,52 @@
+//! Host names from `known_hosts` files. Hashed entries (`|1|…`) can't be
+//! listed and `@cert-authority`/`@revoked` lines aren't hosts, so both are
+//! skipped, as are wildcard patterns.
+
+/// A host seen in `known_hosts`, with its port when not 22.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownHost {
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+pub fn parse(text: &str) -> Vec<KnownHost> {
+    let mut out: Vec<KnownHost> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('@') {
+            continue;
+        }
+        let Some(hosts) = line.split_whitespace().next() else {
+            continue;
+        };
+
+        for entry in hosts.split(',') {
+            if entry.starts_with('|') || entry.contains(['*', '?', '!']) {
+                continue;
+            }
+            let Some(known) = parse_host(entry) else {
+                continue;
+            };
+            if !out.contains(&known) {
+                out.push(known);
+            }
+        }
+    }
+    out
+}
+
+/// `host` or `[host]:port`.
+fn parse_host(entry: &str) -> Option<KnownHost> {
+    let Some(rest) = entry.strip_prefix('[') else {
+        return (!entry.is_empty()).then(|| KnownHost {
+            host: entry.to_string(),
+            port: None,
+        });
+    };
+    let (host, port) = rest.split_once("]:")?;
+    let port: u16 = port.parse().ok()?;
+    Some(KnownHost {
+        host: host.to_string(),
+        port: (port != 22).then_some(port),
+    })
+}
@@ -0,0 +1,298 @@
+//! SSH hosts from `~/.ssh/config` (following `Include`s) and unhashed
+//! `known_hosts` entries, each opened in the configured terminal.
+
+mod config;
+mod known_hosts;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Preview, Provider, ResultItem, command_query, tildify};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use config::SshConfigFile;
+pub use known_hosts::{KnownHost, parse as parse_known_hosts};
+
+const PROVIDER_ID: &str = "ssh";
+const KEY_PREFIX: &str = "ssh:";
+
+/// The `[ssh]` table of the engine config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SshConfig {
+    /// Defaults to `~/.ssh/config`. Relative `Include`s resolve against
+    /// this file's directory.
+    pub config: Option<PathBuf>,
+    /// Defaults to `~/.ssh/known_hosts`.
+    pub known_hosts: Option<Vec<PathBuf>>,
+}
+
+/// A host to connect to, with its settings resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SshHost {
+    /// The `Host` alias, or the known_hosts name (`host:port` when the
+    /// port isn't 22).
+    pub alias: String,
+    pub hostname: String,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    /// Every option that applies, as `ssh -G` would list the configured ones.
+    pub settings: Vec<(String, String)>,
+    /// The file the host was found in.
+    pub source: PathBuf,
+    /// What "connect" runs.
+    pub command: Vec<String>,
+}
+
+impl SshHost {
+    /// `user@hostname:port`, leaving out what isn't set.
+    pub fn target(&self) -> String {
+        let mut out = String::new();
+        if let Some(user) = &self.user {
+            out.push_str(user);
+            out.push('@');
+        }
+        out.push_str(&self.hostname);
+        if let Some(port) = self.port {
+            out.push_str(&format!(":{port}"));
+        }
+        out
+    }
+}
+
+pub struct SshProvider {
+    config: Option<PathBuf>,
+    known_hosts: Vec<PathBuf>,
+    apps: Arc<AppsProvider>,
+}
+
+impl SshProvider {
+    pub fn new(config: SshConfig, apps: Arc<AppsProvider>) -> Self {
+        let ssh_dir = dirs::home_dir().map(|h| h.join(".ssh"));
+        let known_hosts = config
+            .known_hosts
+            .unwrap_or_else(|| ssh_dir.iter().map(|d| d.join("known_hosts")).collect());
+
+        Self {
+            config: config.config.or_else(|| ssh_dir.map(|d| d.join("config"))),
+            known_hosts,
+            apps,
+        }
+    }
+
+    /// Config hosts in file order, then known_hosts entries not already
+    /// covered by an alias or `HostName`. Both files are small, so they are
+    /// re-read every time rather than watched.
+    pub fn hosts(&self) -> Vec<SshHost> {
+        let parsed = match self.config.as_deref() {
+            Some(path) => SshConfigFile::load(path, path.parent().unwrap_or(Path::new("."))),
+            None => SshConfigFile::default(),
+        };
+        let source = self.config.clone().unwrap_or_default();
+
+        let mut hosts: Vec<SshHost> = parsed
+            .aliases()
+            .into_iter()
+            .map(|alias| {
+                let settings = parsed.resolve(&alias);
+                let get = |key: &str| {
+                    settings
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                        .map(|(_, v)| v.clone())
+                };
+                SshHost {
+                    hostname: get("HostName").unwrap_or_else(|| alias.clone()),
+                    user: get("User"),
+                    port: get("Port")
+                        .and_then(|p| p.parse().ok())
+                        .filter(|&p| p != 22),
+                    command: vec!["ssh".into(), alias.clone()],
+                    alias,
+                    settings,
+                    source: source.clone(),
+                }
+            })
+            .collect();
+
+        for path in &self.known_hosts {
+            let Ok(text) = fs::read_to_string(path) else {
+                continue;
+            };
+            for known in parse_known_hosts(&text) {
+                let host = known_host(&parsed, &known.host, known.port.unwrap_or(22), path);
+                let covered = hosts.iter().any(|h| {
+                    h.alias == host.alias || (h.hostname == host.hostname && h.port == host.port)
+                });
+                if !covered {
+                    hosts.push(host);
+                }
+            }
+        }
+        hosts
+    }
+
+    fn host(&self, key: &str) -> Result<SshHost> {
+        let alias = key
+            .strip_prefix(KEY_PREFIX)
+            .with_context(|| format!("Not an ssh key: {key}"))?;
+        self.hosts()
+            .into_iter()
+            .find(|h| h.alias == alias)
+            .with_context(|| format!("No ssh host {alias}"))
+    }
+
+    /// Every host for an empty query; otherwise fuzzy matches on the alias
+    /// or on `user@hostname:port`, whichever scores higher.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &["/ssh"]);
+        let hosts = self.hosts();
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let mut hits: Vec<(i64, usize, Option<Matches>)> = hosts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, h)| {
+                if q.is_empty() {
+                    return Some((0, i, None));
+                }
+                let target = h.target();
+                let by_alias = matcher.fuzzy_indices(&h.alias, q);
+                let by_target = matcher.fuzzy_indices(&target, q);
+                match (by_alias, by_target) {
+                    (Some((s, idx)), t) if t.as_ref().is_none_or(|(ts, _)| s >= *ts) => {
+                        let matches = Matches {
+                            title: MatchRange::from_char_indices(&h.alias, idx),
+                            subtitle: None,
+                        };
+                        Some((s, i, Some(matches)))
+                    }
+                    (_, Some((s, idx))) => {
+                        let matches = Matches {
+                            title: Vec::new(),
+                            subtitle: Some(MatchRange::from_char_indices(&target, idx)),
+                        };
+                        Some((s, i, Some(matches)))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        // Ties keep config order.
+        hits.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, i, matches))| {
+                to_result_item(&hosts[i], 1.0 - rank as f32 / count, matches)
+            })
+            .collect()
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let host = self.host(key).ok()?;
+        let settings: Vec<String> = host
+            .settings
+            .iter()
+            .map(|(k, v)| format!("{k} {v}"))
+            .collect();
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": host.alias,
+                "comment": tildify(&host.source),
+                "snippet": if settings.is_empty() {
+                    format!("HostName {}", host.hostname)
+                } else {
+                    settings.join("\n")
+                },
+            }),
+        })
+    }
+
+    /// The command line "connect" runs for `key`, before the terminal.
+    pub fn command(&self, key: &str) -> Result<Vec<String>> {
+        Ok(self.host(key)?.command)
+    }
+
+    pub fn execute_connect(&self, key: &str) -> Result<()> {
+        let host = self.host(key)?;
+        self.apps
+            .run_in_terminal(PROVIDER_ID, &format!("SSH {}", host.alias), &host.command)
+    }
+}
+
+/// A known_hosts entry, picking up whatever `Host *` and friends set for it.
+fn known_host(parsed: &SshConfigFile, name: &str, port: u16, source: &Path) -> SshHost {
+    let settings = parsed.resolve(name);
+    let user = settings
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("User"))
+        .map(|(_, v)| v.clone());
+    let port = (port != 22).then_some(port);
+
+    let mut command = vec!["ssh".to_string()];
+    if let Some(port) = port {
+        command.extend(["-p".into(), port.to_string()]);
+    }
+    command.push(name.to_string());
+
+    SshHost {
+        alias: match port {
+            Some(port) => format!("{name}:{port}"),
+            None => name.to_string(),
+        },
+        hostname: name.to_string(),
+        user,
+        port,
+        settings,
+        source: source.to_path_buf(),
+        command,
+    }
+}
+
+fn to_result_item(host: &SshHost, score: f32, matches: Option<Matches>) -> ResultItem {
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}", host.alias),
+        title: host.alias.clone(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": host.target(),
+            "copyText": host.command.join(" "),
+            "defaultAction": "connect",
+            "actions": ["connect", "copy"],
+        })),
+        matches,
+    }
+}
+
+impl Provider for SshProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "connect" => self.execute_connect(key).is_ok(),
+            "copy" => self.host(key).is_ok(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::fs;
+
+use lancea_model::Provider;
+use lancea_provider_ssh::{KnownHost, SshConfig, SshConfigFile, SshProvider, parse_known_hosts};
+use lancea_testkit::{quiet_terminal, titles};
+use tempfile::TempDir;
+
+const CONFIG: &str = "\
+# Top-level options apply everywhere.
+ServerAliveInterval 30
+
+Host web1 web2
+    HostName web.example.org
+    User deploy
+
+Host db
+    HostName=10.0.0.5
+    Port 2222
+    Include hosts.d/*.conf
+
+Host *.lan !printer.lan
+    User pi
+
+Host * \"quoted alias\"
+    User fallback
+    IdentityFile ~/.ssh/id_ed25519
+
+Match host nas
+    User ignored
+";
+
+const KNOWN_HOSTS: &str = "\
+web.example.org,192.0.2.7 ssh-ed25519 AAAAC3Nza
+|1|aGFzaGVk|c2FsdA== ssh-ed25519 AAAAC3Nza
+[git.example.org]:2200 ssh-ed25519 AAAAC3Nza
+[10.0.0.5]:2222 ssh-ed25519 AAAAC3Nza
+@cert-authority *.corp ssh-ed25519 AAAAC3Nza
+pi.lan ssh-rsa AAAAB3Nza
+";
+
+fn setup() -> (TempDir, SshProvider) {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::write(root.join("config"), CONFIG).unwrap();
+    fs::create_dir(root.join("hosts.d")).unwrap();
+    fs::write(
+        root.join("hosts.d/10-backup.conf"),
+        "ForwardAgent yes\nHost backup\n    HostName backup.example.org\n",
+    )
+    .unwrap();
+    fs::write(root.join("known_hosts"), KNOWN_HOSTS).unwrap();
+
+    let config = SshConfig {
+        config: Some(root.join("config")),
+        known_hosts: Some(vec![root.join("known_hosts"), root.join("missing")]),
+    };
+    (dir, SshProvider::new(config, quiet_terminal()))
+}
+
+#[test]
+fn parses_config_with_includes() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("extra"), "Host extra\n  User x\n").unwrap();
+    let parsed = SshConfigFile::parse(
+        CONFIG.replace("hosts.d/*.conf", "extra").as_str(),
+        dir.path(),
+    );
+
+    assert_eq!(
+        parsed.aliases(),
+        vec!["web1", "web2", "db", "extra", "quoted alias"]
+    );
+    // First value wins; the include inside `Host db` still belongs to db.
+    assert_eq!(parsed.get("db", "user").as_deref(), Some("fallback"));
+    assert_eq!(parsed.get("web2", "User").as_deref(), Some("deploy"));
+    assert_eq!(parsed.get("nas", "User").as_deref(), Some("fallback"));
+    assert_eq!(parsed.get("pi.lan", "User").as_deref(), Some("pi"));
+    assert_eq!(
+        parsed.get("printer.lan", "User").as_deref(),
+        Some("fallback")
+    );
+    assert_eq!(parsed.get("extra", "User").as_deref(), Some("x"));
+    assert_eq!(
+        parsed.get("web1", "ServerAliveInterval").as_deref(),
+        Some("30")
+    );
+
+    let missing = SshConfigFile::load(&dir.path().join("nope"), dir.path());
+    assert!(missing.aliases().is_empty());
+}
+
+#[test]
+fn parses_known_hosts() {
+    assert_eq!(
+        parse_known_hosts(KNOWN_HOSTS),
+        vec![
+            KnownHost {
+                host: "web.example.org".into(),
+                port: None
+            },
+            KnownHost {
+                host: "192.0.2.7".into(),
+                port: None
+            },
+            KnownHost {
+                host: "git.example.org".into(),
+                port: Some(2200)
+            },
+            KnownHost {
+                host: "10.0.0.5".into(),
+                port: Some(2222)
+            },
+            KnownHost {
+                host: "pi.lan".into(),
+                port: None
+            },
+        ]
+    );
+}
+
+#[test]
+fn lists_and_searches_hosts() {
+    let (_dir, p) = setup();
+
+    // web.example.org and [10.0.0.5]:2222 are covered by config hosts.
+    assert_eq!(
+        titles(&p, ""),
+        vec![
+            "web1",
+            "web2",
+            "db",
+            "backup",
+            "quoted alias",
+            "192.0.2.7",
+            "git.example.org:2200",
+            "pi.lan",
+        ]
+    );
+    assert_eq!(titles(&p, "/ssh backup")[0], "backup");
+
+    let results = p.search("deploy");
+    assert_eq!(titles(&p, "deploy"), vec!["web1", "web2"]);
+    let item = &results[0];
+    assert_eq!(item.key, "ssh:web1");
+    let extras = item.extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], "deploy@web.example.org");
+    assert_eq!(extras["copyText"], "ssh web1");
+    assert_eq!(extras["defaultAction"], "connect");
+    let matches = item.matches.as_ref().unwrap();
+    assert!(matches.title.is_empty());
+    let ranges = matches.subtitle.as_ref().unwrap();
+    assert_eq!((ranges[0].start, ranges[0].end), (0, 6));
+
+    let db = &p.search("db")[0];
+    assert_eq!(
+        db.extras.as_ref().unwrap()["subtitle"],
+        "fallback@10.0.0.5:2222"
+    );
+    let pi = &p.search("pi.lan")[0];
+    assert_eq!(pi.extras.as_ref().unwrap()["subtitle"], "pi@pi.lan");
+}
+
+#[test]
+fn previews_resolved_settings() {
+    let (dir, p) = setup();
+
+    let preview = p.preview("ssh:db").unwrap();
+    assert_eq!(preview.data["title"], "db");
+    assert_eq!(
+        preview.data["comment"],
+        dir.path().join("config").display().to_string()
+    );
+    assert_eq!(
+        preview.data["snippet"],
+        "ServerAliveInterval 30\nHostName 10.0.0.5\nPort 2222\nForwardAgent yes\n\
+         User fallback\nIdentityFile ~/.ssh/id_ed25519"
+    );
+    assert!(p.preview("ssh:nas").is_none());
+}
+
+#[test]
+fn connects_through_the_terminal() {
+    let (_dir, p) = setup();
+
+    assert_eq!(p.command("ssh:web2").unwrap(), ["ssh", "web2"]);
+    assert_eq!(
+        p.command("ssh:git.example.org:2200").unwrap(),
+        ["ssh", "-p", "2200", "git.example.org"]
+    );
+    assert!(p.command("ssh:nope").is_err());
+    assert!(p.command("apps:web1").is_err());
+
+    assert!(p.execute("connect", "ssh:web1"));
+    assert!(p.execute("copy", "ssh:pi.lan"));
+    assert!(!p.execute("connect", "ssh:nope"));
+    assert!(!p.execute("sftp", "ssh:web1"));
+}
@@ -0,0 +1,323 @@
+//! The user's systemd units from `org.freedesktop.systemd1` on the session
+//! bus: `/unit sync` finds them with their state, starts, stops and restarts
+//! them, and follows their logs in the terminal.
+
+mod manager;
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{
+    MatchRange, Matches, Outcome, Preview, Provider, ResultItem, command_query, off_runtime,
+};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+use zbus::blocking::Connection;
+
+pub use manager::{ManagerProxy, UnitStatus};
+
+const PROVIDER_ID: &str = "systemd";
+const KEY_PREFIX: &str = "systemd:";
+const PREFIX: &str = "/unit";
+
+/// Job mode for start, stop and restart: replace whatever is queued.
+const JOB_MODE: &str = "replace";
+
+/// The `[systemd]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SystemdConfig {
+    /// Unit types to list, by suffix; all of them when empty.
+    pub unit_types: Vec<String>,
+    pub max_results: usize,
+}
+
+impl Default for SystemdConfig {
+    fn default() -> Self {
+        Self {
+            unit_types: vec!["service".into(), "timer".into(), "socket".into()],
+            max_results: 50,
+        }
+    }
+}
+
+/// A loaded unit as `ListUnits` reports it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    pub description: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+}
+
+impl Unit {
+    fn from_status(status: UnitStatus) -> Self {
+        let (name, description, load_state, active_state, sub_state, ..) = status;
+        Self {
+            name,
+            description,
+            load_state,
+            active_state,
+            sub_state,
+        }
+    }
+
+    /// `service` for `foo.service`.
+    pub fn unit_type(&self) -> &str {
+        self.name.rsplit_once('.').map_or("", |(_, t)| t)
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.active_state.as_str(),
+            "active" | "reloading" | "activating"
+        )
+    }
+
+    /// Failed units first, then running ones.
+    fn rank(&self) -> u8 {
+        match self.active_state.as_str() {
+            "failed" => 0,
+            _ if self.is_active() => 1,
+            _ => 2,
+        }
+    }
+
+    fn state(&self) -> String {
+        format!("{} ({})", self.active_state, self.sub_state)
+    }
+}
+
+pub struct SystemdProvider {
+    config: SystemdConfig,
+    apps: Arc<AppsProvider>,
+    /// Connected on first use, and again after the connection breaks.
+    conn: Mutex<Option<Connection>>,
+}
+
+impl SystemdProvider {
+    /// Talks to the user's systemd on the session bus.
+    pub fn new(config: SystemdConfig, apps: Arc<AppsProvider>) -> Self {
+        Self {
+            config,
+            apps,
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// Talks to whatever serves `org.freedesktop.systemd1` on `conn`, e.g. a
+    /// mock on a private bus in tests.
+    pub fn with_connection(
+        config: SystemdConfig,
+        apps: Arc<AppsProvider>,
+        conn: Connection,
+    ) -> Self {
+        Self {
+            config,
+            apps,
+            conn: Mutex::new(Some(conn)),
+        }
+    }
+
+    /// Calls the manager, off the engine's runtime.
+    fn manager<T: Send>(
+        &self,
+        call: impl FnOnce(&ManagerProxy) -> zbus::Result<T> + Send,
+    ) -> Result<T> {
+        off_runtime(|| {
+            let mut conn = self
+                .conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("systemd connection lock poisoned"))?;
+            let connection = match conn.as_ref() {
+                Some(c) => c.clone(),
+                None => Connection::session().context("connect to the session bus")?,
+            };
+            let result = ManagerProxy::new(&connection).and_then(|proxy| call(&proxy));
+            *conn = match &result {
+                Err(zbus::Error::InputOutput(_)) => None,
+                _ => Some(connection),
+            };
+            result.context("call systemd")
+        })
+    }
+
+    /// Loaded units of the configured types, sorted by name.
+    pub fn units(&self) -> Result<Vec<Unit>> {
+        let mut units: Vec<Unit> = self
+            .manager(|m| m.list_units())?
+            .into_iter()
+            .map(Unit::from_status)
+            .filter(|u| {
+                self.config.unit_types.is_empty()
+                    || self.config.unit_types.iter().any(|t| t == u.unit_type())
+            })
+            .collect();
+        units.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(units)
+    }
+
+    /// Units whose name or else description matches the query; failed and
+    /// running units first when there is no query.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &[PREFIX]);
+        let Ok(units) = self.units() else {
+            return Vec::new();
+        };
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        // (tier, score): name matches above description matches.
+        let mut hits: Vec<((u8, i64), Vec<usize>, Unit)> = units
+            .into_iter()
+            .filter_map(|u| {
+                if q.is_empty() {
+                    return Some(((0, 0), Vec::new(), u));
+                }
+                if let Some((score, idx)) = matcher.fuzzy_indices(&u.name, q) {
+                    return Some(((1, score), idx, u));
+                }
+                let score = matcher.fuzzy_match(&u.description, q)?;
+                Some(((0, score), Vec::new(), u))
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then(a.2.rank().cmp(&b.2.rank()))
+                .then(a.2.name.cmp(&b.2.name))
+        });
+        hits.truncate(self.config.max_results);
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, idx, u))| to_result_item(&u, 1.0 - rank as f32 / count, idx))
+            .collect()
+    }
+
+    /// The unit behind `key`, as currently loaded.
+    pub fn unit(&self, key: &str) -> Result<Unit> {
+        let name = unit_name(key)?;
+        self.units()?
+            .into_iter()
+            .find(|u| u.name == name)
+            .with_context(|| format!("No unit {name}"))
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let u = self.unit(key).ok()?;
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": u.name,
+                "comment": u.description,
+                "snippet": format!("Active: {}\nLoaded: {}", u.state(), u.load_state),
+                "activeState": u.active_state,
+            }),
+        })
+    }
+
+    pub fn execute_start(&self, key: &str) -> Result<()> {
+        let name = unit_name(key)?;
+        self.manager(|m| m.start_unit(name, JOB_MODE)).map(drop)
+    }
+
+    pub fn execute_stop(&self, key: &str) -> Result<()> {
+        let name = unit_name(key)?;
+        self.manager(|m| m.stop_unit(name, JOB_MODE)).map(drop)
+    }
+
+    pub fn execute_restart(&self, key: &str) -> Result<()> {
+        let name = unit_name(key)?;
+        self.manager(|m| m.restart_unit(name, JOB_MODE)).map(drop)
+    }
+
+    /// Follows the unit's journal in the terminal.
+    pub fn execute_logs(&self, key: &str) -> Result<()> {
+        let name = unit_name(key)?;
+        let argv: Vec<String> = ["journalctl", "--user", "--unit", name, "--follow"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        self.apps.run_in_terminal(PROVIDER_ID, name, &argv)
+    }
+}
+
+impl Provider for SystemdProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_outcome(action, key, &[]).status == "ok"
+    }
+
+    fn execute_outcome(&self, action: &str, key: &str, _args: &[String]) -> Outcome {
+        let result = match action {
+            "start" => self.execute_start(key).map(|()| "Started"),
+            "stop" => self.execute_stop(key).map(|()| "Stopped"),
+            "restart" => self.execute_restart(key).map(|()| "Restarted"),
+            "logs" => self.execute_logs(key).map(|()| "Following its logs"),
+            "copy" => unit_name(key).map(|_| "Copied"),
+            _ => Err(anyhow::anyhow!("Unknown action {action}")),
+        };
+
+        match result {
+            Ok(message) => Outcome {
+                status: "ok".into(),
+                message: Some(message.into()),
+                exit_code: None,
+            },
+            Err(e) => Outcome {
+                status: "error".into(),
+                message: Some(format!("{e:#}")),
+                exit_code: None,
+            },
+        }
+    }
+}
+
+fn to_result_item(u: &Unit, score: f32, indices: Vec<usize>) -> ResultItem {
+    // Running units are restarted by default, the rest started.
+    let actions: &[&str] = if u.is_active() {
+        &["restart", "stop", "logs", "copy"]
+    } else {
+        &["start", "logs", "copy"]
+    };
+
+    ResultItem {
+        key: format!("{KEY_PREFIX}{}", u.name),
+        title: u.name.clone(),
+        provider_id: PROVIDER_ID.into(),
+        score,
+        extras: Some(serde_json::json!({
+            "subtitle": format!("{} · {}", u.state(), u.description),
+            "activeState": u.active_state,
+            "subState": u.sub_state,
+            "loadState": u.load_state,
+            "copyText": u.name,
+            "defaultAction": actions[0],
+            "actions": actions,
+        })),
+        matches: (!indices.is_empty()).then(|| Matches {
+            title: MatchRange::from_char_indices(&u.name, indices),
+            subtitle: None,
+        }),
+    }
+}
+
+fn unit_name(key: &str) -> Result<&str> {
+    key.strip_prefix(KEY_PREFIX)
+        .filter(|n| !n.is_empty())
+        .with_context(|| format!("Not a unit key: {key}"))
+}
@@ -0,0 +1,36 @@
+//! The part of `org.freedesktop.systemd1.Manager` the provider uses.
+
+use zbus::proxy;
+use zbus::zvariant::OwnedObjectPath;
+
+/// One entry of `ListUnits`: name, description, load state, active state,
+/// sub state, followed unit, unit path, job id, job type and job path.
+pub type UnitStatus = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1",
+    gen_async = false,
+    blocking_name = "ManagerProxy"
+)]
+pub trait Manager {
+    fn list_units(&self) -> zbus::Result<Vec<UnitStatus>>;
+
+    fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+}
@@ -0,0 +1,245 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use lancea_model::Provider;
+use lancea_provider_systemd::{SystemdConfig, SystemdProvider, UnitStatus};
+use lancea_testkit::{quiet_terminal, titles};
+use zbus::fdo;
+use zbus::interface;
+use zbus::zvariant::OwnedObjectPath;
+
+/// A `dbus-daemon` of our own, so tests need neither a session bus nor
+/// systemd.
+struct PrivateBus {
+    daemon: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    fn start() -> Self {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("dbus-daemon is needed for these tests");
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+        Self {
+            daemon,
+            address: address.trim().to_string(),
+        }
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+/// (name, description, active state, sub state)
+type MockUnit = (&'static str, &'static str, &'static str, &'static str);
+
+/// Serves the subset of `org.freedesktop.systemd1.Manager` the provider
+/// uses, recording the jobs it is asked for.
+struct MockManager {
+    units: Arc<Mutex<Vec<MockUnit>>>,
+    jobs: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockManager {
+    fn job(
+        &self,
+        verb: &str,
+        name: &str,
+        mode: &str,
+        state: MockUnit,
+    ) -> fdo::Result<OwnedObjectPath> {
+        let mut units = self.units.lock().unwrap();
+        let unit = units
+            .iter_mut()
+            .find(|u| u.0 == name)
+            .ok_or_else(|| fdo::Error::Failed(format!("Unit {name} not loaded.")))?;
+        (unit.2, unit.3) = (state.2, state.3);
+        self.jobs
+            .lock()
+            .unwrap()
+            .push(format!("{verb} {name} {mode}"));
+        Ok(OwnedObjectPath::try_from("/org/freedesktop/systemd1/job/1").unwrap())
+    }
+}
+
+#[interface(name = "org.freedesktop.systemd1.Manager")]
+impl MockManager {
+    fn list_units(&self) -> Vec<UnitStatus> {
+        let root = OwnedObjectPath::try_from("/").unwrap();
+        self.units
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, description, active, sub)| {
+                (
+                    name.to_string(),
+                    description.to_string(),
+                    "loaded".into(),
+                    active.to_string(),
+                    sub.to_string(),
+                    String::new(),
+                    root.clone(),
+                    0,
+                    String::new(),
+                    root.clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn start_unit(&self, name: &str, mode: &str) -> fdo::Result<OwnedObjectPath> {
+        self.job("start", name, mode, ("", "", "active", "running"))
+    }
+
+    fn stop_unit(&self, name: &str, mode: &str) -> fdo::Result<OwnedObjectPath> {
+        self.job("stop", name, mode, ("", "", "inactive", "dead"))
+    }
+
+    fn restart_unit(&self, name: &str, mode: &str) -> fdo::Result<OwnedObjectPath> {
+        self.job("restart", name, mode, ("", "", "active", "running"))
+    }
+}
+
+struct Fixture {
+    provider: SystemdProvider,
+    jobs: Arc<Mutex<Vec<String>>>,
+    rt: tokio::runtime::Runtime,
+    _server: zbus::Connection,
+    _bus: PrivateBus,
+}
+
+fn setup() -> Fixture {
+    let bus = PrivateBus::start();
+    let units = Arc::new(Mutex::new(vec![
+        (
+            "syncthing.service",
+            "Syncthing - Open Source Continuous File Synchronization",
+            "active",
+            "running",
+        ),
+        ("pipewire.service", "Sound server", "active", "running"),
+        ("backup.service", "Nightly backup", "failed", "failed"),
+        ("backup.timer", "Run the nightly backup", "inactive", "dead"),
+        ("home-user-media.mount", "Media", "active", "mounted"),
+    ]));
+    let jobs = Arc::new(Mutex::new(Vec::new()));
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mock = MockManager {
+        units,
+        jobs: jobs.clone(),
+    };
+    let server = rt
+        .block_on(async {
+            zbus::connection::Builder::address(bus.address.as_str())?
+                .name("org.freedesktop.systemd1")?
+                .serve_at("/org/freedesktop/systemd1", mock)?
+                .build()
+                .await
+        })
+        .unwrap();
+
+    let client = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .build()
+        .unwrap();
+    let provider =
+        SystemdProvider::with_connection(SystemdConfig::default(), quiet_terminal(), client);
+
+    Fixture {
+        provider,
+        jobs,
+        rt,
+        _server: server,
+        _bus: bus,
+    }
+}
+
+#[test]
+fn lists_units_failed_and_running_first() {
+    let f = setup();
+
+    // Mounts aren't among the default unit types.
+    assert_eq!(
+        titles(&f.provider, "/unit"),
+        vec![
+            "backup.service",
+            "pipewire.service",
+            "syncthing.service",
+            "backup.timer"
+        ]
+    );
+
+    let items = f.provider.search("/unit");
+    let extras = items[1].extras.as_ref().unwrap();
+    assert_eq!(extras["activeState"], "active");
+    assert_eq!(extras["defaultAction"], "restart");
+    assert_eq!(extras["subtitle"], "active (running) · Sound server");
+    assert_eq!(items[3].extras.as_ref().unwrap()["defaultAction"], "start");
+}
+
+#[test]
+fn searches_names_then_descriptions() {
+    let f = setup();
+
+    assert_eq!(titles(&f.provider, "/unit sync")[0], "syncthing.service");
+    assert_eq!(titles(&f.provider, "/unit sound"), vec!["pipewire.service"]);
+    assert_eq!(
+        titles(&f.provider, "/unit backup"),
+        vec!["backup.service", "backup.timer"]
+    );
+
+    let preview = f.provider.preview("systemd:pipewire.service").unwrap();
+    assert_eq!(preview.data["comment"], "Sound server");
+    assert_eq!(
+        preview.data["snippet"],
+        "Active: active (running)\nLoaded: loaded"
+    );
+}
+
+#[test]
+fn actions_queue_jobs() {
+    let f = setup();
+    let p = &f.provider;
+
+    assert!(p.execute("start", "systemd:backup.service"));
+    assert_eq!(
+        p.preview("systemd:backup.service").unwrap().data["activeState"],
+        "active"
+    );
+    assert!(p.execute("stop", "systemd:syncthing.service"));
+    assert!(p.execute("restart", "systemd:pipewire.service"));
+    assert!(p.execute("logs", "systemd:pipewire.service"));
+    assert!(p.execute("copy", "systemd:pipewire.service"));
+    assert_eq!(
+        *f.jobs.lock().unwrap(),
+        vec![
+            "start backup.service replace",
+            "stop syncthing.service replace",
+            "restart pipewire.service replace",
+        ]
+    );
+
+    let outcome = p.execute_outcome("start", "systemd:missing.service", &[]);
+    assert_eq!(outcome.status, "error");
+    assert!(outcome.message.unwrap().contains("not loaded"));
+}
+
+#[test]
+fn works_from_inside_the_engine_runtime() {
+    let f = setup();
+    let found = f.rt.block_on(async { f.provider.search("/unit pipewire") });
+    assert_eq!(found[0].title, "pipewire.service");
+}
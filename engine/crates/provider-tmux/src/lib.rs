@@ -0,0 +1,330 @@
+//! tmux sessions and their windows: `/tmux name` attaches to one in the
+//! configured terminal, or starts a new session by that name. The preview
+//! lists the windows and what runs in their panes.
+
+mod tmux;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Preview, Provider, ResultItem, command_query, tildify};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use tmux::{Pane, Session, Tmux, Window};
+
+const PROVIDER_ID: &str = "tmux";
+/// `tmux:session:<name>`, `tmux:window:<session>:<index>` or
+/// `tmux:new:<name>`; tmux keeps `:` out of session names.
+const KEY_PREFIX: &str = "tmux:";
+const PREFIX: &str = "/tmux";
+
+/// The `[tmux]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TmuxConfig {
+    /// The server's socket, as for `tmux -S`; the default server when
+    /// unset.
+    pub socket: Option<PathBuf>,
+    pub max_results: usize,
+}
+
+impl Default for TmuxConfig {
+    fn default() -> Self {
+        Self {
+            socket: None,
+            max_results: 50,
+        }
+    }
+}
+
+/// What a key stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Session(String),
+    Window(String, u32),
+    /// A session to create; tmux picks the name when it is empty.
+    New(String),
+}
+
+impl Target {
+    fn parse(key: &str) -> Result<Self> {
+        let rest = key
+            .strip_prefix(KEY_PREFIX)
+            .with_context(|| format!("Not a tmux key: {key}"))?;
+        let (kind, rest) = rest.split_once(':').context("Bad tmux key")?;
+        Ok(match kind {
+            "session" => Self::Session(rest.to_string()),
+            "window" => {
+                let (session, index) = rest.split_once(':').context("Bad window key")?;
+                Self::Window(
+                    session.to_string(),
+                    index.parse().context("Bad window index")?,
+                )
+            }
+            "new" => Self::New(rest.to_string()),
+            _ => bail!("Bad tmux key: {key}"),
+        })
+    }
+
+    fn key(&self) -> String {
+        match self {
+            Self::Session(name) => format!("{KEY_PREFIX}session:{name}"),
+            Self::Window(name, index) => format!("{KEY_PREFIX}window:{name}:{index}"),
+            Self::New(name) => format!("{KEY_PREFIX}new:{name}"),
+        }
+    }
+}
+
+pub struct TmuxProvider {
+    config: TmuxConfig,
+    tmux: Tmux,
+    apps: Arc<AppsProvider>,
+}
+
+impl TmuxProvider {
+    pub fn new(config: TmuxConfig, apps: Arc<AppsProvider>) -> Self {
+        Self {
+            tmux: Tmux::new(config.socket.clone()),
+            config,
+            apps,
+        }
+    }
+
+    /// Sessions, each followed by its windows, matched by name; then an
+    /// item creating a session named after the query, unless one exists.
+    /// The server is asked again every time, so the list is never stale.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &[PREFIX]);
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let sessions = self.tmux.sessions();
+        let windows = self.tmux.windows();
+
+        let mut hits: Vec<(i64, Vec<usize>, Target)> = Vec::new();
+        for session in &sessions {
+            let mut candidates = vec![(&session.name, Target::Session(session.name.clone()))];
+            for window in windows.iter().filter(|w| w.session_id == session.id) {
+                let target = Target::Window(session.name.clone(), window.index);
+                candidates.push((&window.name, target));
+            }
+            for (name, target) in candidates {
+                if q.is_empty() {
+                    hits.push((0, Vec::new(), target));
+                } else if let Some((score, idx)) = matcher.fuzzy_indices(name, q) {
+                    hits.push((score, idx, target));
+                }
+            }
+        }
+        // Stable, so ties keep sessions ahead of their windows.
+        hits.sort_by_key(|(score, ..)| std::cmp::Reverse(*score));
+        hits.truncate(self.config.max_results);
+
+        let name = session_name(q);
+        if !sessions.iter().any(|s| s.name == name) {
+            hits.push((0, Vec::new(), Target::New(name)));
+        }
+
+        let count = hits.len() as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, idx, target))| {
+                let score = 1.0 - rank as f32 / count;
+                self.to_result_item(&target, &sessions, &windows, score, idx)
+            })
+            .collect()
+    }
+
+    fn to_result_item(
+        &self,
+        target: &Target,
+        sessions: &[Session],
+        windows: &[Window],
+        score: f32,
+        indices: Vec<usize>,
+    ) -> ResultItem {
+        let (title, subtitle, action) = match target {
+            Target::Session(name) => {
+                let session = sessions.iter().find(|s| &s.name == name);
+                (
+                    name.clone(),
+                    session.map(session_summary).unwrap_or_default(),
+                    "attach",
+                )
+            }
+            Target::Window(name, index) => {
+                let window = sessions
+                    .iter()
+                    .find(|s| &s.name == name)
+                    .and_then(|s| find_window(windows, &s.id, *index));
+                let panes = window.map_or(0, |w| w.panes);
+                (
+                    window.map(|w| w.name.clone()).unwrap_or_default(),
+                    format!("Window {name}:{index} · {}", plural(panes, "pane")),
+                    "attach",
+                )
+            }
+            Target::New(name) if name.is_empty() => {
+                ("New tmux session".to_string(), String::new(), "new")
+            }
+            Target::New(name) => (
+                format!("New tmux session \u{201c}{name}\u{201d}"),
+                String::new(),
+                "new",
+            ),
+        };
+
+        ResultItem {
+            key: target.key(),
+            provider_id: PROVIDER_ID.into(),
+            score,
+            extras: Some(serde_json::json!({
+                "subtitle": subtitle,
+                "icon": "utilities-terminal",
+                "defaultAction": action,
+                "actions": [action],
+            })),
+            matches: (!indices.is_empty()).then(|| Matches {
+                title: MatchRange::from_char_indices(&title, indices),
+                subtitle: None,
+            }),
+            title,
+        }
+    }
+
+    /// A session's windows with the command and directory of each pane,
+    /// or a window's panes alone.
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let (name, only) = match Target::parse(key).ok()? {
+            Target::Session(name) => (name, None),
+            Target::Window(name, index) => (name, Some(index)),
+            Target::New(_) => return None,
+        };
+        let session = self.tmux.sessions().into_iter().find(|s| s.name == name)?;
+        let windows: Vec<Window> = self
+            .tmux
+            .windows()
+            .into_iter()
+            .filter(|w| w.session_id == session.id && only.is_none_or(|i| w.index == i))
+            .collect();
+        let panes = self.tmux.panes();
+
+        let mut lines = Vec::new();
+        for window in &windows {
+            if only.is_none() {
+                lines.push(format!(
+                    "{}: {}{}",
+                    window.index,
+                    window.name,
+                    if window.active { " *" } else { "" }
+                ));
+            }
+            let indent = if only.is_none() { "  " } else { "" };
+            for pane in panes
+                .iter()
+                .filter(|p| p.session_id == session.id && p.window_index == window.index)
+            {
+                lines.push(format!(
+                    "{indent}{}: {} in {}{}",
+                    pane.index,
+                    pane.command,
+                    tildify(Path::new(&pane.path)),
+                    if pane.active { " *" } else { "" }
+                ));
+            }
+        }
+
+        let (title, comment) = match (only, windows.first()) {
+            (Some(index), Some(window)) => (
+                format!("{name}:{index} {}", window.name),
+                plural(window.panes, "pane"),
+            ),
+            (Some(_), None) => return None,
+            (None, _) => (name, session_summary(&session)),
+        };
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": title,
+                "comment": comment,
+                "snippet": lines.join("\n"),
+            }),
+        })
+    }
+
+    /// The tmux command line an action on `key` runs in the terminal.
+    pub fn command(&self, action: &str, key: &str) -> Result<Vec<String>> {
+        let target = Target::parse(key)?;
+        let exists = |name: &str| self.tmux.sessions().iter().any(|s| s.name == name);
+        Ok(match (action, target) {
+            ("attach", Target::Session(name)) if exists(&name) => {
+                self.tmux
+                    .argv(&["attach-session", "-t", &format!("={name}")])
+            }
+            ("attach", Target::Window(name, index)) if exists(&name) => {
+                let target = format!("={name}:{index}");
+                self.tmux.argv(&["attach-session", "-t", &target])
+            }
+            ("attach", _) => bail!("No such tmux session"),
+            // -A attaches instead should the session have appeared since.
+            ("new", Target::New(name)) if name.is_empty() => self.tmux.argv(&["new-session"]),
+            ("new", Target::New(name)) => self.tmux.argv(&["new-session", "-A", "-s", &name]),
+            _ => bail!("Can't {action} {key}"),
+        })
+    }
+
+    pub fn execute_in_terminal(&self, action: &str, key: &str) -> Result<()> {
+        let argv = self.command(action, key)?;
+        self.apps.run_in_terminal(PROVIDER_ID, "tmux", &argv)
+    }
+}
+
+impl Provider for TmuxProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        self.execute_in_terminal(action, key).is_ok()
+    }
+}
+
+fn find_window<'a>(windows: &'a [Window], session_id: &str, index: u32) -> Option<&'a Window> {
+    windows
+        .iter()
+        .find(|w| w.session_id == session_id && w.index == index)
+}
+
+/// `3 windows · attached`
+fn session_summary(session: &Session) -> String {
+    let windows = plural(session.windows, "window");
+    if session.attached > 0 {
+        format!("{windows} · attached")
+    } else {
+        windows
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// The name tmux would give a session asked to be called `query`: it
+/// turns `:` and `.` into `_`.
+fn session_name(query: &str) -> String {
+    query.replace([':', '.'], "_")
+}
@@ -0,0 +1,135 @@
+//! The tmux server, through `tmux list-* -F`. Fields are joined with `|`
+//! and the free-form one (a name or a path) comes last, so it may contain
+//! the separator.
+
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+
+const SESSION_FORMAT: &str = "#{session_id}|#{session_windows}|#{session_attached}|#{session_name}";
+const WINDOW_FORMAT: &str =
+    "#{session_id}|#{window_index}|#{window_active}|#{window_panes}|#{window_name}";
+const PANE_FORMAT: &str = "#{session_id}|#{window_index}|#{pane_index}|#{pane_active}|\
+                           #{pane_current_command}|#{pane_current_path}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    /// `$0` and up; stable while the session lives, unlike its name.
+    pub id: String,
+    pub name: String,
+    pub windows: usize,
+    /// How many clients are attached.
+    pub attached: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Window {
+    pub session_id: String,
+    pub index: u32,
+    pub name: String,
+    pub active: bool,
+    pub panes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pane {
+    pub session_id: String,
+    pub window_index: u32,
+    pub index: u32,
+    pub active: bool,
+    pub command: String,
+    pub path: String,
+}
+
+pub struct Tmux {
+    /// `-S`; the default server when unset.
+    socket: Option<PathBuf>,
+}
+
+impl Tmux {
+    pub fn new(socket: Option<PathBuf>) -> Self {
+        Self { socket }
+    }
+
+    /// `tmux` and `args`, on the configured server.
+    pub fn argv(&self, args: &[&str]) -> Vec<String> {
+        let mut argv = vec!["tmux".to_string()];
+        if let Some(socket) = &self.socket {
+            argv.extend(["-S".into(), socket.to_string_lossy().into_owned()]);
+        }
+        argv.extend(args.iter().map(|a| a.to_string()));
+        argv
+    }
+
+    fn list(&self, args: &[&str]) -> Result<String> {
+        let argv = self.argv(args);
+        let out = Command::new(&argv[0])
+            .args(&argv[1..])
+            .stdin(Stdio::null())
+            .output()
+            .context("failed to spawn tmux")?;
+        if !out.status.success() {
+            bail!(
+                "tmux {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    /// Every session, in tmux's order. Without a running server, none.
+    pub fn sessions(&self) -> Vec<Session> {
+        self.list(&["list-sessions", "-F", SESSION_FORMAT])
+            .map(|text| text.lines().filter_map(parse_session).collect())
+            .unwrap_or_default()
+    }
+
+    /// Windows of every session, by session and index.
+    pub fn windows(&self) -> Vec<Window> {
+        self.list(&["list-windows", "-a", "-F", WINDOW_FORMAT])
+            .map(|text| text.lines().filter_map(parse_window).collect())
+            .unwrap_or_default()
+    }
+
+    /// Panes of every window of every session.
+    pub fn panes(&self) -> Vec<Pane> {
+        self.list(&["list-panes", "-a", "-F", PANE_FORMAT])
+            .map(|text| text.lines().filter_map(parse_pane).collect())
+            .unwrap_or_default()
+    }
+}
+
+fn parse_session(line: &str) -> Option<Session> {
+    let mut f = line.splitn(4, '|');
+    Some(Session {
+        id: f.next()?.to_string(),
+        windows: f.next()?.parse().ok()?,
+        attached: f.next()?.parse().ok()?,
+        name: f.next()?.to_string(),
+    })
+}
+
+fn parse_window(line: &str) -> Option<Window> {
+    let mut f = line.splitn(5, '|');
+    Some(Window {
+        session_id: f.next()?.to_string(),
+        index: f.next()?.parse().ok()?,
+        active: f.next()? == "1",
+        panes: f.next()?.parse().ok()?,
+        name: f.next()?.to_string(),
+    })
+}
+
+fn parse_pane(line: &str) -> Option<Pane> {
+    let mut f = line.splitn(6, '|');
+    Some(Pane {
+        session_id: f.next()?.to_string(),
+        window_index: f.next()?.parse().ok()?,
+        index: f.next()?.parse().ok()?,
+        active: f.next()? == "1",
+        command: f.next()?.to_string(),
+        path: f.next()?.to_string(),
+    })
+}
@@ -0,0 +1,221 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Once;
+use std::thread;
+use std::time::Duration;
+
+use lancea_model::Provider;
+use lancea_provider_tmux::{TmuxConfig, TmuxProvider};
+use lancea_testkit::recording_terminal;
+use tempfile::TempDir;
+
+/// Plays a tmux server whose state is the files in the `-S` directory,
+/// logging every call there.
+const FAKE_TMUX: &str = r#"#!/bin/sh
+[ "$1" = -S ] || exit 1
+state=$2
+shift 2
+echo "$*" >> "$state/calls"
+case $1 in
+    list-sessions) file=sessions ;;
+    list-windows) file=windows ;;
+    list-panes) file=panes ;;
+    *) exit 0 ;;
+esac
+if [ ! -f "$state/sessions" ]; then
+    echo "no server running on $state" >&2
+    exit 1
+fi
+cat "$state/$file"
+"#;
+
+/// Puts the fake `tmux` first on `PATH`, once for all tests.
+fn fake_tmux_on_path() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let bin = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fake-tmux");
+        fs::create_dir_all(&bin).unwrap();
+        let tmux = bin.join("tmux");
+        fs::write(&tmux, FAKE_TMUX).unwrap();
+        fs::set_permissions(&tmux, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let paths = std::iter::once(bin).chain(std::env::split_paths(&path));
+        // SAFETY: every test calls this before spawning anything, and
+        // `Once` makes the others wait until it is done.
+        unsafe { std::env::set_var("PATH", std::env::join_paths(paths).unwrap()) };
+    });
+}
+
+/// A server with two sessions: `work`, attached, with an editor window
+/// split in two and a shell, and `notes` with one window.
+fn server(state: &Path) {
+    fs::write(state.join("sessions"), "$0|2|1|work\n$1|1|0|notes\n").unwrap();
+    fs::write(
+        state.join("windows"),
+        "$0|1|1|2|editor\n$0|2|0|1|shell\n$1|1|1|1|vim\n",
+    )
+    .unwrap();
+    fs::write(
+        state.join("panes"),
+        "$0|1|0|1|nvim|/src/lancea\n\
+         $0|1|1|0|cargo|/src/lancea/engine\n\
+         $0|2|0|1|zsh|/tmp\n\
+         $1|1|0|1|vim|/notes\n",
+    )
+    .unwrap();
+}
+
+fn setup(with_server: bool) -> (TempDir, TmuxProvider) {
+    fake_tmux_on_path();
+    let dir = tempfile::tempdir().unwrap();
+    if with_server {
+        server(dir.path());
+    }
+    let config = TmuxConfig {
+        socket: Some(dir.path().to_path_buf()),
+        ..TmuxConfig::default()
+    };
+    let apps = recording_terminal(&dir.path().join("terminal"));
+    (dir, TmuxProvider::new(config, apps))
+}
+
+fn socket(dir: &TempDir) -> String {
+    dir.path().display().to_string()
+}
+
+#[test]
+fn lists_sessions_with_their_windows() {
+    let (dir, p) = setup(true);
+
+    let results = p.search("/tmux");
+    let keys: Vec<&str> = results.iter().map(|r| r.key.as_str()).collect();
+    assert_eq!(
+        keys,
+        [
+            "tmux:session:work",
+            "tmux:window:work:1",
+            "tmux:window:work:2",
+            "tmux:session:notes",
+            "tmux:window:notes:1",
+            "tmux:new:",
+        ]
+    );
+    // The engine routes previews and actions by what precedes the first `:`.
+    assert!(
+        results
+            .iter()
+            .all(|r| r.key.split(':').next() == Some("tmux"))
+    );
+    let extras = results[0].extras.as_ref().unwrap();
+    assert_eq!(extras["subtitle"], "2 windows · attached");
+    assert_eq!(extras["defaultAction"], "attach");
+    assert_eq!(results[1].title, "editor");
+    assert_eq!(
+        results[1].extras.as_ref().unwrap()["subtitle"],
+        "Window work:1 · 2 panes"
+    );
+    assert!(results.windows(2).all(|w| w[0].score > w[1].score));
+
+    let calls = fs::read_to_string(dir.path().join("calls")).unwrap();
+    assert!(calls.starts_with(
+        "list-sessions -F #{session_id}|#{session_windows}|#{session_attached}|#{session_name}\n\
+         list-windows -a -F "
+    ));
+
+    let results = p.search("/TMUX edi");
+    assert_eq!(results[0].key, "tmux:window:work:1");
+    assert_eq!(results.last().unwrap().key, "tmux:new:edi");
+    // An existing session isn't offered again as a new one.
+    assert!(
+        p.search("/tmux notes")
+            .iter()
+            .all(|r| !r.key.starts_with("tmux:new:"))
+    );
+    assert_eq!(
+        p.search("/tmux a.b:c").last().unwrap().key,
+        "tmux:new:a_b_c"
+    );
+}
+
+#[test]
+fn preview_shows_windows_and_panes() {
+    let (_dir, p) = setup(true);
+
+    let preview = p.preview("tmux:session:work").unwrap();
+    assert_eq!(preview.data["title"], "work");
+    assert_eq!(preview.data["comment"], "2 windows · attached");
+    assert_eq!(
+        preview.data["snippet"],
+        "1: editor *\n\
+         \x20 0: nvim in /src/lancea *\n\
+         \x20 1: cargo in /src/lancea/engine\n\
+         2: shell\n\
+         \x20 0: zsh in /tmp *"
+    );
+
+    let preview = p.preview("tmux:window:work:2").unwrap();
+    assert_eq!(preview.data["title"], "work:2 shell");
+    assert_eq!(preview.data["snippet"], "0: zsh in /tmp *");
+
+    assert!(p.preview("tmux:window:work:9").is_none());
+    assert!(p.preview("tmux:session:gone").is_none());
+    assert!(p.preview("tmux:new:work").is_none());
+}
+
+/// Waits for the fake terminal to record what it was asked to run.
+fn terminal_argv(dir: &TempDir) -> String {
+    let file = dir.path().join("terminal");
+    for _ in 0..100 {
+        if let Ok(text) = fs::read_to_string(&file)
+            && !text.is_empty()
+        {
+            fs::remove_file(&file).unwrap();
+            return text;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("the terminal never ran");
+}
+
+#[test]
+fn attaches_or_creates_in_the_terminal() {
+    let (dir, p) = setup(true);
+    let tmux = format!("tmux\n-S\n{}\n", socket(&dir));
+
+    assert!(p.execute("attach", "tmux:session:work"));
+    assert_eq!(
+        terminal_argv(&dir),
+        format!("{tmux}attach-session\n-t\n=work\n")
+    );
+    assert!(p.execute("attach", "tmux:window:work:2"));
+    assert_eq!(
+        terminal_argv(&dir),
+        format!("{tmux}attach-session\n-t\n=work:2\n")
+    );
+    assert!(p.execute("new", "tmux:new:scratch"));
+    assert_eq!(
+        terminal_argv(&dir),
+        format!("{tmux}new-session\n-A\n-s\nscratch\n")
+    );
+    assert!(p.execute("new", "tmux:new:"));
+    assert_eq!(terminal_argv(&dir), format!("{tmux}new-session\n"));
+
+    assert!(!p.execute("attach", "tmux:session:gone"));
+    assert!(!p.execute("attach", "tmux:new:work"));
+    assert!(!p.execute("kill", "tmux:session:work"));
+    assert!(!p.execute("attach", "ssh:session:work"));
+}
+
+#[test]
+fn without_a_server_only_offers_a_new_session() {
+    let (_dir, p) = setup(false);
+
+    let results = p.search("/tmux");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].key, "tmux:new:");
+    assert_eq!(results[0].title, "New tmux session");
+    assert_eq!(results[0].extras.as_ref().unwrap()["defaultAction"], "new");
+    assert_eq!(p.search("/tmux work")[0].title, "New tmux session “work”");
+}
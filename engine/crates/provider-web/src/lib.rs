@@ -0,0 +1,257 @@
+//! Web search quicklinks: `/g rust traits` opens the engine's URL template
+//! with the query filled in. Outside slash commands, the same engines back
+//! a "Search the web for …" item when nothing else matches well.
+
+mod opener;
+
+use anyhow::{Context, Result, bail};
+use lancea_model::{Preview, Provider, ResultItem};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use serde::Deserialize;
+
+pub use opener::{Opener, XdgOpen};
+
+const PROVIDER_ID: &str = "web";
+const KEY_PREFIX: &str = "web:";
+
+/// One search engine in `[[web.engines]]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SearchEngine {
+    pub name: String,
+    /// Typed as `/<keyword> <query>`; a leading slash is optional here.
+    pub keyword: String,
+    /// `{query}` is replaced by the percent-encoded query.
+    pub url: String,
+    /// Icon name or path, passed through to the UI.
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+impl SearchEngine {
+    fn new(name: &str, keyword: &str, url: &str, icon: &str) -> Self {
+        Self {
+            name: name.into(),
+            keyword: keyword.into(),
+            url: url.into(),
+            icon: Some(icon.into()),
+        }
+    }
+
+    /// The keyword as typed, with its slash.
+    pub fn command(&self) -> String {
+        format!("/{}", self.bare_keyword())
+    }
+
+    fn bare_keyword(&self) -> &str {
+        self.keyword.trim_start_matches('/')
+    }
+
+    pub fn url_for(&self, query: &str) -> String {
+        let encoded = utf8_percent_encode(query, NON_ALPHANUMERIC).to_string();
+        self.url.replace("{query}", &encoded)
+    }
+}
+
+/// The `[web]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WebConfig {
+    /// Replaces the built-in engines when set.
+    pub engines: Vec<SearchEngine>,
+    /// Keyword of the engine behind the global fallback; the first engine
+    /// when unset. An empty string turns the fallback off.
+    pub fallback: Option<String>,
+    /// The fallback shows when no other result scores at least this much.
+    pub fallback_below: f32,
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        Self {
+            engines: vec![
+                SearchEngine::new(
+                    "Google",
+                    "g",
+                    "https://www.google.com/search?q={query}",
+                    "web-browser",
+                ),
+                SearchEngine::new(
+                    "DuckDuckGo",
+                    "ddg",
+                    "https://duckduckgo.com/?q={query}",
+                    "web-browser",
+                ),
+                SearchEngine::new(
+                    "GitHub",
+                    "gh",
+                    "https://github.com/search?q={query}",
+                    "github",
+                ),
+                SearchEngine::new(
+                    "Wikipedia",
+                    "wiki",
+                    "https://en.wikipedia.org/w/index.php?search={query}",
+                    "wikipedia",
+                ),
+            ],
+            fallback: None,
+            // Above fuzzy and substring hits, below word-prefix ones.
+            fallback_below: 0.7,
+        }
+    }
+}
+
+pub struct WebProvider {
+    engines: Vec<SearchEngine>,
+    fallback: Option<usize>,
+    fallback_below: f32,
+    opener: Box<dyn Opener>,
+}
+
+impl WebProvider {
+    pub fn new(config: WebConfig) -> Self {
+        Self::with_opener(config, Box::new(XdgOpen))
+    }
+
+    /// Opens URLs through `opener`, e.g. a stub in tests.
+    pub fn with_opener(config: WebConfig, opener: Box<dyn Opener>) -> Self {
+        let fallback = match config.fallback.as_deref() {
+            None => (!config.engines.is_empty()).then_some(0),
+            Some(keyword) => config
+                .engines
+                .iter()
+                .position(|e| e.bare_keyword() == keyword.trim_start_matches('/')),
+        };
+
+        Self {
+            engines: config.engines,
+            fallback,
+            fallback_below: config.fallback_below,
+            opener,
+        }
+    }
+
+    /// Every engine's `/keyword`, for the command registry.
+    pub fn commands(&self) -> Vec<String> {
+        self.engines.iter().map(SearchEngine::command).collect()
+    }
+
+    /// The engine selected by the text's `/keyword` and the query after it.
+    fn parse<'a>(&self, text: &'a str) -> Option<(usize, &'a str)> {
+        let text = text.trim_start();
+        let (head, query) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let i = self
+            .engines
+            .iter()
+            .position(|e| e.command().eq_ignore_ascii_case(head))?;
+        Some((i, query.trim()))
+    }
+
+    /// One item opening the keyword's engine for the query; nothing until
+    /// there is a query to search for.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let Some((i, query)) = self.parse(raw_query) else {
+            return Vec::new();
+        };
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let engine = &self.engines[i];
+        vec![self.to_result_item(
+            i,
+            query,
+            format!("Search {} for “{query}”", engine.name),
+            1.0,
+        )]
+    }
+
+    /// "Search the web for …" with the fallback engine, unless `best`, the
+    /// top score among the other results, is good enough.
+    pub fn fallback(&self, text: &str, best: Option<f32>) -> Option<ResultItem> {
+        let i = self.fallback?;
+        let query = text.trim();
+        if query.is_empty()
+            || query.starts_with('/')
+            || best.is_some_and(|s| s >= self.fallback_below)
+        {
+            return None;
+        }
+        Some(self.to_result_item(i, query, format!("Search the web for “{query}”"), 0.0))
+    }
+
+    fn to_result_item(&self, i: usize, query: &str, title: String, score: f32) -> ResultItem {
+        let engine = &self.engines[i];
+        let url = engine.url_for(query);
+
+        ResultItem {
+            key: format!("{KEY_PREFIX}{}:{query}", engine.bare_keyword()),
+            title,
+            provider_id: PROVIDER_ID.into(),
+            score,
+            extras: Some(serde_json::json!({
+                "subtitle": url,
+                "engine": engine.name,
+                "icon": engine.icon,
+                "copyText": url,
+                "defaultAction": "open",
+                "actions": ["open", "copy"],
+            })),
+            matches: None,
+        }
+    }
+
+    /// The engine and URL behind `key`.
+    pub fn resolve(&self, key: &str) -> Result<(&SearchEngine, String)> {
+        let rest = key
+            .strip_prefix(KEY_PREFIX)
+            .with_context(|| format!("Not a web key: {key}"))?;
+        let Some((keyword, query)) = rest.split_once(':') else {
+            bail!("No query in {key}");
+        };
+        let engine = self
+            .engines
+            .iter()
+            .find(|e| e.bare_keyword() == keyword)
+            .with_context(|| format!("No search engine {keyword}"))?;
+        Ok((engine, engine.url_for(query)))
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let (engine, url) = self.resolve(key).ok()?;
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": engine.name,
+                "comment": engine.command(),
+                "snippet": url,
+            }),
+        })
+    }
+
+    pub fn execute_open(&self, key: &str) -> Result<()> {
+        let (_, url) = self.resolve(key)?;
+        self.opener.open(&url)
+    }
+}
+
+impl Provider for WebProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "open" => self.execute_open(key).is_ok(),
+            "copy" => self.resolve(key).is_ok(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Handing URLs to the desktop.
+
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+pub trait Opener: Send + Sync {
+    fn open(&self, url: &str) -> Result<()>;
+}
+
+/// `xdg-open`, detached from the engine.
+#[derive(Debug, Default)]
+pub struct XdgOpen;
+
+impl Opener for XdgOpen {
+    fn open(&self, url: &str) -> Result<()> {
+        let mut child = Command::new("xdg-open")
+            .arg(url)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to run xdg-open")?;
+        // Reap it so it doesn't linger as a zombie.
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    }
+}
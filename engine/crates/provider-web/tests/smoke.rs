@@ -0,0 +1,118 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use lancea_model::Provider;
+use lancea_provider_web::{Opener, SearchEngine, WebConfig, WebProvider};
+
+/// Records URLs instead of opening them.
+#[derive(Clone, Default)]
+struct StubOpener {
+    opened: Arc<Mutex<Vec<String>>>,
+}
+
+impl Opener for StubOpener {
+    fn open(&self, url: &str) -> Result<()> {
+        self.opened.lock().unwrap().push(url.to_string());
+        Ok(())
+    }
+}
+
+fn setup(config: WebConfig) -> (WebProvider, StubOpener) {
+    let opener = StubOpener::default();
+    (
+        WebProvider::with_opener(config, Box::new(opener.clone())),
+        opener,
+    )
+}
+
+#[test]
+fn keywords_pick_the_engine() {
+    let (p, _) = setup(WebConfig::default());
+    assert_eq!(p.commands(), vec!["/g", "/ddg", "/gh", "/wiki"]);
+
+    let results = p.search("/gh  lancea launcher ");
+    assert_eq!(results.len(), 1);
+    let item = &results[0];
+    assert_eq!(item.title, "Search GitHub for “lancea launcher”");
+    assert_eq!(item.key, "web:gh:lancea launcher");
+    let extras = item.extras.as_ref().unwrap();
+    assert_eq!(
+        extras["subtitle"],
+        "https://github.com/search?q=lancea%20launcher"
+    );
+    assert_eq!(extras["icon"], "github");
+    assert_eq!(extras["defaultAction"], "open");
+
+    assert_eq!(p.search("/DDG c++ & rust")[0].key, "web:ddg:c++ & rust");
+    assert!(p.search("/g").is_empty());
+    assert!(p.search("/go home").is_empty());
+    assert!(p.search("rust").is_empty());
+}
+
+#[test]
+fn opens_and_previews_the_templated_url() {
+    let (p, opener) = setup(WebConfig::default());
+
+    assert!(p.execute("open", "web:ddg:c++ & rust"));
+    assert!(p.execute("open", "web:wiki:Zürich"));
+    assert!(p.execute("copy", "web:g:x"));
+    assert!(!p.execute("open", "web:yt:cats"));
+    assert!(!p.execute("open", "web:g"));
+    assert!(!p.execute("open", "apps:g:x"));
+    assert_eq!(
+        *opener.opened.lock().unwrap(),
+        vec![
+            "https://duckduckgo.com/?q=c%2B%2B%20%26%20rust",
+            "https://en.wikipedia.org/w/index.php?search=Z%C3%BCrich",
+        ]
+    );
+
+    let preview = p.preview("web:gh:lancea").unwrap();
+    assert_eq!(preview.data["title"], "GitHub");
+    assert_eq!(preview.data["comment"], "/gh");
+    assert_eq!(
+        preview.data["snippet"],
+        "https://github.com/search?q=lancea"
+    );
+}
+
+#[test]
+fn falls_back_when_nothing_matches_well() {
+    let config = WebConfig {
+        engines: vec![
+            SearchEngine {
+                name: "Kagi".into(),
+                keyword: "/k".into(),
+                url: "https://kagi.com/search?q={query}".into(),
+                icon: None,
+            },
+            SearchEngine {
+                name: "Searx".into(),
+                keyword: "sx".into(),
+                url: "https://searx.example/?q={query}".into(),
+                icon: None,
+            },
+        ],
+        fallback: Some("sx".into()),
+        ..WebConfig::default()
+    };
+    let (p, _) = setup(config);
+    assert_eq!(p.commands(), vec!["/k", "/sx"]);
+    assert_eq!(p.search("/k rust")[0].title, "Search Kagi for “rust”");
+
+    let item = p.fallback(" what is a monad ", None).unwrap();
+    assert_eq!(item.title, "Search the web for “what is a monad”");
+    assert_eq!(item.key, "web:sx:what is a monad");
+    assert_eq!(item.score, 0.0);
+
+    assert!(p.fallback("firefox", Some(0.5)).is_some());
+    assert!(p.fallback("firefox", Some(0.9)).is_none());
+    assert!(p.fallback("", None).is_none());
+    assert!(p.fallback("/unknown thing", None).is_none());
+
+    let off = WebConfig {
+        fallback: Some(String::new()),
+        ..WebConfig::default()
+    };
+    assert!(setup(off).0.fallback("anything", None).is_none());
+}
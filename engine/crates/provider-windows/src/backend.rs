@@ -0,0 +1,61 @@
+//! What the provider needs from a compositor, and picking the one the
+//! session runs under.
+
+use std::env;
+
+use anyhow::{Result, bail};
+use serde::Deserialize;
+
+use crate::hyprland::Hyprland;
+use crate::kwin::KWin;
+use crate::sway::Sway;
+
+/// An open window as a compositor reports it.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Window {
+    /// The compositor's handle for it, passed back to [`Compositor::focus`].
+    pub id: String,
+    /// Wayland app id, or X11 class for XWayland windows.
+    pub app_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub workspace: Option<String>,
+    #[serde(default)]
+    pub focused: bool,
+}
+
+pub trait Compositor: Send + Sync {
+    /// Shown in previews and errors.
+    fn name(&self) -> &str;
+
+    fn windows(&self) -> Result<Vec<Window>>;
+
+    fn focus(&self, id: &str) -> Result<()>;
+}
+
+/// The compositor called `name` in the config: `sway`, `hyprland` or
+/// `kwin`.
+pub fn by_name(name: &str) -> Result<Box<dyn Compositor>> {
+    Ok(match name {
+        "sway" => Box::new(Sway::from_env()?),
+        "hyprland" => Box::new(Hyprland::from_env()?),
+        "kwin" => Box::new(KWin),
+        _ => bail!("Unknown compositor {name}"),
+    })
+}
+
+/// The compositor this session runs under, going by the variables each
+/// sets for its clients.
+pub fn detect() -> Option<Box<dyn Compositor>> {
+    if env::var_os("SWAYSOCK").is_some() {
+        return by_name("sway").ok();
+    }
+    if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        return by_name("hyprland").ok();
+    }
+    let desktop = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    if desktop.split(':').any(|d| d.eq_ignore_ascii_case("KDE")) {
+        return by_name("kwin").ok();
+    }
+    None
+}
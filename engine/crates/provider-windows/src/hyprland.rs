@@ -0,0 +1,110 @@
+//! Hyprland over its request socket.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::backend::{Compositor, Window};
+
+pub struct Hyprland {
+    socket: PathBuf,
+}
+
+/// One entry of `j/clients`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Client {
+    address: String,
+    class: String,
+    title: String,
+    workspace: ClientWorkspace,
+    #[serde(default = "mapped")]
+    mapped: bool,
+    /// 0 for the focused window.
+    #[serde(rename = "focusHistoryID", default = "unfocused")]
+    focus_history_id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientWorkspace {
+    name: String,
+}
+
+fn mapped() -> bool {
+    true
+}
+
+fn unfocused() -> i64 {
+    -1
+}
+
+impl Hyprland {
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        Self {
+            socket: socket.into(),
+        }
+    }
+
+    /// The instance's socket: under `$XDG_RUNTIME_DIR/hypr` since 0.40,
+    /// under `/tmp/hypr` before.
+    pub fn from_env() -> Result<Self> {
+        let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+            .context("HYPRLAND_INSTANCE_SIGNATURE is not set")?;
+        let runtime = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(|d| PathBuf::from(d).join("hypr"))
+            .filter(|d| d.join(&signature).exists())
+            .unwrap_or_else(|| PathBuf::from("/tmp/hypr"));
+        Ok(Self::new(runtime.join(signature).join(".socket.sock")))
+    }
+
+    /// Sends one request and reads the reply until Hyprland closes the
+    /// connection.
+    fn request(&self, request: &str) -> Result<String> {
+        let mut stream = UnixStream::connect(&self.socket)
+            .with_context(|| format!("connect to {}", self.socket.display()))?;
+        stream.write_all(request.as_bytes())?;
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply)?;
+        Ok(reply)
+    }
+}
+
+impl Compositor for Hyprland {
+    fn name(&self) -> &str {
+        "Hyprland"
+    }
+
+    fn windows(&self) -> Result<Vec<Window>> {
+        let clients: Vec<Client> =
+            serde_json::from_str(&self.request("j/clients")?).context("parse Hyprland clients")?;
+        Ok(clients
+            .into_iter()
+            .filter(|c| c.mapped)
+            .map(|c| Window {
+                id: c.address,
+                app_id: c.class,
+                title: c.title,
+                workspace: Some(c.workspace.name),
+                focused: c.focus_history_id == 0,
+            })
+            .collect())
+    }
+
+    fn focus(&self, id: &str) -> Result<()> {
+        // Only ever an address, so nothing else ends up in the dispatch.
+        if !id
+            .strip_prefix("0x")
+            .is_some_and(|hex| !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        {
+            bail!("Bad Hyprland window {id}");
+        }
+        let reply = self.request(&format!("dispatch focuswindow address:{id}"))?;
+        match reply.trim() {
+            "ok" => Ok(()),
+            err => bail!("Hyprland: {err}"),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! KWin through its D-Bus scripting interface. A script can't return
+//! anything, so the listing script calls back into a reply object the
+//! engine serves for the length of the request.
+
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use lancea_model::off_runtime;
+use tokio::sync::oneshot;
+use zbus::{Connection, interface};
+
+use crate::backend::{Compositor, Window};
+
+const REPLY_PATH: &str = "/org/lancea/KWinReply";
+const REPLY_INTERFACE: &str = "org.lancea.KWinReply";
+
+/// How long the listing script gets to call back.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Plasma 6 names these `windowList` and `activeWindow`, Plasma 5
+/// `clientList` and `activeClient`.
+const PRELUDE: &str = "
+const list = workspace.windowList ? workspace.windowList() : workspace.clientList();
+const active = workspace.activeWindow !== undefined ? workspace.activeWindow : workspace.activeClient;
+";
+
+const LIST_SCRIPT: &str = "
+const windows = list
+    .filter(w => w.normalWindow && !w.skipTaskbar)
+    .map(w => ({
+        id: w.internalId.toString(),
+        app_id: w.resourceClass,
+        title: w.caption,
+        workspace: w.desktops ? w.desktops.map(d => d.name).join(', ') : String(w.desktop),
+        focused: w === active,
+    }));
+callDBus(REPLY_NAME, REPLY_PATH, REPLY_INTERFACE, 'Report', JSON.stringify(windows));
+";
+
+const FOCUS_SCRIPT: &str = "
+for (const w of list) {
+    if (w.internalId.toString() === WINDOW_ID) {
+        if (workspace.activeWindow !== undefined) workspace.activeWindow = w;
+        else workspace.activeClient = w;
+    }
+}
+";
+
+static SCRIPTS: AtomicU64 = AtomicU64::new(0);
+
+pub struct KWin;
+
+struct Reply(Mutex<Option<oneshot::Sender<String>>>);
+
+#[interface(name = "org.lancea.KWinReply")]
+impl Reply {
+    fn report(&self, json: String) {
+        if let Some(tx) = self.0.lock().ok().and_then(|mut tx| tx.take()) {
+            let _ = tx.send(json);
+        }
+    }
+}
+
+impl KWin {
+    /// Runs `script` in KWin with `REPLY_*` and `WINDOW_ID` defined, and
+    /// returns what it reports when `wait` is set.
+    ///
+    /// Runs on a thread and runtime of its own: the engine calls providers
+    /// from its async runtime, where this can't block.
+    fn run(&self, script: &str, window_id: &str, wait: bool) -> Result<Option<String>> {
+        off_runtime(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?
+                .block_on(self.run_async(script, window_id, wait))
+        })
+    }
+
+    async fn run_async(&self, script: &str, window_id: &str, wait: bool) -> Result<Option<String>> {
+        let (tx, rx) = oneshot::channel();
+        let conn = zbus::connection::Builder::session()?
+            .serve_at(REPLY_PATH, Reply(Mutex::new(Some(tx))))?
+            .build()
+            .await
+            .context("connect to the session bus")?;
+        let name = conn.unique_name().context("no bus name")?.to_string();
+
+        let n = SCRIPTS.fetch_add(1, Ordering::Relaxed);
+        let plugin = format!("lancea-{}-{n}", std::process::id());
+        let defines = [
+            ("REPLY_NAME", name.as_str()),
+            ("REPLY_PATH", REPLY_PATH),
+            ("REPLY_INTERFACE", REPLY_INTERFACE),
+            ("WINDOW_ID", window_id),
+        ];
+        let mut source = String::new();
+        for (constant, value) in defines {
+            // JSON strings are valid JavaScript string literals.
+            source += &format!("const {constant} = {};\n", serde_json::to_string(value)?);
+        }
+        source += PRELUDE;
+        source += script;
+        // Created afresh, readable only by us: in a shared temp directory a
+        // predictable name could be swapped for someone else's script.
+        let mut file = tempfile::Builder::new()
+            .prefix(&format!("{plugin}-"))
+            .suffix(".js")
+            .tempfile()
+            .context("create the KWin script")?;
+        file.write_all(source.as_bytes())
+            .and_then(|()| file.flush())
+            .with_context(|| format!("write {}", file.path().display()))?;
+
+        let result = async {
+            load_and_run(&conn, &file.path().to_string_lossy(), &plugin).await?;
+            if !wait {
+                return Ok(None);
+            }
+            let json = tokio::time::timeout(REPLY_TIMEOUT, rx)
+                .await
+                .context("KWin script didn't report back")?
+                .context("KWin script didn't report back")?;
+            Ok(Some(json))
+        }
+        .await;
+
+        let _ = scripting(&conn, "/Scripting", "unloadScript", &(plugin.as_str(),)).await;
+        result
+    }
+}
+
+/// Loads the script file as `plugin` and starts it. Plasma 6 puts the
+/// loaded script under `/Scripting/Script<id>`, Plasma 5 under `/<id>`.
+async fn load_and_run(conn: &Connection, path: &str, plugin: &str) -> Result<()> {
+    let id: i32 = scripting(conn, "/Scripting", "loadScript", &(path, plugin))
+        .await?
+        .body()
+        .deserialize()?;
+    let script = "org.kde.kwin.Script";
+    let plasma6 = conn
+        .call_method(
+            Some("org.kde.KWin"),
+            format!("/Scripting/Script{id}").as_str(),
+            Some(script),
+            "run",
+            &(),
+        )
+        .await;
+    if plasma6.is_err() {
+        conn.call_method(
+            Some("org.kde.KWin"),
+            format!("/{id}").as_str(),
+            Some(script),
+            "run",
+            &(),
+        )
+        .await
+        .context("run KWin script")?;
+    }
+    Ok(())
+}
+
+async fn scripting<B>(
+    conn: &Connection,
+    path: &str,
+    method: &str,
+    body: &B,
+) -> Result<zbus::Message>
+where
+    B: serde::Serialize + zbus::zvariant::DynamicType,
+{
+    conn.call_method(
+        Some("org.kde.KWin"),
+        path,
+        Some("org.kde.kwin.Scripting"),
+        method,
+        body,
+    )
+    .await
+    .with_context(|| format!("KWin {method}"))
+}
+
+impl Compositor for KWin {
+    fn name(&self) -> &str {
+        "KWin"
+    }
+
+    fn windows(&self) -> Result<Vec<Window>> {
+        let json = self.run(LIST_SCRIPT, "", true)?.unwrap_or_default();
+        serde_json::from_str(&json).context("parse KWin windows")
+    }
+
+    fn focus(&self, id: &str) -> Result<()> {
+        self.run(FOCUS_SCRIPT, id, false).map(drop)
+    }
+}
@@ -0,0 +1,243 @@
+//! Open windows: `/win term` lists them by title and app, with the
+//! workspace they are on, and focuses the chosen one. Each compositor is a
+//! [`Compositor`] backend; sway, Hyprland and KWin are built in.
+
+mod backend;
+mod hyprland;
+mod kwin;
+mod sway;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use lancea_model::{MatchRange, Matches, Preview, Provider, ResultItem, command_query};
+use lancea_provider_apps::AppsProvider;
+use serde::Deserialize;
+
+pub use backend::{Compositor, Window, by_name, detect};
+pub use hyprland::Hyprland;
+pub use kwin::KWin;
+pub use sway::Sway;
+
+const PROVIDER_ID: &str = "windows";
+const KEY_PREFIX: &str = "windows:";
+const PREFIX: &str = "/win";
+
+/// The `[windows]` table of the engine config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WindowsConfig {
+    /// `sway`, `hyprland` or `kwin`; detected from the session when unset.
+    pub backend: Option<String>,
+    /// How long a window list is reused while typing.
+    pub cache_ms: u64,
+    pub max_results: usize,
+}
+
+impl Default for WindowsConfig {
+    fn default() -> Self {
+        Self {
+            backend: None,
+            cache_ms: 500,
+            max_results: 50,
+        }
+    }
+}
+
+pub struct WindowsProvider {
+    config: WindowsConfig,
+    backend: Option<Box<dyn Compositor>>,
+    apps: Arc<AppsProvider>,
+    listed: Mutex<Option<(Instant, Vec<Window>)>>,
+}
+
+impl WindowsProvider {
+    /// Uses the configured compositor, else the detected one; without
+    /// either, lists nothing.
+    pub fn new(config: WindowsConfig, apps: Arc<AppsProvider>) -> Self {
+        let backend = match config.backend.as_deref() {
+            Some(name) => by_name(name).ok(),
+            None => detect(),
+        };
+        Self {
+            config,
+            backend,
+            apps,
+            listed: Mutex::new(None),
+        }
+    }
+
+    /// Uses `backend`, e.g. a fake in tests.
+    pub fn with_backend(
+        config: WindowsConfig,
+        apps: Arc<AppsProvider>,
+        backend: Box<dyn Compositor>,
+    ) -> Self {
+        Self {
+            config,
+            backend: Some(backend),
+            apps,
+            listed: Mutex::new(None),
+        }
+    }
+
+    fn backend(&self) -> Result<&dyn Compositor> {
+        self.backend
+            .as_deref()
+            .context("No supported compositor found")
+    }
+
+    /// The open windows, listed again once the last list is `cache_ms` old.
+    pub fn windows(&self) -> Result<Vec<Window>> {
+        let max_age = Duration::from_millis(self.config.cache_ms);
+        if let Ok(listed) = self.listed.lock()
+            && let Some((at, windows)) = listed.as_ref()
+            && at.elapsed() < max_age
+        {
+            return Ok(windows.clone());
+        }
+
+        let windows = self.backend()?.windows()?;
+        if let Ok(mut listed) = self.listed.lock() {
+            *listed = Some((Instant::now(), windows.clone()));
+        }
+        Ok(windows)
+    }
+
+    /// Windows whose title, app id or app name matches the query. Without
+    /// a query, in the compositor's order with the focused window last,
+    /// since switching to it does nothing.
+    pub fn search(&self, raw_query: &str) -> Vec<ResultItem> {
+        let q = command_query(raw_query, &[PREFIX]);
+        let Ok(windows) = self.windows() else {
+            return Vec::new();
+        };
+        let matcher = SkimMatcherV2::default().ignore_case();
+
+        let mut hits: Vec<(i64, Vec<usize>, Window)> = windows
+            .into_iter()
+            .filter_map(|w| {
+                if q.is_empty() {
+                    return Some((if w.focused { -1 } else { 0 }, Vec::new(), w));
+                }
+                let by_title = matcher.fuzzy_indices(&w.title, q);
+                let app_name = self.apps.find_by_window_class(&w.app_id).map(|a| &a.name);
+                let by_app = [Some(&w.app_id), app_name]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|s| matcher.fuzzy_match(s, q))
+                    .max();
+                match (by_title, by_app) {
+                    (Some((t, idx)), Some(a)) if t >= a => Some((t, idx, w)),
+                    (_, Some(a)) => Some((a, Vec::new(), w)),
+                    (Some((t, idx)), None) => Some((t, idx, w)),
+                    (None, None) => None,
+                }
+            })
+            .collect();
+        // Stable, so ties keep the compositor's order.
+        hits.sort_by_key(|(score, ..)| std::cmp::Reverse(*score));
+        hits.truncate(self.config.max_results);
+
+        let count = hits.len().max(1) as f32;
+        hits.into_iter()
+            .enumerate()
+            .map(|(rank, (_, idx, w))| self.to_result_item(&w, 1.0 - rank as f32 / count, idx))
+            .collect()
+    }
+
+    fn to_result_item(&self, w: &Window, score: f32, indices: Vec<usize>) -> ResultItem {
+        let app = self.apps.find_by_window_class(&w.app_id);
+        let subtitle = match &w.workspace {
+            Some(ws) => format!("{} · workspace {ws}", w.app_id),
+            None => w.app_id.clone(),
+        };
+
+        ResultItem {
+            key: format!("{KEY_PREFIX}{}", w.id),
+            title: w.title.clone(),
+            provider_id: PROVIDER_ID.into(),
+            score,
+            extras: Some(serde_json::json!({
+                "subtitle": subtitle,
+                "appId": w.app_id,
+                "appName": app.map(|a| &a.name),
+                "workspace": w.workspace,
+                "focused": w.focused,
+                "icon": app.and_then(|a| a.icon.as_ref()),
+                "defaultAction": "focus",
+                "actions": ["focus"],
+            })),
+            matches: (!indices.is_empty()).then(|| Matches {
+                title: MatchRange::from_char_indices(&w.title, indices),
+                subtitle: None,
+            }),
+        }
+    }
+
+    fn window(&self, key: &str) -> Result<Window> {
+        let id = window_id(key)?;
+        self.windows()?
+            .into_iter()
+            .find(|w| w.id == id)
+            .with_context(|| format!("Window {id} is gone"))
+    }
+
+    pub fn preview(&self, key: &str) -> Option<Preview> {
+        let w = self.window(key).ok()?;
+        let app = self.apps.find_by_window_class(&w.app_id);
+        let mut lines = vec![format!("App id: {}", w.app_id)];
+        if let Some(ws) = &w.workspace {
+            lines.push(format!("Workspace: {ws}"));
+        }
+        lines.push(format!("Compositor: {}", self.backend().ok()?.name()));
+
+        Some(Preview {
+            preview_kind: "card".into(),
+            data: serde_json::json!({
+                "title": w.title,
+                "comment": app.map_or(w.app_id.as_str(), |a| a.name.as_str()),
+                "snippet": lines.join("\n"),
+            }),
+        })
+    }
+
+    pub fn execute_focus(&self, key: &str) -> Result<()> {
+        self.backend()?.focus(window_id(key)?)?;
+        // Focus changed, so the next list must come from the compositor.
+        if let Ok(mut listed) = self.listed.lock() {
+            *listed = None;
+        }
+        Ok(())
+    }
+}
+
+impl Provider for WindowsProvider {
+    fn id(&self) -> &str {
+        PROVIDER_ID
+    }
+
+    fn search(&self, query: &str) -> Vec<ResultItem> {
+        self.search(query)
+    }
+
+    fn preview(&self, key: &str) -> Option<Preview> {
+        self.preview(key)
+    }
+
+    fn execute(&self, action: &str, key: &str) -> bool {
+        match action {
+            "focus" => self.execute_focus(key).is_ok(),
+            _ => false,
+        }
+    }
+}
+
+fn window_id(key: &str) -> Result<&str> {
+    key.strip_prefix(KEY_PREFIX)
+        .filter(|id| !id.is_empty())
+        .with_context(|| format!("Not a window key: {key}"))
+}
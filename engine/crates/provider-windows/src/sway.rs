@@ -0,0 +1,116 @@
+//! sway over its i3-compatible IPC socket.
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use crate::backend::{Compositor, Window};
+
+const MAGIC: &[u8; 6] = b"i3-ipc";
+const RUN_COMMAND: u32 = 0;
+const GET_TREE: u32 = 4;
+
+pub struct Sway {
+    socket: PathBuf,
+}
+
+impl Sway {
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        Self {
+            socket: socket.into(),
+        }
+    }
+
+    /// The socket in `$SWAYSOCK`.
+    pub fn from_env() -> Result<Self> {
+        let socket = std::env::var_os("SWAYSOCK").context("SWAYSOCK is not set")?;
+        Ok(Self::new(socket))
+    }
+
+    /// One request and its reply: the magic string, payload length and
+    /// message type in native byte order, then the payload.
+    fn request(&self, kind: u32, payload: &str) -> Result<Value> {
+        let mut stream = UnixStream::connect(&self.socket)
+            .with_context(|| format!("connect to {}", self.socket.display()))?;
+        let mut message = MAGIC.to_vec();
+        message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+        message.extend_from_slice(&kind.to_ne_bytes());
+        message.extend_from_slice(payload.as_bytes());
+        stream.write_all(&message)?;
+
+        let mut header = [0u8; 14];
+        stream.read_exact(&mut header)?;
+        if &header[..6] != MAGIC {
+            bail!("Not an i3-ipc reply");
+        }
+        let len = u32::from_ne_bytes(header[6..10].try_into()?) as usize;
+        let mut body = vec![0u8; len];
+        stream.read_exact(&mut body)?;
+        serde_json::from_slice(&body).context("parse sway reply")
+    }
+}
+
+impl Compositor for Sway {
+    fn name(&self) -> &str {
+        "sway"
+    }
+
+    fn windows(&self) -> Result<Vec<Window>> {
+        let tree = self.request(GET_TREE, "")?;
+        let mut out = Vec::new();
+        collect(&tree, None, &mut out);
+        Ok(out)
+    }
+
+    fn focus(&self, id: &str) -> Result<()> {
+        // Only ever a number, so nothing else ends up in the command.
+        let id: i64 = id
+            .parse()
+            .with_context(|| format!("Bad sway window {id}"))?;
+        let reply = self.request(RUN_COMMAND, &format!("[con_id={id}] focus"))?;
+        match reply.get(0) {
+            Some(r) if r["success"].as_bool() == Some(true) => Ok(()),
+            Some(r) => bail!("{}", r["error"].as_str().unwrap_or("sway refused")),
+            None => bail!("Empty sway reply"),
+        }
+    }
+}
+
+/// Leaf containers holding an app, with the workspace they are on.
+fn collect(node: &Value, workspace: Option<&str>, out: &mut Vec<Window>) {
+    let workspace = match node["type"].as_str() {
+        Some("workspace") => node["name"].as_str(),
+        _ => workspace,
+    };
+    let children = ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|k| node[k].as_array())
+        .flatten();
+
+    let is_leaf = ["nodes", "floating_nodes"]
+        .iter()
+        .all(|k| node[k].as_array().is_none_or(Vec::is_empty));
+    // Wayland windows have an app id, XWayland ones a class.
+    let app_id = node["app_id"]
+        .as_str()
+        .or_else(|| node["window_properties"]["class"].as_str());
+    if is_leaf
+        && matches!(node["type"].as_str(), Some("con" | "floating_con"))
+        && let (Some(id), Some(app_id)) = (node["id"].as_i64(), app_id)
+    {
+        out.push(Window {
+            id: id.to_string(),
+            app_id: app_id.to_string(),
+            title: node["name"].as_str().unwrap_or_default().to_string(),
+            workspace: workspace.map(str::to_string),
+            focused: node["focused"].as_bool().unwrap_or(false),
+        });
+    }
+
+    for child in children {
+        collect(child, workspace, out);
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{Result, bail};
+use lancea_model::Provider;
+use lancea_provider_apps::AppsProvider;
+use lancea_provider_windows::{Compositor, Hyprland, Sway, Window, WindowsConfig, WindowsProvider};
+use lancea_testkit::titles;
+
+struct FakeCompositor {
+    windows: Vec<Window>,
+    focused: Arc<Mutex<Vec<String>>>,
+}
+
+impl Compositor for FakeCompositor {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn windows(&self) -> Result<Vec<Window>> {
+        Ok(self.windows.clone())
+    }
+
+    fn focus(&self, id: &str) -> Result<()> {
+        if !self.windows.iter().any(|w| w.id == id) {
+            bail!("no window {id}");
+        }
+        self.focused.lock().unwrap().push(id.to_string());
+        Ok(())
+    }
+}
+
+fn window(id: &str, app_id: &str, title: &str, workspace: &str, focused: bool) -> Window {
+    Window {
+        id: id.into(),
+        app_id: app_id.into(),
+        title: title.into(),
+        workspace: Some(workspace.into()),
+        focused,
+    }
+}
+
+/// An apps index with one entry matched by `StartupWMClass` and one by its
+/// reverse-DNS desktop id.
+fn apps(dir: &Path) -> Arc<AppsProvider> {
+    fs::write(
+        dir.join("alacritty.desktop"),
+        "[Desktop Entry]\nType=Application\nName=Alacritty\nIcon=Alacritty\nStartupWMClass=Alacritty\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("org.mozilla.firefox.desktop"),
+        "[Desktop Entry]\nType=Application\nName=Firefox\nIcon=org.mozilla.firefox\n",
+    )
+    .unwrap();
+    Arc::new(AppsProvider::from_dirs(&[dir.to_path_buf()]).unwrap())
+}
+
+fn setup() -> (tempfile::TempDir, WindowsProvider, Arc<Mutex<Vec<String>>>) {
+    let dir = tempfile::tempdir().unwrap();
+    let focused = Arc::new(Mutex::new(Vec::new()));
+    let backend = FakeCompositor {
+        windows: vec![
+            window("1", "Alacritty", "~/src/lancea", "1", true),
+            window("2", "firefox", "Rust docs — Mozilla Firefox", "2", false),
+            window("3", "Alacritty", "htop", "3", false),
+        ],
+        focused: focused.clone(),
+    };
+    let p = WindowsProvider::with_backend(
+        WindowsConfig::default(),
+        apps(dir.path()),
+        Box::new(backend),
+    );
+    (dir, p, focused)
+}
+
+#[test]
+fn lists_windows_with_app_workspace_and_icon() {
+    let (_dir, p, _) = setup();
+
+    // The focused window goes last.
+    assert_eq!(
+        titles(&p, "/win"),
+        vec!["Rust docs — Mozilla Firefox", "htop", "~/src/lancea"]
+    );
+
+    let items = p.search("/win");
+    let firefox = items[0].extras.as_ref().unwrap();
+    assert_eq!(firefox["subtitle"], "firefox · workspace 2");
+    assert_eq!(firefox["appName"], "Firefox");
+    assert_eq!(firefox["icon"], "org.mozilla.firefox");
+    assert_eq!(firefox["workspace"], "2");
+    assert_eq!(items[1].extras.as_ref().unwrap()["icon"], "Alacritty");
+
+    let preview = p.preview("windows:3").unwrap();
+    assert_eq!(preview.data["comment"], "Alacritty");
+    assert_eq!(
+        preview.data["snippet"],
+        "App id: Alacritty\nWorkspace: 3\nCompositor: fake"
+    );
+}
+
+#[test]
+fn searches_titles_and_apps_and_focuses() {
+    let (_dir, p, focused) = setup();
+
+    assert_eq!(titles(&p, "/win htop"), vec!["htop"]);
+    assert_eq!(titles(&p, "/win rust")[0], "Rust docs — Mozilla Firefox");
+    let terminals = titles(&p, "/win alacritty");
+    assert_eq!(terminals.len(), 2);
+    assert!(terminals.contains(&"htop".to_string()));
+
+    assert!(p.execute("focus", "windows:2"));
+    assert!(!p.execute("focus", "windows:9"));
+    assert!(!p.execute("close", "windows:2"));
+    assert_eq!(*focused.lock().unwrap(), vec!["2"]);
+}
+
+/// Answers i3-ipc requests on `socket` with `reply` per message type,
+/// recording the payloads.
+fn fake_sway(socket: &Path, replies: Vec<(u32, String)>) -> Arc<Mutex<Vec<String>>> {
+    let listener = UnixListener::bind(socket).unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut header = [0u8; 14];
+            stream.read_exact(&mut header).unwrap();
+            let len = u32::from_ne_bytes(header[6..10].try_into().unwrap()) as usize;
+            let kind = u32::from_ne_bytes(header[10..14].try_into().unwrap());
+            let mut payload = vec![0u8; len];
+            stream.read_exact(&mut payload).unwrap();
+            log.lock()
+                .unwrap()
+                .push(String::from_utf8(payload).unwrap());
+
+            let body = &replies.iter().find(|(k, _)| *k == kind).unwrap().1;
+            let mut reply = b"i3-ipc".to_vec();
+            reply.extend_from_slice(&(body.len() as u32).to_ne_bytes());
+            reply.extend_from_slice(&kind.to_ne_bytes());
+            reply.extend_from_slice(body.as_bytes());
+            stream.write_all(&reply).unwrap();
+        }
+    });
+    seen
+}
+
+#[test]
+fn sway_lists_the_tree_and_focuses_by_con_id() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("sway.sock");
+    let tree = serde_json::json!({
+        "id": 1, "type": "root", "name": "root",
+        "nodes": [{
+            "id": 2, "type": "output", "name": "eDP-1",
+            "nodes": [{
+                "id": 3, "type": "workspace", "name": "1",
+                "nodes": [
+                    {"id": 10, "type": "con", "name": "vim", "app_id": "foot", "focused": true, "nodes": []},
+                    {"id": 11, "type": "con", "name": null, "nodes": [
+                        {"id": 12, "type": "con", "name": "Slack", "app_id": null,
+                         "window_properties": {"class": "Slack"}, "nodes": []}
+                    ]}
+                ],
+                "floating_nodes": [
+                    {"id": 13, "type": "floating_con", "name": "Calculator", "app_id": "org.gnome.Calculator", "nodes": []}
+                ]
+            }]
+        }]
+    });
+    let seen = fake_sway(
+        &socket,
+        vec![(4, tree.to_string()), (0, r#"[{"success":true}]"#.into())],
+    );
+
+    let sway = Sway::new(&socket);
+    let windows = sway.windows().unwrap();
+    assert_eq!(
+        windows,
+        vec![
+            window("10", "foot", "vim", "1", true),
+            window("12", "Slack", "Slack", "1", false),
+            window("13", "org.gnome.Calculator", "Calculator", "1", false),
+        ]
+    );
+
+    sway.focus("12").unwrap();
+    assert!(sway.focus("12; exec rm -rf ~").is_err());
+    assert_eq!(seen.lock().unwrap().last().unwrap(), "[con_id=12] focus");
+}
+
+#[test]
+fn hyprland_lists_clients_and_focuses_by_address() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join(".socket.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = seen.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut buf = [0u8; 256];
+            let n = stream.read(&mut buf).unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).into_owned();
+            let reply = if request == "j/clients" {
+                serde_json::json!([
+                    {"address": "0x55d1", "mapped": true, "class": "kitty", "title": "zsh",
+                     "workspace": {"id": 1, "name": "1"}, "focusHistoryID": 0},
+                    {"address": "0x55d2", "mapped": false, "class": "kitty", "title": "hidden",
+                     "workspace": {"id": 1, "name": "1"}, "focusHistoryID": 2},
+                    {"address": "0x55d3", "mapped": true, "class": "firefox", "title": "News",
+                     "workspace": {"id": 2, "name": "web"}, "focusHistoryID": 1}
+                ])
+                .to_string()
+            } else {
+                "ok".to_string()
+            };
+            log.lock().unwrap().push(request);
+            stream.write_all(reply.as_bytes()).unwrap();
+        }
+    });
+
+    let hypr = Hyprland::new(&socket);
+    assert_eq!(
+        hypr.windows().unwrap(),
+        vec![
+            window("0x55d1", "kitty", "zsh", "1", true),
+            window("0x55d3", "firefox", "News", "web", false),
+        ]
+    );
+
+    hypr.focus("0x55d3").unwrap();
+    assert!(hypr.focus("0x55d3; dispatch exit").is_err());
+    assert_eq!(
+        seen.lock().unwrap().last().unwrap(),
+        "dispatch focuswindow address:0x55d3"
+    );
+}
@@ -1,42 +1,317 @@
+use std::sync::LazyLock;
+
 use lancea_model::ResolvedCommand;
+use regex::Regex;
+
+/// A slash command and the prefixes that select it. Prefixes are matched in
+/// table order, so longer ones must come before any shorter prefix of them.
+struct SlashCommand {
+    id: &'static str,
+    provider_id: &'static str,
+    prefixes: &'static [&'static str],
+}
+
+const SLASH_COMMANDS: &[SlashCommand] = &[
+    SlashCommand {
+        id: "emoji",
+        provider_id: "emoji",
+        prefixes: &["/emoji", "/em"],
+    },
+    SlashCommand {
+        id: "apps",
+        provider_id: "apps",
+        prefixes: &["/apps", "/ap"],
+    },
+    SlashCommand {
+        id: "calc",
+        provider_id: "calc",
+        prefixes: &["/calc"],
+    },
+    SlashCommand {
+        id: "convert",
+        provider_id: "convert",
+        prefixes: &["/convert", "/conv"],
+    },
+    SlashCommand {
+        id: "files",
+        provider_id: "files",
+        prefixes: &["/files", "/f"],
+    },
+    SlashCommand {
+        id: "recent",
+        provider_id: "recent",
+        prefixes: &["/recent", "/rec"],
+    },
+    SlashCommand {
+        id: "clip",
+        provider_id: "clip",
+        prefixes: &["/clip"],
+    },
+    SlashCommand {
+        id: "snip",
+        provider_id: "snip",
+        prefixes: &["/snip"],
+    },
+    SlashCommand {
+        id: "ssh",
+        provider_id: "ssh",
+        prefixes: &["/ssh"],
+    },
+    SlashCommand {
+        id: "bookmarks",
+        provider_id: "bookmarks",
+        prefixes: &["/bookmarks", "/bm"],
+    },
+    SlashCommand {
+        id: "run",
+        provider_id: "run",
+        prefixes: &["/run", ">"],
+    },
+    SlashCommand {
+        id: "kill",
+        provider_id: "proc",
+        prefixes: &["/kill"],
+    },
+    SlashCommand {
+        id: "unit",
+        provider_id: "systemd",
+        prefixes: &["/unit"],
+    },
+    SlashCommand {
+        id: "sys",
+        provider_id: "session",
+        prefixes: &["/sys"],
+    },
+    SlashCommand {
+        id: "win",
+        provider_id: "windows",
+        prefixes: &["/win"],
+    },
+    SlashCommand {
+        id: "pass",
+        provider_id: "pass",
+        prefixes: &["/pass"],
+    },
+    SlashCommand {
+        id: "repo",
+        provider_id: "repo",
+        prefixes: &["/repo"],
+    },
+    SlashCommand {
+        id: "tmux",
+        provider_id: "tmux",
+        prefixes: &["/tmux"],
+    },
+    SlashCommand {
+        id: "date",
+        provider_id: "date",
+        prefixes: &["/date", "/time"],
+    },
+    SlashCommand {
+        id: "color",
+        provider_id: "color",
+        prefixes: &["/color", "/colour"],
+    },
+    // Last, so "/u" doesn't take longer commands such as "/unit".
+    SlashCommand {
+        id: "char",
+        provider_id: "char",
+        prefixes: &["/char", "/u"],
+    },
+];
+
+/// Recognises plain input that a provider can answer without a slash
+/// command. Rules are tried in order and the first match wins.
+struct IntentRule {
+    intent: &'static str,
+    provider_id: &'static str,
+    matches: fn(&str) -> bool,
+}
+
+const INTENT_RULES: &[IntentRule] = &[
+    // Before calc, which would take `2026-10-17` for a subtraction.
+    IntentRule {
+        intent: "date",
+        provider_id: "date",
+        matches: is_date_query,
+    },
+    IntentRule {
+        intent: "calc",
+        provider_id: "calc",
+        matches: is_calc_expression,
+    },
+    IntentRule {
+        intent: "convert",
+        provider_id: "convert",
+        matches: is_conversion,
+    },
+    IntentRule {
+        intent: "color",
+        provider_id: "color",
+        matches: is_color,
+    },
+];
+
+static DATE_QUERY: LazyLock<Regex> = LazyLock::new(|| {
+    let zone = r"(?:utc|gmt)(?:\s*[-+]\s*\d{1,2}(?::00)?)?|z|zulu|pst|pdt|pt|mst|mdt|cst|cdt|est|edt|et|bst|cet|cest|eet|eest|msk|ist|jst|kst|aest|aedt|nzst|nzdt";
+    let base = r"now|today|tomorrow|yesterday|\d{4}-\d{2}-\d{2}(?:[t ]\d{1,2}:\d{2}(?::\d{2})?(?:z|[-+]\d{2}:?\d{2})?)?";
+    let unit = r"s|secs?|seconds?|m|mins?|minutes?|h|hrs?|hours?|d|days?|w|wks?|weeks?|mo|mons?|months?|y|yrs?|years?";
+    Regex::new(&format!(
+        r"(?i)^(?:(?:now|time|date|today)(?:\s+(?:in|at)\s+\S.*|\s+(?:{zone}))?|@-?\d{{1,13}}|\d{{10}}|\d{{13}}|week\s+(?:number|num|no\.?)(?:\s+(?:of|for)\s+\S.*)?|(?:{base})(?:\s*[-+]\s*\d+\s*(?:{unit}))*(?:\s+in\s+\S.*)?)$"
+    ))
+    .unwrap()
+});
+
+/// `time in tokyo`, `now utc`, a unix timestamp such as `1700000000`,
+/// `week number`, or a date with optional steps like `2026-10-17 + 45d`.
+/// Only known abbreviations may follow a bare `now`, so `now playing`
+/// stays a search.
+fn is_date_query(text: &str) -> bool {
+    DATE_QUERY.is_match(text)
+}
+
+/// Words the calculator understands; anything else means the text is not
+/// an expression.
+const CALC_WORDS: &str = "sqrt|cbrt|abs|exp|ln|log|log2|log10|sin|cos|tan|asin|acos|atan|sinh|cosh|tanh|floor|ceil|round|trunc|pow|min|max|pi|tau|e|xor|mod";
+
+static CALC_TOKENS: LazyLock<Regex> = LazyLock::new(|| {
+    let number = r"0x[0-9a-f_]+|0b[01_]+|0o[0-7_]+|(?:\d[\d_]*(?:\.\d*)?|\.\d+)(?:e[+-]?\d+)?";
+    let ops = r"\*\*|<<|>>|[-+*/%^&|~(),×÷−π]";
+    Regex::new(&format!(
+        r"(?i)^(?:\s*(?:{number}|{ops}|(?:{CALC_WORDS})\b))+\s*$"
+    ))
+    .unwrap()
+});
+
+static CALC_SIGNAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)\d\s*(?:\*\*|<<|>>|[-+*/%^&|×÷−]|xor|mod)|\b(?:{CALC_WORDS})\s*\(|\b0[xbo][0-9a-f]"
+    ))
+    .unwrap()
+});
+
+/// Arithmetic such as `2*(3+4)`, `sqrt(2)` or `0xff`: only numbers,
+/// operators and calculator words, with an operator, a function call or a
+/// radix literal, so a bare `42` or a word like `pine` is left alone.
+fn is_calc_expression(text: &str) -> bool {
+    CALC_TOKENS.is_match(text) && CALC_SIGNAL.is_match(text)
+}
+
+static CONVERSION: LazyLock<Regex> = LazyLock::new(|| {
+    let unit = r#"[\p{L}°µ℃℉"'][\p{L}°µ/"'²³]*"#;
+    Regex::new(&format!(
+        r"(?i)^[-+]?(?:\d[\d_]*(?:\.\d*)?|\.\d+)(?:e[-+]?\d+)?\s*{unit}\s+(?:in|to|as|into)\s+{unit}$"
+    ))
+    .unwrap()
+});
+
+/// `<amount> <unit> in|to|as|into <unit>`, e.g. `10 km in miles` or
+/// `72f to c`. Whether the units exist is up to the provider.
+fn is_conversion(text: &str) -> bool {
+    CONVERSION.is_match(text)
+}
+
+static COLOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:#(?:[0-9a-f]{3,4}|[0-9a-f]{6}|[0-9a-f]{8})|(?:rgba?|hsla?|oklch)\(\s*[-+\d.][^()]*\))$")
+        .unwrap()
+});
+
+/// CSS color syntax: `#ff8800`, `#f80`, `rgb(1,2,3)`, `hsl(...)` or
+/// `oklch(...)`. Whether the channels make sense is up to the provider.
+fn is_color(text: &str) -> bool {
+    COLOR.is_match(text)
+}
+
+/// A slash command whose prefixes come from config rather than the table,
+/// such as web search keywords. These must be followed by whitespace or
+/// the end of the input, so `/g` doesn't take `/gh`, and ignore case.
+struct ConfiguredCommand {
+    id: String,
+    provider_id: String,
+    prefixes: Vec<String>,
+}
 
-pub struct CommandRegistry;
+pub struct CommandRegistry {
+    configured: Vec<ConfiguredCommand>,
+}
 
 impl CommandRegistry {
     pub fn new() -> Self {
-        Self
+        Self {
+            configured: Vec::new(),
+        }
+    }
+
+    /// Adds a command selected by any of `prefixes`. These are tried before
+    /// the built-in commands.
+    pub fn with_command(mut self, id: &str, provider_id: &str, prefixes: Vec<String>) -> Self {
+        self.configured.push(ConfiguredCommand {
+            id: id.to_string(),
+            provider_id: provider_id.to_string(),
+            prefixes,
+        });
+        self
     }
 
     pub fn resolve(&self, text: &str) -> ResolvedCommand {
         dbg!("[Registry#resolve] - query resolving with: {}", &text);
         let trimmed = text.trim();
 
-        if trimmed.starts_with("/emoji") || trimmed.starts_with("/em") {
-            dbg!("[Registry#resolve] - query found with: {}", &trimmed);
+        let head = trimmed
+            .split(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        if let Some(cmd) = self
+            .configured
+            .iter()
+            .find(|c| c.prefixes.iter().any(|p| p.eq_ignore_ascii_case(head)))
+        {
             return ResolvedCommand {
                 matched: true,
-                provider_id: Some("emoji".to_string()),
-                command_id: Some("emoji".to_string()),
+                provider_id: Some(cmd.provider_id.clone()),
+                command_id: Some(cmd.id.clone()),
                 intent: None,
                 reason: Some("slash-command".into()),
             };
-        } else if trimmed.starts_with("/apps") || trimmed.starts_with("/ap") {
+        }
+
+        if let Some(cmd) = SLASH_COMMANDS
+            .iter()
+            .find(|c| c.prefixes.iter().any(|p| trimmed.starts_with(p)))
+        {
+            dbg!("[Registry#resolve] - query found with: {}", &trimmed);
             return ResolvedCommand {
                 matched: true,
-                provider_id: Some("apps".to_string()),
-                command_id: Some("apps".to_string()),
+                provider_id: Some(cmd.provider_id.to_string()),
+                command_id: Some(cmd.id.to_string()),
                 intent: None,
                 reason: Some("slash-command".into()),
             };
-        } else {
+        }
+
+        if let Some(rule) = INTENT_RULES.iter().find(|r| (r.matches)(trimmed)) {
             return ResolvedCommand {
-                matched: false,
-                provider_id: None,
+                matched: true,
+                provider_id: Some(rule.provider_id.to_string()),
                 command_id: None,
-                intent: None,
-                reason: None,
+                intent: Some(rule.intent.to_string()),
+                reason: Some("intent".into()),
             };
         }
+
+        ResolvedCommand {
+            matched: false,
+            provider_id: None,
+            command_id: None,
+            intent: None,
+            reason: None,
+        }
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -75,4 +350,322 @@ mod tests {
         assert!(resolved.provider_id.is_none());
         assert!(resolved.command_id.is_none());
     }
+
+    #[test]
+    fn test_calc_intent() {
+        let registry = CommandRegistry::new();
+
+        for text in ["2*(3+4)", " 1 + 1 ", "sqrt(2)/2", "0xff", "6 xor 3"] {
+            let resolved = registry.resolve(text);
+            assert!(resolved.matched, "{text}");
+            assert_eq!(resolved.provider_id, Some("calc".to_string()));
+            assert_eq!(resolved.intent, Some("calc".to_string()));
+            assert!(resolved.command_id.is_none());
+            assert_eq!(resolved.reason, Some("intent".into()));
+        }
+
+        for text in ["42", "pine", "e", "firefox", "vsc", "2048 game", "-", "2pi"] {
+            assert!(!registry.resolve(text).matched, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_date_intent() {
+        let registry = CommandRegistry::new();
+
+        for text in [
+            "time in tokyo",
+            "now utc",
+            "Now UTC+3",
+            "today",
+            "1700000000",
+            "@0",
+            "2026-10-17 + 45d",
+            "2026-10-17",
+            "tomorrow - 2 weeks",
+            "2026-10-17T10:00Z in new york",
+            "week number",
+            "week number of 2027-01-01",
+        ] {
+            let resolved = registry.resolve(text);
+            assert!(resolved.matched, "{text}");
+            assert_eq!(resolved.provider_id, Some("date".to_string()), "{text}");
+            assert_eq!(resolved.intent, Some("date".to_string()));
+            assert!(resolved.command_id.is_none());
+            assert_eq!(resolved.reason, Some("intent".into()));
+        }
+
+        assert_eq!(
+            registry.resolve("2026 - 10").provider_id,
+            Some("calc".to_string())
+        );
+        for text in [
+            "now playing",
+            "date picker",
+            "timer",
+            "week",
+            "12345",
+            "2026-10-17 + 3 parsecs",
+        ] {
+            assert!(!registry.resolve(text).matched, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_convert_intent() {
+        let registry = CommandRegistry::new();
+
+        for text in [
+            "10 km in miles",
+            "72f to c",
+            "5 GiB in MB",
+            "20 usd to eur",
+            "100 km/h as mph",
+        ] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("convert".to_string()), "{text}");
+            assert_eq!(resolved.intent, Some("convert".to_string()));
+            assert_eq!(resolved.reason, Some("intent".into()));
+        }
+
+        for text in [
+            "10 km",
+            "km in miles",
+            "10 km in",
+            "the 10 best apps in 2024",
+        ] {
+            assert!(!registry.resolve(text).matched, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_files_aliases() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/files report", "/f report"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("files".to_string()));
+            assert_eq!(resolved.command_id, Some("files".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_recent_aliases() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/recent notes", "/rec notes", "/recent"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("recent".to_string()));
+            assert_eq!(resolved.command_id, Some("recent".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_clip_slash_command() {
+        let resolved = CommandRegistry::new().resolve("/clip https");
+        assert_eq!(resolved.provider_id, Some("clip".to_string()));
+        assert_eq!(resolved.command_id, Some("clip".to_string()));
+    }
+
+    #[test]
+    fn test_snip_slash_command() {
+        let resolved = CommandRegistry::new().resolve("/snip thanks");
+        assert_eq!(resolved.provider_id, Some("snip".to_string()));
+        assert_eq!(resolved.command_id, Some("snip".to_string()));
+    }
+
+    #[test]
+    fn test_ssh_slash_command() {
+        let resolved = CommandRegistry::new().resolve("/ssh web1");
+        assert_eq!(resolved.provider_id, Some("ssh".to_string()));
+        assert_eq!(resolved.command_id, Some("ssh".to_string()));
+    }
+
+    #[test]
+    fn test_bookmarks_aliases() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/bookmarks rust", "/bm rust", "/bm"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("bookmarks".to_string()));
+            assert_eq!(resolved.command_id, Some("bookmarks".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_run_aliases() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/run make", "> make", ">ls -la"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("run".to_string()));
+            assert_eq!(resolved.command_id, Some("run".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_kill_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/kill firefox", "/kill", "/kill 4242"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("proc".to_string()));
+            assert_eq!(resolved.command_id, Some("kill".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_unit_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/unit syncthing", "/unit"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("systemd".to_string()));
+            assert_eq!(resolved.command_id, Some("unit".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_sys_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/sys reboot", "/sys"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("session".to_string()));
+            assert_eq!(resolved.command_id, Some("sys".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_win_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/win firefox", "/win"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("windows".to_string()));
+            assert_eq!(resolved.command_id, Some("win".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_pass_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/pass email/work", "/pass"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("pass".to_string()));
+            assert_eq!(resolved.command_id, Some("pass".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_repo_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/repo lancea", "/repo"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("repo".to_string()));
+            assert_eq!(resolved.command_id, Some("repo".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_color_intent() {
+        let registry = CommandRegistry::new();
+
+        for text in [
+            "#ff8800",
+            "#F80",
+            "#ff880080",
+            "rgb(1,2,3)",
+            "rgba(0, 0, 255, 0.5)",
+            "hsl(32deg 100% 50%)",
+            "oklch(0.7 0.15 62)",
+        ] {
+            let resolved = registry.resolve(text);
+            assert!(resolved.matched, "{text}");
+            assert_eq!(resolved.provider_id, Some("color".to_string()), "{text}");
+            assert_eq!(resolved.intent, Some("color".to_string()));
+            assert!(resolved.command_id.is_none());
+            assert_eq!(resolved.reason, Some("intent".into()));
+        }
+
+        for text in ["#12345", "#hashtag", "rgb", "rgb(", "hsl(foo)", "ff8800"] {
+            assert!(!registry.resolve(text).matched, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_color_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/color ff8800", "/colour rgb(1,2,3)", "/color"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("color".to_string()));
+            assert_eq!(resolved.command_id, Some("color".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_tmux_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/tmux work", "/tmux"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("tmux".to_string()));
+            assert_eq!(resolved.command_id, Some("tmux".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_date_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/date", "/time berlin", "/date 2026-10-17 + 45d"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("date".to_string()));
+            assert_eq!(resolved.command_id, Some("date".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_char_command() {
+        let registry = CommandRegistry::new();
+
+        for text in ["/char arrow", "/u U+2192", "/u"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("char".to_string()));
+            assert_eq!(resolved.command_id, Some("char".to_string()));
+        }
+        let resolved = registry.resolve("/unit ssh");
+        assert_eq!(resolved.provider_id, Some("systemd".to_string()));
+    }
+
+    #[test]
+    fn test_configured_commands() {
+        let registry = CommandRegistry::new().with_command(
+            "web",
+            "web",
+            vec!["/g".into(), "/gh".into(), "/f".into()],
+        );
+
+        for text in ["/g rust traits", "/GH lancea", "/g", "/f report"] {
+            let resolved = registry.resolve(text);
+            assert_eq!(resolved.provider_id, Some("web".to_string()));
+            assert_eq!(resolved.command_id, Some("web".to_string()));
+        }
+        // Only whole keywords count; the rest goes to the built-in table.
+        let resolved = registry.resolve("/files report");
+        assert_eq!(resolved.provider_id, Some("files".to_string()));
+        assert!(!registry.resolve("/go home").matched);
+    }
+
+    #[test]
+    fn test_calc_slash_command() {
+        let resolved = CommandRegistry::new().resolve("/calc 1+1");
+
+        assert_eq!(resolved.provider_id, Some("calc".to_string()));
+        assert_eq!(resolved.command_id, Some("calc".to_string()));
+        assert!(resolved.intent.is_none());
+    }
 }
@@ -0,0 +1,45 @@
+//! Scaffolding the providers' smoke tests share: apps providers with
+//! nothing installed, terminals that do nothing or write down what they
+//! were asked to run, and the titles a query turns up.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use lancea_model::Provider;
+use lancea_provider_apps::AppsProvider;
+
+/// Titles of the results `q` turns up, best first.
+pub fn titles(p: &impl Provider, q: &str) -> Vec<String> {
+    p.search(q).into_iter().map(|r| r.title).collect()
+}
+
+/// An apps provider with nothing installed, so nothing opens.
+pub fn no_apps() -> Arc<AppsProvider> {
+    Arc::new(AppsProvider::from_dirs(&[]).expect("an empty apps provider"))
+}
+
+/// No apps, and a terminal that exits at once: actions that open one
+/// succeed without a window appearing.
+pub fn quiet_terminal() -> Arc<AppsProvider> {
+    with_terminal(vec!["true".into()])
+}
+
+/// No apps, and a terminal that writes what it runs to `file`, one
+/// argument per line.
+pub fn recording_terminal(file: &Path) -> Arc<AppsProvider> {
+    with_terminal(recorder(file))
+}
+
+/// A command line that writes its arguments to `file`, one per line.
+pub fn recorder(file: &Path) -> Vec<String> {
+    let script = format!("printf '%s\\n' \"$@\" > {}", file.display());
+    vec!["sh".into(), "-c".into(), script, "sh".into()]
+}
+
+fn with_terminal(terminal: Vec<String>) -> Arc<AppsProvider> {
+    Arc::new(
+        AppsProvider::from_dirs(&[])
+            .expect("an empty apps provider")
+            .with_terminal(terminal),
+    )
+}
@@ -31,6 +31,101 @@ Window {
         {}
     }
 
+    function escapeHtml(s) {
+        return s.replace(/&/g, "&amp;").replace(/</g, "&lt;").replace(/>/g, "&gt;");
+    }
+
+    // Bolds the matched [start, end) UTF-16 ranges of `text`. `ranges` arrives
+    // either as a JS array or, once it has been through ListModel, as a
+    // nested model.
+    function highlight(text, ranges) {
+        if (!ranges)
+            return escapeHtml(text);
+        const n = ranges.count !== undefined ? ranges.count : ranges.length;
+        let out = "";
+        let pos = 0;
+        for (let i = 0; i < n; i++) {
+            const r = ranges.get ? ranges.get(i) : ranges[i];
+            out += escapeHtml(text.slice(pos, r.start));
+            out += "<b>" + escapeHtml(text.slice(r.start, r.end)) + "</b>";
+            pos = r.end;
+        }
+        return out + escapeHtml(text.slice(pos));
+    }
+
+    // Plain string list from extras.args or extras.actions, which may have
+    // been turned into a nested model by ListModel.
+    function listArgs(args) {
+        if (!args)
+            return [];
+        const n = args.count !== undefined ? args.count : args.length;
+        const out = [];
+        for (let i = 0; i < n; i++) {
+            const a = args.get ? args.get(i) : args[i];
+            out.push(typeof a === "string" ? a : Object.values(a)[0]);
+        }
+        return out;
+    }
+
+    // Copies extras.copyText, or the action's entry of extras.copyTexts
+    // where a result copies several things (e.g. a character's codepoint),
+    // and tells the provider, which validates the key.
+    function copyItem(item, action) {
+        const text = item.extras.copyTexts?.[action] ?? item.extras.copyText;
+        Clipboard.setText(text);
+        toast.text = "Copied " + text;
+        toast.visible = true;
+        toastTimer.restart();
+        engineProxy.execute(action, win.providerId, item.key);
+    }
+
+    // Runs a result action other than the default one: asks for its
+    // arguments, copies its text, searches on with the action's entry of
+    // extras.queryTexts (a file's "Open with…" list) or hands it to run().
+    function runAction(item, action) {
+        if (win.listArgs(item.extras?.prompts).length > 0)
+            promptDialog.ask(item, action);
+        else if (item.extras?.copyTexts?.[action] !== undefined)
+            win.copyItem(item, action);
+        else if (item.extras?.queryTexts?.[action] !== undefined) {
+            engineProxy.execute(action, win.providerId, item.key);
+            input.text = item.extras.queryTexts[action];
+            input.cursorPosition = input.text.length;
+        } else
+            win.run(item, action, []);
+    }
+
+    // Runs a result action. Typing text out (snippets) needs the focus back
+    // in the window the text is for, so the launcher gets out of the way;
+    // so does copying a password, which may bring up a pinentry first.
+    // Commands run in the background report their exit code; their
+    // output shows in the refreshed preview. Actions listed in
+    // extras.confirm (e.g. killing a process) are asked about first and
+    // then sent with the "confirm" argument.
+    function run(item, action, args, confirmed) {
+        if (!confirmed && win.listArgs(item.extras?.confirm).includes(action)) {
+            confirmDialog.ask(item, action);
+            return;
+        }
+        if (action === "type" || action === "clip_password")
+            win.hide();
+        const reply = engineProxy.execute(action, win.providerId, item.key, args);
+        const outcome = reply ? JSON.parse(reply).data : {};
+        if (outcome.exit_code !== undefined) {
+            toast.text = outcome.message;
+            toast.visible = true;
+            toastTimer.restart();
+            engineProxy.requestPreview(item.key, win.currentEpoch);
+        } else if (confirmed) {
+            if (outcome.message) {
+                toast.text = outcome.message;
+                toast.visible = true;
+                toastTimer.restart();
+            }
+            debounce.restart();
+        }
+    }
+
     Connections {
         target: engineProxy
         // (epoch, providerId, token, batchJson)
@@ -42,6 +137,14 @@ Window {
         }
     }
 
+    // Feeds the engine's clipboard history (/clip).
+    Connections {
+        target: Clipboard
+        function onChanged() {
+            engineProxy.recordClipboard(Clipboard.getText(), Clipboard.mimeTypes());
+        }
+    }
+
     ColumnLayout {
         anchors.fill: parent
         anchors.margins: 16
@@ -53,6 +156,36 @@ Window {
             focus: true
             KeyNavigation.tab: results
             onTextChanged: debounce.restart()
+            // Ctrl+Return runs a result's second action (e.g. reveal a file),
+            // Alt+1 to Alt+9 its first to ninth (e.g. copy a character as
+            // HTML), Ctrl+Shift+C copies its text (e.g. a file's path),
+            // Shift+Delete deletes it where that is an action (e.g. a
+            // clipboard entry) and Tab takes its completion into the field
+            // (e.g. a command).
+            Keys.onPressed: event => {
+                if (resultsModel.count === 0)
+                    return;
+                const item = resultsModel.get(selectedIndex);
+                const actions = win.listArgs(item.extras?.actions);
+                if ((event.key === Qt.Key_Return || event.key === Qt.Key_Enter) && (event.modifiers & Qt.ControlModifier) && actions.length > 1) {
+                    win.runAction(item, actions[1]);
+                    event.accepted = true;
+                } else if ((event.modifiers & Qt.AltModifier) && event.key >= Qt.Key_1 && event.key <= Qt.Key_9 && event.key - Qt.Key_1 < actions.length) {
+                    win.runAction(item, actions[event.key - Qt.Key_1]);
+                    event.accepted = true;
+                } else if (event.key === Qt.Key_C && (event.modifiers & Qt.ControlModifier) && (event.modifiers & Qt.ShiftModifier) && item.extras?.copyText !== undefined) {
+                    win.copyItem(item, actions.find(a => a.startsWith("copy")) ?? "copy");
+                    event.accepted = true;
+                } else if (event.key === Qt.Key_Delete && (event.modifiers & Qt.ShiftModifier) && actions.includes("delete")) {
+                    engineProxy.execute("delete", win.providerId, item.key);
+                    debounce.restart();
+                    event.accepted = true;
+                } else if (event.key === Qt.Key_Tab && item.extras?.completion !== undefined) {
+                    input.text = item.extras.completion;
+                    input.cursorPosition = input.text.length;
+                    event.accepted = true;
+                }
+            }
             onAccepted: {
                 if (resultsModel.count > 0) {
                     const item = resultsModel.get(selectedIndex);
@@ -65,8 +198,14 @@ Window {
                         toastTimer.restart();
                         // also call execute if desired:
                         engineProxy.execute("copy_glyph", currentProviderId, item.key);
+                    } else if (win.listArgs(item.extras?.prompts).length > 0) {
+                        promptDialog.ask(item, item.extras.defaultAction);
+                    } else if (item.extras?.defaultAction === "copy" && item.extras?.copyText !== undefined) {
+                        win.copyItem(item, "copy");
+                    } else if (item.extras?.copyTexts?.[item.extras?.defaultAction] !== undefined) {
+                        win.copyItem(item, item.extras.defaultAction);
                     } else {
-                        engineProxy.execute("launch", currentProviderId, item.key);
+                        win.run(item, item.extras?.defaultAction ?? "launch", win.listArgs(item.extras?.args));
                     }
                 }
             }
@@ -126,7 +265,8 @@ Window {
                         anchors.verticalCenter: parent.verticalCenter
                         anchors.left: parent.left
                         anchors.leftMargin: 8
-                        text: model.title + (model.extras?.glyph ? "  " + model.extras.glyph : "")
+                        textFormat: Text.StyledText
+                        text: win.highlight(model.title, model.matches?.title) + (model.extras?.glyph ? "  " + model.extras.glyph : "")
                     }
                     MouseArea {
                         anchors.fill: parent
@@ -135,6 +275,18 @@ Window {
                         }
                         onDoubleClicked: input.accepted()
                     }
+                    // Dropping files or URLs onto an app opens them with it.
+                    DropArea {
+                        anchors.fill: parent
+                        enabled: model.provider_id === "apps"
+                        onDropped: drop => {
+                            if (!drop.hasUrls)
+                                return;
+                            const urls = drop.urls.map(u => u.toString());
+                            engineProxy.execute("launch", "apps", model.key, urls);
+                            drop.accept();
+                        }
+                    }
                 }
             }
 
@@ -146,6 +298,65 @@ Window {
             }
         }
 
+        // Asks for a snippet's arguments, one field per extras.prompts entry.
+        Dialog {
+            id: promptDialog
+            property var item: null
+            property string action: ""
+            property var prompts: []
+            function ask(item, action) {
+                promptDialog.item = item;
+                promptDialog.action = action;
+                promptDialog.prompts = win.listArgs(item.extras.prompts);
+                open();
+            }
+            parent: Overlay.overlay
+            anchors.centerIn: parent
+            modal: true
+            title: item ? item.title : ""
+            standardButtons: Dialog.Ok | Dialog.Cancel
+            ColumnLayout {
+                anchors.fill: parent
+                Repeater {
+                    id: promptFields
+                    model: promptDialog.prompts
+                    TextField {
+                        Layout.fillWidth: true
+                        placeholderText: modelData
+                        focus: index === 0
+                        onAccepted: promptDialog.accept()
+                    }
+                }
+            }
+            onAccepted: {
+                const values = [];
+                for (let i = 0; i < promptFields.count; i++)
+                    values.push(promptFields.itemAt(i).text);
+                win.run(item, action, values);
+            }
+        }
+
+        // Asks before a destructive action, listed in extras.confirm.
+        Dialog {
+            id: confirmDialog
+            property var item: null
+            property string action: ""
+            function ask(item, action) {
+                confirmDialog.item = item;
+                confirmDialog.action = action;
+                open();
+            }
+            parent: Overlay.overlay
+            anchors.centerIn: parent
+            modal: true
+            title: item ? (item.extras?.confirmText ?? item.title) : ""
+            standardButtons: Dialog.Yes | Dialog.No
+            Label {
+                text: "Run “" + confirmDialog.action + "”?"
+            }
+            onAccepted: win.run(item, action, ["confirm"], true)
+        }
+
         // Toast
         Label {
             id: toast
@@ -9,6 +9,18 @@ Item {
     Column {
       anchors.centerIn: parent
       spacing: 8
+      Rectangle {
+        id: swatch
+        property var rgba: {
+          if (!root.previewJson) return null;
+          const env = JSON.parse(root.previewJson);
+          return env.data?.swatch ?? null;
+        }
+        visible: rgba !== null
+        width: 96; height: 64; radius: 6
+        border.color: "#888"
+        color: rgba ? Qt.rgba(rgba.r / 255, rgba.g / 255, rgba.b / 255, rgba.a) : "transparent"
+      }
       Text {
         id: glyph
         font.pixelSize: 64
@@ -26,6 +38,27 @@ Item {
           return env.data?.title ?? "";
         }
       }
+      Text {
+        width: root.width - 16
+        wrapMode: Text.Wrap
+        color: "#888"
+        text: {
+          if (!root.previewJson) return "";
+          const env = JSON.parse(root.previewJson);
+          return env.data?.comment ?? "";
+        }
+      }
+      Text {
+        width: root.width - 16
+        elide: Text.ElideRight
+        font.family: "monospace"
+        font.pixelSize: 11
+        text: {
+          if (!root.previewJson) return "";
+          const env = JSON.parse(root.previewJson);
+          return env.data?.snippet ?? "";
+        }
+      }
     }
   }
 }
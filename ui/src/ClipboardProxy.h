@@ -1,6 +1,7 @@
 #pragma once
 #include <QClipboard>
 #include <QGuiApplication>
+#include <QMimeData>
 #include <QObject>
 
 class ClipboardProxy : public QObject {
@@ -24,6 +25,12 @@ public:
     return m_clipboard->text(QClipboard::Clipboard);
   }
 
+  // Formats on offer, e.g. the hint password managers add to secrets.
+  Q_INVOKABLE QStringList mimeTypes() const {
+    const QMimeData *data = m_clipboard->mimeData(QClipboard::Clipboard);
+    return data ? data->formats() : QStringList();
+  }
+
   Q_INVOKABLE void setText(const QString &text) {
     m_clipboard->setText(text, QClipboard::Clipboard);
     // Emit so QML bindings / tests can react immediately
@@ -86,13 +86,24 @@ void EngineProxy::requestPreview(const QString &key, quint64 epoch) {
                                      QJsonDocument::Compact)));
 }
 
-QString EngineProxy::execute(const QString &action, const QString &providerId, const QString &key) {
-  const QJsonObject env{
-      {"v", "1.0"},
-      {"data",
-       QJsonObject{{"providerId", providerId}, {"action", action}, {"key", key}}}};
+QString EngineProxy::execute(const QString &action, const QString &providerId, const QString &key,
+                             const QStringList &args) {
+  QJsonObject data{{"providerId", providerId}, {"action", action}, {"key", key}};
+  if (!args.isEmpty())
+    data.insert(QStringLiteral("args"), QJsonArray::fromStringList(args));
+
+  const QJsonObject env{{"v", "1.0"}, {"data", data}};
   QDBusReply<QString> reply = m_iface.call(
       "Execute",
       QString::fromUtf8(QJsonDocument(env).toJson(QJsonDocument::Compact)));
   return reply.isValid() ? reply.value() : QString();
 }
+
+bool EngineProxy::recordClipboard(const QString &text, const QStringList &mimeTypes) {
+  const QJsonObject data{{"text", text}, {"mimeTypes", QJsonArray::fromStringList(mimeTypes)}};
+  const QJsonObject env{{"v", "1.0"}, {"data", data}};
+  QDBusReply<bool> reply = m_iface.call(
+      "RecordClipboard",
+      QString::fromUtf8(QJsonDocument(env).toJson(QJsonDocument::Compact)));
+  return reply.isValid() && reply.value();
+}
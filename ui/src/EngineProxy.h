@@ -11,7 +11,9 @@ public:
   Q_INVOKABLE QString resolveCommand(const QString &text);
   Q_INVOKABLE quint64 search(const QString &text, const QString &providerId, quint64 epoch = 0);
   Q_INVOKABLE void requestPreview(const QString &key, quint64 epoch = 0);
-  Q_INVOKABLE QString execute(const QString &action, const QString &providerId, const QString &key);
+  Q_INVOKABLE QString execute(const QString &action, const QString &providerId, const QString &key,
+                              const QStringList &args = {});
+  Q_INVOKABLE bool recordClipboard(const QString &text, const QStringList &mimeTypes);
 
 signals:
   void resultsUpdated(qulonglong epoch, QString providerId, qulonglong token,